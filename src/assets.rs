@@ -0,0 +1,260 @@
+//! Local cache of downloaded images.
+//!
+//! An [`AssetCache`](struct.AssetCache.html) downloads images like `Film::poster`,
+//! `Film::backdrop`, `FilmSummary::poster` and `MemberSummary::avatar` into a local directory,
+//! so that they can be displayed without network access afterwards.
+//!
+//! Images are stored content-addressed, i.e. named by the SHA-256 hash of their bytes, so an
+//! image served under several urls is stored once. For each url, an index entry records the
+//! hash together with the `ETag` and `Last-Modified` headers, which are used to re-download an
+//! image only if it changed.
+//!
+//! The directory has the following layout:
+//!
+//! ```text
+//! objects/<sha256 of image>.<extension>
+//! index/<sha256 of url>.json
+//! ```
+//!
+//! Images are stored as downloaded. Backdrops can be cropped to their focal point with
+//! [`Film::backdrop_crop_rect`](../struct.Film.html#method.backdrop_crop_rect).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::assets::AssetCache;
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let client = letterboxd::Client::new(api_key_pair);
+//!     let cache = AssetCache::new("posters")?;
+//!
+//!     let films = client.films(&letterboxd::FilmsRequest::default()).await?;
+//!     let posters: Vec<_> = films
+//!         .items
+//!         .iter()
+//!         .filter_map(|film| film.poster.as_ref()?.best_for_width(230, 2.0))
+//!         .collect();
+//!     for path in cache.fetch_all(posters).await {
+//!         println!("{}", path?.display());
+//!     }
+//!     Ok::<_, letterboxd::Error>(())
+//! };
+//! ```
+
+use crate::defs::{Image, ImageSize};
+use crate::error::{Error, Result};
+
+use futures::stream::{self, StreamExt};
+use hyper::{client::HttpConnector, header, Body, Request, StatusCode, Uri};
+use hyper_tls::HttpsConnector;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Default number of concurrent downloads.
+const CONCURRENCY: usize = 8;
+
+/// Index entry of a downloaded url.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    url: String,
+    /// File name of the image in the objects directory.
+    object: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Directory of downloaded images.
+pub struct AssetCache {
+    dir: PathBuf,
+    concurrency: usize,
+    http_client: hyper::Client<HttpsConnector<HttpConnector>>,
+}
+
+impl AssetCache {
+    /// Creates a new cache storing images in the given directory.
+    ///
+    /// The directory is created if it does not exist.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join("objects"))?;
+        fs::create_dir_all(dir.join("index"))?;
+        let https = HttpsConnector::new();
+        Ok(Self {
+            dir,
+            concurrency: CONCURRENCY,
+            http_client: hyper::Client::builder().build::<_, Body>(https),
+        })
+    }
+
+    /// Sets the maximum number of concurrent downloads of `fetch_all` (default is 8).
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Returns the directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of a previously downloaded image by url, without network access.
+    pub fn get(&self, url: &str) -> Option<PathBuf> {
+        let entry = self.read_index(url).ok()?;
+        let path = self.dir.join("objects").join(entry.object);
+        if path.exists() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the path of the best size of an image for a width in CSS pixels, if it was
+    /// previously downloaded.
+    ///
+    /// See [`Image::best_for_width`](../struct.Image.html#method.best_for_width).
+    pub fn get_image(
+        &self,
+        image: &Image,
+        width: usize,
+        device_pixel_ratio: f32,
+    ) -> Option<PathBuf> {
+        self.get(&image.best_for_width(width, device_pixel_ratio)?.url)
+    }
+
+    /// Downloads an image, unless the cached image is still current, and returns its path.
+    pub async fn fetch(&self, size: &ImageSize) -> Result<PathBuf> {
+        self.fetch_url(&size.url).await
+    }
+
+    /// Downloads the best size of an image for a width in CSS pixels and returns its path.
+    ///
+    /// Returns `None` if the image has no sizes.
+    pub async fn fetch_image(
+        &self,
+        image: &Image,
+        width: usize,
+        device_pixel_ratio: f32,
+    ) -> Result<Option<PathBuf>> {
+        match image.best_for_width(width, device_pixel_ratio) {
+            Some(size) => Ok(Some(self.fetch(size).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Downloads images concurrently and returns their paths in the same order.
+    pub async fn fetch_all<'a, I>(&self, sizes: I) -> Vec<Result<PathBuf>>
+    where
+        I: IntoIterator<Item = &'a ImageSize>,
+    {
+        stream::iter(sizes)
+            .map(|size| self.fetch(size))
+            .buffered(self.concurrency)
+            .collect()
+            .await
+    }
+
+    /// Downloads an image by url, unless the cached image is still current, and returns its
+    /// path.
+    pub async fn fetch_url(&self, url: &str) -> Result<PathBuf> {
+        let uri: Uri = url.parse()?;
+        let cached = self.read_index(url).ok().filter(|entry| {
+            let path = self.dir.join("objects").join(&entry.object);
+            path.exists()
+        });
+
+        let mut req = Request::get(uri.clone());
+        if let Some(entry) = cached.as_ref() {
+            if let Some(etag) = entry.etag.as_ref() {
+                req = req.header(header::IF_NONE_MATCH, etag.as_str());
+            }
+            if let Some(last_modified) = entry.last_modified.as_ref() {
+                req = req.header(header::IF_MODIFIED_SINCE, last_modified.as_str());
+            }
+        }
+        let req = req.body(Body::empty()).expect("invalid request");
+        let resp = self.http_client.request(req).await?;
+        let status = resp.status();
+        let header_value = |name| {
+            resp.headers()
+                .get(name)
+                .and_then(|value: &header::HeaderValue| value.to_str().ok())
+                .map(String::from)
+        };
+        let etag = header_value(header::ETAG);
+        let last_modified = header_value(header::LAST_MODIFIED);
+        let bytes = hyper::body::to_bytes(resp.into_body()).await?;
+
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                return Ok(self.dir.join("objects").join(entry.object));
+            }
+        }
+        if !status.is_success() {
+            let body = String::from_utf8_lossy(&bytes).into_owned();
+            return Err(Error::server_error(status, body, uri));
+        }
+
+        let object = format!("{}{}", hex::encode(Sha256::digest(&bytes)), extension(url));
+        let path = self.dir.join("objects").join(&object);
+        if !path.exists() {
+            write_atomic(&path, &bytes)?;
+        }
+        let entry = IndexEntry {
+            url: url.to_string(),
+            object,
+            etag,
+            last_modified,
+        };
+        write_atomic(&self.index_path(url), &serde_json::to_vec(&entry)?)?;
+        Ok(path)
+    }
+
+    fn index_path(&self, url: &str) -> PathBuf {
+        let name = format!("{}.json", hex::encode(Sha256::digest(url.as_bytes())));
+        self.dir.join("index").join(name)
+    }
+
+    fn read_index(&self, url: &str) -> Result<IndexEntry> {
+        let bytes = fs::read(self.index_path(url))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+impl fmt::Debug for AssetCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AssetCache")
+            .field("dir", &self.dir)
+            .field("concurrency", &self.concurrency)
+            .finish()
+    }
+}
+
+/// Returns the file extension of the path of a url including the dot, if any.
+fn extension(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    let name = path.rsplit('/').next().unwrap_or("");
+    match name.rfind('.') {
+        Some(index)
+            if name.len() - index <= 5
+                && name[index + 1..].chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            name[index..].to_ascii_lowercase()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Writes a file by renaming a temporary file, so that readers never see partial files.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension(format!("tmp{}", uuid::Uuid::new_v4().to_simple()));
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
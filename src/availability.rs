@@ -0,0 +1,283 @@
+//! Availability of the films in a member's watchlist on streaming services.
+//!
+//! A [`Snapshot`](struct.Snapshot.html) records which watchlist films are offered by which
+//! service. It is fetched either with the availability of each film in a country, or with the
+//! watchlist filtered by each of the services listed by `Client::film_services`, which uses the
+//! regions set by the authenticated member.
+//!
+//! Snapshots are saved as JSON. Comparing a new snapshot with the previous one results in a
+//! [`Report`](struct.Report.html) of the films which became available on a service since, e.g.
+//! for a weekly "now streaming from your watchlist" message.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::availability::Snapshot;
+//! use letterboxd::Country;
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let client = letterboxd::Client::new(api_key_pair);
+//!
+//!     let previous = Snapshot::load("watchlist-deu.json")?;
+//!     let snapshot = Snapshot::fetch(&client, "2bbs", Country::DEU).await?;
+//!     let report = snapshot.newly_available(&previous);
+//!     if !report.is_empty() {
+//!         print!("Now streaming from your watchlist:\n{}", report);
+//!     }
+//!     snapshot.save("watchlist-deu.json")
+//! };
+//! ```
+
+use crate::client::Client;
+use crate::defs::{
+    Country, FilmAvailabilityResponse, FilmRelationshipType, FilmSummary, FilmsRequest, Service,
+    WatchlistRequest,
+};
+use crate::error::Result;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Page size used when fetching the watchlist.
+const PER_PAGE: usize = 100;
+/// Number of concurrent requests when fetching the availability of the films.
+const CONCURRENCY: usize = 8;
+
+/// A service offering a film.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Offer {
+    /// The name of the service, like `Netflix`.
+    pub service: String,
+    /// The URL of the film on the service, if known.
+    pub url: Option<String>,
+}
+
+/// A film with the services offering it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailableFilm {
+    /// The LID of the film.
+    pub id: String,
+    /// The name of the film.
+    pub name: String,
+    /// The year in which the film was first released.
+    pub release_year: Option<u16>,
+    /// The URL of the film on Letterboxd.
+    pub link: Option<String>,
+    /// The services offering the film.
+    pub offers: Vec<Offer>,
+}
+
+/// The watchlist films offered by services at a point in time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The country of the offers, `None` if it depends on the member's settings.
+    pub country: Option<Country>,
+    /// The films offered by at least one service, in the order of the watchlist.
+    pub films: Vec<AvailableFilm>,
+}
+
+impl Snapshot {
+    /// Creates an empty snapshot.
+    pub fn new(country: Option<Country>) -> Self {
+        Self {
+            country,
+            films: Vec::new(),
+        }
+    }
+
+    /// Fetches the watchlist of a member and the availability of its films in a country.
+    pub async fn fetch(client: &Client, member: &str, country: Country) -> Result<Self> {
+        let watchlist = fetch_watchlist(client, member).await?;
+        let availability: Vec<FilmAvailabilityResponse> = stream::iter(&watchlist)
+            .map(|film| client.film_availability(&film.id))
+            .buffered(CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        let mut snapshot = Self::new(Some(country));
+        for (film, availability) in watchlist.iter().zip(availability) {
+            let offers = availability.items.unwrap_or_default();
+            for offer in offers.iter().filter(|offer| offer.country == country) {
+                snapshot.add(film, &offer.display_name, Some(&offer.url));
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Fetches the films in the watchlist of a member offered by each of the given services, as
+    /// returned by `Client::film_services`.
+    ///
+    /// The offers are in the regions set by the authenticated member, and have no URLs.
+    pub async fn fetch_by_service(
+        client: &Client,
+        member: &str,
+        services: &[Service],
+    ) -> Result<Self> {
+        let mut snapshot = Self::new(None);
+        for service in services {
+            let mut req = FilmsRequest {
+                per_page: Some(PER_PAGE),
+                service: Some(service.id.clone()),
+                member: Some(member.to_string()),
+                member_relationship: Some(FilmRelationshipType::InWatchlist),
+                ..Default::default()
+            };
+            loop {
+                let resp = client.films(&req).await?;
+                for film in &resp.items {
+                    snapshot.add(film, &service.name, None);
+                }
+                match resp.next {
+                    Some(next) => req.cursor = Some(next),
+                    None => break,
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Loads a snapshot saved by [`save`](#method.save).
+    ///
+    /// A missing file results in an empty snapshot, so that every available film is reported
+    /// the first time.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Saves the snapshot as JSON.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Records that a film is offered by a service.
+    pub fn add(&mut self, film: &FilmSummary, service: &str, url: Option<&str>) {
+        let index = match self.films.iter().position(|f| f.id == film.id) {
+            Some(index) => index,
+            None => {
+                self.films.push(AvailableFilm {
+                    id: film.id.clone(),
+                    name: film.name.clone(),
+                    release_year: film.release_year,
+                    link: film.letterboxd_url().map(String::from),
+                    offers: Vec::new(),
+                });
+                self.films.len() - 1
+            }
+        };
+        let offers = &mut self.films[index].offers;
+        if offers.iter().all(|offer| offer.service != service) {
+            offers.push(Offer {
+                service: service.to_string(),
+                url: url.map(String::from),
+            });
+        }
+    }
+
+    /// Returns the films with the offers which are not in the previous snapshot.
+    ///
+    /// Films added to the watchlist since the previous snapshot are reported with all their
+    /// offers. The report is empty if the previous snapshot contains offers of another country,
+    /// since they are not comparable.
+    pub fn newly_available(&self, previous: &Snapshot) -> Report {
+        if previous.country != self.country && !previous.films.is_empty() {
+            return Report {
+                country: self.country,
+                films: Vec::new(),
+            };
+        }
+        let known: HashSet<(&str, &str)> = previous
+            .films
+            .iter()
+            .flat_map(|film| {
+                film.offers
+                    .iter()
+                    .map(move |offer| (film.id.as_str(), offer.service.as_str()))
+            })
+            .collect();
+        let films = self
+            .films
+            .iter()
+            .filter_map(|film| {
+                let offers: Vec<Offer> = film
+                    .offers
+                    .iter()
+                    .filter(|offer| !known.contains(&(film.id.as_str(), offer.service.as_str())))
+                    .cloned()
+                    .collect();
+                if offers.is_empty() {
+                    None
+                } else {
+                    Some(AvailableFilm {
+                        offers,
+                        ..film.clone()
+                    })
+                }
+            })
+            .collect();
+        Report {
+            country: self.country,
+            films,
+        }
+    }
+}
+
+/// The films which became available on a service since a previous snapshot.
+///
+/// Displays as one line per film, like `Parasite (2019): Netflix, MUBI`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// The country of the offers, `None` if it depends on the member's settings.
+    pub country: Option<Country>,
+    /// The films with their new offers, in the order of the watchlist.
+    pub films: Vec<AvailableFilm>,
+}
+
+impl Report {
+    /// Returns whether no film became available.
+    pub fn is_empty(&self) -> bool {
+        self.films.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for film in &self.films {
+            write!(f, "{}", film.name)?;
+            if let Some(release_year) = film.release_year {
+                write!(f, " ({})", release_year)?;
+            }
+            let services: Vec<&str> = film.offers.iter().map(|o| o.service.as_str()).collect();
+            writeln!(f, ": {}", services.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+async fn fetch_watchlist(client: &Client, member: &str) -> Result<Vec<FilmSummary>> {
+    let mut films = Vec::new();
+    let mut req = WatchlistRequest {
+        per_page: Some(PER_PAGE),
+        ..Default::default()
+    };
+    loop {
+        let resp = client.member_watchlist(member, &req).await?;
+        films.extend(resp.items);
+        match resp.next {
+            Some(next) => req.cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(films)
+}
@@ -0,0 +1,460 @@
+//! Command-line client for everyday Letterboxd account operations.
+//!
+//! API key and secret are taken from the environment variables `LETTERBOXD_API_KEY` and
+//! `LETTERBOXD_API_SECRET`. Run `letterboxd login` once to authenticate with
+//! `LETTERBOXD_USERNAME` and `LETTERBOXD_PASSWORD`; the token is cached and used by all
+//! following invocations. The token is stored in `$LETTERBOXD_TOKEN_CACHE`, or by default in
+//! `$XDG_CACHE_HOME/letterboxd/token.json` resp. `~/.cache/letterboxd/token.json`.
+
+mod output;
+
+use output::{Format, Table};
+
+use clap::{Args, Parser, Subcommand};
+use letterboxd::token_store::FileTokenStore;
+use letterboxd::{AbstractSearchItem, ApiKeyPair, Client, FilmSummary};
+use tokio::runtime::Runtime;
+
+use std::path::PathBuf;
+use std::process;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "letterboxd",
+    version,
+    about = "Command-line client for the Letterboxd API"
+)]
+struct Opt {
+    /// Output format of the results.
+    #[arg(long, short, value_enum, global = true, default_value = "table")]
+    format: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Authenticates with LETTERBOXD_USERNAME/LETTERBOXD_PASSWORD and caches the token.
+    Login,
+    /// Removes the cached token.
+    Logout,
+    /// Searches for films, lists, members, contributors, reviews and tags.
+    Search {
+        /// The word, partial word or phrase to search for.
+        input: String,
+        /// The number of results (maximum is 100).
+        #[arg(long)]
+        per_page: Option<usize>,
+    },
+    /// Shows details of a film.
+    Film(FilmArgs),
+    /// Shows and manages lists.
+    #[command(subcommand)]
+    List(ListCommand),
+    /// Rates a film.
+    Rate {
+        /// The LID of the film.
+        film: String,
+        /// The rating between 0.5 and 5.0, with increments of 0.5.
+        stars: f32,
+    },
+    /// Shows the watchlist of a member.
+    Watchlist {
+        /// The LID of the member.
+        member: String,
+    },
+    /// Logs a film in the diary and/or reviews it.
+    Log(LogArgs),
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct FilmArgs {
+    #[command(subcommand)]
+    command: Option<FilmCommand>,
+    /// The LID of the film.
+    #[arg(required = true)]
+    id: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum FilmCommand {
+    /// Shows statistics of a film.
+    Stats {
+        /// The LID of the film.
+        id: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ListCommand {
+    /// Shows a list and its entries.
+    Show {
+        /// The LID of the list.
+        id: String,
+    },
+    /// Creates a list.
+    Create {
+        /// The name of the list.
+        name: String,
+        /// The LIDs of the films in the list.
+        films: Vec<String>,
+        /// Publishes the list for other members to see.
+        #[arg(long)]
+        published: bool,
+        /// Makes this a ranked list.
+        #[arg(long)]
+        ranked: bool,
+        /// The description of the list in LBML.
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Updates a list.
+    Update {
+        /// The LID of the list.
+        id: String,
+        /// The new name of the list.
+        #[arg(long)]
+        name: Option<String>,
+        /// The new description of the list in LBML.
+        #[arg(long)]
+        description: Option<String>,
+        /// LIDs of films to append to the list.
+        #[arg(long)]
+        add: Vec<String>,
+        /// LIDs of films to remove from the list.
+        #[arg(long)]
+        remove: Vec<String>,
+    },
+    /// Deletes a list.
+    Delete {
+        /// The LID of the list.
+        id: String,
+    },
+}
+
+#[derive(Debug, Args)]
+struct LogArgs {
+    /// The LID of the film.
+    film: String,
+    /// The date the film was watched, i.e. YYYY-MM-DD.
+    #[arg(long)]
+    date: Option<String>,
+    /// The rating between 0.5 and 5.0, with increments of 0.5.
+    #[arg(long)]
+    rating: Option<f32>,
+    /// Marks the film as watched before.
+    #[arg(long)]
+    rewatch: bool,
+    /// Likes the film.
+    #[arg(long)]
+    like: bool,
+    /// The review text in LBML.
+    #[arg(long)]
+    review: Option<String>,
+    /// Marks the review as containing spoilers.
+    #[arg(long)]
+    spoilers: bool,
+    /// Tags for the log entry.
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+}
+
+const USAGE: &str = "The following environment variables must be set:
+  LETTERBOXD_API_KEY       letterboxd api key
+  LETTERBOXD_API_SECRET    letterboxd api secret";
+
+fn main() {
+    let opt = Opt::parse();
+    let api_key_pair = match ApiKeyPair::from_env() {
+        Some(api_key_pair) => api_key_pair,
+        None => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut rt = Runtime::new().expect("failed to create runtime");
+    if let Err(e) = rt.block_on(run(api_key_pair, opt)) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+async fn run(api_key_pair: ApiKeyPair, opt: Opt) -> letterboxd::Result<()> {
+    let format = opt.format;
+    let path = std::env::var_os("LETTERBOXD_TOKEN_CACHE")
+        .map(PathBuf::from)
+        .unwrap_or_else(FileTokenStore::default_path);
+    let mut client = Client::from_store(api_key_pair, FileTokenStore::new(path))?;
+    match opt.command {
+        Command::Login => return login(&mut client).await,
+        Command::Logout => return client.logout(),
+        _ => (),
+    }
+
+    match opt.command {
+        Command::Login | Command::Logout => unreachable!(),
+        Command::Search { input, per_page } => {
+            let req = letterboxd::SearchRequest {
+                per_page,
+                ..letterboxd::SearchRequest::new(input)
+            };
+            let resp = client.search(&req).await?;
+            let mut table = Table::new(&["type", "id", "name"]);
+            for item in &resp.items {
+                table.row(search_item_row(item));
+            }
+            output::print(format, &resp, &table)
+        }
+        Command::Film(FilmArgs {
+            command: Some(FilmCommand::Stats { id }),
+            ..
+        }) => {
+            let stats = client.film_statistics(&id).await?;
+            let mut table = Table::new(&["field", "value"]);
+            table.row(vec![String::from("rating"), option(stats.rating)]);
+            table.row(vec!["watches", &stats.counts.watches.to_string()]);
+            table.row(vec!["likes", &stats.counts.likes.to_string()]);
+            table.row(vec!["ratings", &stats.counts.ratings.to_string()]);
+            table.row(vec!["fans", &stats.counts.fans.to_string()]);
+            table.row(vec!["lists", &stats.counts.lists.to_string()]);
+            table.row(vec!["reviews", &stats.counts.reviews.to_string()]);
+            output::print(format, &stats, &table)
+        }
+        Command::Film(FilmArgs { id, .. }) => {
+            let id = id.expect("clap requires film id");
+            let film = client.film(&id).await?;
+            let genres: Vec<&str> = film.genres.iter().map(|g| g.name.as_str()).collect();
+            let mut table = Table::new(&["field", "value"]);
+            table.row(vec!["id", &film.id]);
+            table.row(vec!["name", &film.name]);
+            table.row(vec!["year", &film.release_year.to_string()]);
+            table.row(vec!["runtime", &format!("{} min", film.run_time)]);
+            table.row(vec!["genres", &genres.join(", ")]);
+            table.row(vec!["tagline", &film.tagline]);
+            output::print(format, &film, &table)
+        }
+        Command::List(ListCommand::Show { id }) => {
+            let list = client.list(&id).await?;
+            let mut entries = Vec::new();
+            let mut req = letterboxd::ListEntriesRequest {
+                per_page: Some(100),
+                ..Default::default()
+            };
+            loop {
+                let resp = client.list_entries(&id, &req).await?;
+                entries.extend(resp.items);
+                match resp.next {
+                    Some(next) => req.cursor = Some(next),
+                    None => break,
+                }
+            }
+            let mut table = Table::new(&["rank", "id", "name", "year"]);
+            for (index, entry) in entries.iter().enumerate() {
+                let mut row = vec![entry.rank.unwrap_or(index + 1).to_string()];
+                row.extend(film_row(&entry.film));
+                table.row(row);
+            }
+            #[derive(serde::Serialize)]
+            struct ListWithEntries {
+                list: letterboxd::List,
+                entries: Vec<letterboxd::ListEntry>,
+            }
+            output::print(format, &ListWithEntries { list, entries }, &table)
+        }
+        Command::List(ListCommand::Create {
+            name,
+            films,
+            published,
+            ranked,
+            description,
+        }) => {
+            let req = letterboxd::ListCreationRequest {
+                published,
+                ranked,
+                description,
+                entries: films
+                    .into_iter()
+                    .map(letterboxd::ListCreateEntry::new)
+                    .collect(),
+                ..letterboxd::ListCreationRequest::new(name)
+            };
+            let resp = client.create_list(&req).await?;
+            let mut table = Table::new(&["id", "name", "films"]);
+            table.row(vec![
+                resp.data.id.clone(),
+                resp.data.name.clone(),
+                resp.data.film_count.to_string(),
+            ]);
+            output::print(format, &resp, &table)
+        }
+        Command::List(ListCommand::Update {
+            id,
+            name,
+            description,
+            add,
+            remove,
+        }) => {
+            let name = match name {
+                Some(name) => name,
+                None => client.list(&id).await?.name,
+            };
+            let req = letterboxd::ListUpdateRequest {
+                description,
+                films_to_remove: remove,
+                entries: add
+                    .into_iter()
+                    .map(letterboxd::ListUpdateEntry::new)
+                    .collect(),
+                ..letterboxd::ListUpdateRequest::new(name)
+            };
+            let resp = client.update_list(&id, &req).await?;
+            let mut table = Table::new(&["id", "name", "films"]);
+            table.row(vec![
+                resp.data.id.clone(),
+                resp.data.name.clone(),
+                resp.data.film_count.to_string(),
+            ]);
+            output::print(format, &resp, &table)
+        }
+        Command::List(ListCommand::Delete { id }) => client.delete_list(&id).await,
+        Command::Rate { film, stars } => {
+            let req = letterboxd::FilmRelationshipUpdateRequest {
+                rating: Some(stars),
+                ..Default::default()
+            };
+            let resp = client.update_film_relationship(&film, &req).await?;
+            let mut table = Table::new(&["film", "watched", "liked", "rating"]);
+            table.row(vec![
+                film,
+                resp.data.watched.to_string(),
+                resp.data.liked.to_string(),
+                option(resp.data.rating),
+            ]);
+            output::print(format, &resp, &table)
+        }
+        Command::Watchlist { member } => {
+            let mut films = Vec::new();
+            let mut req = letterboxd::WatchlistRequest {
+                per_page: Some(100),
+                ..Default::default()
+            };
+            loop {
+                let resp = client.member_watchlist(&member, &req).await?;
+                films.extend(resp.items);
+                match resp.next {
+                    Some(next) => req.cursor = Some(next),
+                    None => break,
+                }
+            }
+            let mut table = Table::new(&["id", "name", "year"]);
+            for film in &films {
+                table.row(film_row(film));
+            }
+            output::print(format, &films, &table)
+        }
+        Command::Log(LogArgs {
+            film,
+            date,
+            rating,
+            rewatch,
+            like,
+            review,
+            spoilers,
+            tags,
+        }) => {
+            let req = letterboxd::LogEntryCreationRequest {
+                diary_details: date.map(|diary_date| {
+                    letterboxd::LogEntryCreationRequestDiaryDetails {
+                        diary_date,
+                        rewatch,
+                    }
+                }),
+                review: review.map(|text| letterboxd::LogEntryCreationRequestReview {
+                    text,
+                    contains_spoilers: spoilers,
+                    share: Vec::new(),
+                }),
+                tags,
+                rating,
+                like: Some(like).filter(|&like| like),
+                ..letterboxd::LogEntryCreationRequest::new(film)
+            };
+            let entry = client.create_log_entry(&req).await?;
+            let mut table = Table::new(&["id", "film", "date", "rating"]);
+            table.row(vec![
+                entry.id.clone(),
+                entry.film.name.clone(),
+                entry
+                    .diary_details
+                    .as_ref()
+                    .map(|d| d.diary_date.clone())
+                    .unwrap_or_default(),
+                option(entry.rating),
+            ]);
+            output::print(format, &entry, &table)
+        }
+    }
+}
+
+async fn login(client: &mut Client) -> letterboxd::Result<()> {
+    let (username, password) = match (
+        std::env::var("LETTERBOXD_USERNAME"),
+        std::env::var("LETTERBOXD_PASSWORD"),
+    ) {
+        (Ok(username), Ok(password)) => (username, password),
+        _ => {
+            eprintln!("LETTERBOXD_USERNAME and LETTERBOXD_PASSWORD must be set to login");
+            process::exit(2);
+        }
+    };
+    client.login(&username, &password).await
+}
+
+fn search_item_row(item: &AbstractSearchItem) -> Vec<String> {
+    match item {
+        AbstractSearchItem::ContributorSearchItem { contributor, .. } => vec![
+            String::from("contributor"),
+            contributor.id.clone(),
+            contributor.name.clone(),
+        ],
+        AbstractSearchItem::FilmSearchItem { film, .. } => {
+            let name = match film.release_year {
+                Some(year) => format!("{} ({})", film.name, year),
+                None => film.name.clone(),
+            };
+            vec![String::from("film"), film.id.clone(), name]
+        }
+        AbstractSearchItem::ListSearchItem { list, .. } => {
+            vec![String::from("list"), list.id.clone(), list.name.clone()]
+        }
+        AbstractSearchItem::MemberSearchItem { member, .. } => vec![
+            String::from("member"),
+            member.id.clone(),
+            member.display_name.clone(),
+        ],
+        AbstractSearchItem::ReviewSearchItem { review, .. } => {
+            vec![
+                String::from("review"),
+                review.id.clone(),
+                review.name.clone(),
+            ]
+        }
+        AbstractSearchItem::TagSearchItem { tag, .. } => {
+            vec![String::from("tag"), tag.clone(), tag.clone()]
+        }
+    }
+}
+
+fn film_row(film: &FilmSummary) -> Vec<String> {
+    vec![
+        film.id.clone(),
+        film.name.clone(),
+        option(film.release_year),
+    ]
+}
+
+fn option<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,144 @@
+//! Formatting of command results.
+
+use serde::Serialize;
+
+use std::io::{self, Write};
+
+/// Output format of the command results.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// Aligned columns for humans.
+    Table,
+    /// The full API response as JSON.
+    Json,
+    /// Comma separated values with a header row.
+    Csv,
+}
+
+/// Tabular view of a command result.
+#[derive(Debug, Default)]
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row<I, S>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.rows
+            .push(row.into_iter().map(|s| s.to_string()).collect());
+    }
+}
+
+/// Prints a command result in the given format.
+///
+/// JSON output contains the complete value, while table and CSV output only contain the columns
+/// of the table.
+pub fn print<T: Serialize>(format: Format, value: &T, table: &Table) -> letterboxd::Result<()> {
+    let stdout = io::stdout();
+    write(stdout.lock(), format, value, table)
+}
+
+/// Writes a command result in the given format.
+fn write<W, T>(mut writer: W, format: Format, value: &T, table: &Table) -> letterboxd::Result<()>
+where
+    W: Write,
+    T: Serialize,
+{
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut writer, value)?;
+            writeln!(writer)?;
+        }
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(writer);
+            wtr.write_record(&table.headers)?;
+            for row in &table.rows {
+                wtr.write_record(row)?;
+            }
+            wtr.flush()?;
+        }
+        Format::Table => {
+            let mut widths: Vec<usize> = table.headers.iter().map(|h| h.chars().count()).collect();
+            for row in &table.rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+            let headers: Vec<String> = table.headers.iter().map(|h| h.to_uppercase()).collect();
+            write_row(&mut writer, &widths, &headers)?;
+            for row in &table.rows {
+                write_row(&mut writer, &widths, row)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_row<W: Write>(writer: &mut W, widths: &[usize], row: &[String]) -> io::Result<()> {
+    let cells: Vec<String> = row
+        .iter()
+        .zip(widths)
+        .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+        .collect();
+    writeln!(writer, "{}", cells.join("  ").trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(&["id", "name", "year"]);
+        table.row(vec!["2a9q", "Fight Club", "1999"]);
+        table.row(vec!["hTha", "Parasite", ""]);
+        table.row(vec!["1Kxk", "Сталкер", "1979"]);
+        table
+    }
+
+    fn output(format: Format, value: &serde_json::Value) -> String {
+        let mut out = Vec::new();
+        write(&mut out, format, value, &table()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn table_columns_are_aligned() {
+        assert_eq!(
+            output(Format::Table, &serde_json::Value::Null),
+            "ID    NAME        YEAR\n\
+             2a9q  Fight Club  1999\n\
+             hTha  Parasite\n\
+             1Kxk  Сталкер     1979\n"
+        );
+    }
+
+    #[test]
+    fn csv_has_header_row() {
+        assert_eq!(
+            output(Format::Csv, &serde_json::Value::Null),
+            "id,name,year\n2a9q,Fight Club,1999\nhTha,Parasite,\n1Kxk,Сталкер,1979\n"
+        );
+    }
+
+    #[test]
+    fn json_contains_complete_value() {
+        let value = serde_json::json!({ "items": [{ "id": "2a9q", "runTime": 139 }] });
+        let out = output(Format::Json, &value);
+        assert!(out.ends_with("}\n"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&out).unwrap(),
+            value
+        );
+    }
+}
@@ -0,0 +1,448 @@
+//! Builders and validation of requests with constrained parameters.
+//!
+//! The API rejects requests with invalid combinations of parameters with an opaque
+//! `400 Bad Request`. The builders of this module check the documented constraints and return
+//! a descriptive error instead:
+//!
+//! * `per_page` is between 1 and 100,
+//! * `decade` is the starting year of a decade, i.e. ends in 0,
+//! * `member_relationship` and `include_friends` require `member`,
+//! * `tagger` requires `tag_code`, and `include_tagger_friends` requires `tagger`,
+//! * for lists, `tag_code` requires `member` with the relationship `Owner`.
+//!
+//! The same checks are done by the client before sending a request, so requests constructed
+//! directly fail before any network call, too.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use letterboxd::{FilmRelationshipType, FilmsRequest};
+//!
+//! let request = FilmsRequest::builder()
+//!     .decade(1990)
+//!     .member("11Ht")
+//!     .member_relationship(FilmRelationshipType::Watched)
+//!     .per_page(100)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(request.decade, Some(1990));
+//!
+//! let err = FilmsRequest::builder().decade(1994).build().unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "invalid request: decade must be the starting year of a decade, got 1994"
+//! );
+//! ```
+
+use crate::defs::{
+    Cursor, FilmRelationshipType, FilmRequestSort, FilmStatus, FilmsRequest, IncludeFriends,
+    ListEntriesRequest, ListEntriesRequestSort, ListMemberRelationship, ListRequestFilter,
+    ListRequestSort, ListStatus, ListsRequest,
+};
+use crate::error::{Error, Result};
+
+/// Maximum number of items per page.
+pub const MAX_PER_PAGE: usize = 100;
+
+fn validate_per_page(per_page: Option<usize>) -> Result<()> {
+    match per_page {
+        Some(per_page) if per_page == 0 || per_page > MAX_PER_PAGE => {
+            Err(Error::invalid_request(format!(
+                "per_page must be between 1 and {}, got {}",
+                MAX_PER_PAGE, per_page
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_decade(decade: Option<u16>) -> Result<()> {
+    match decade {
+        Some(decade) if decade % 10 != 0 => Err(Error::invalid_request(format!(
+            "decade must be the starting year of a decade, got {}",
+            decade
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a parameter is only used together with the parameter it requires.
+fn validate_requires(used: bool, name: &str, required: bool, required_name: &str) -> Result<()> {
+    if used && !required {
+        return Err(Error::invalid_request(format!(
+            "{} must be used in conjunction with {}",
+            name, required_name
+        )));
+    }
+    Ok(())
+}
+
+/// Checks the parameters which require `member`, `tag_code` or `tagger`, which are shared by
+/// the requests of films and of list entries. Each argument is true if the parameter is used.
+fn validate_member_and_tagger(
+    member: bool,
+    member_relationship: bool,
+    include_friends: bool,
+    tag_code: bool,
+    tagger: bool,
+    include_tagger_friends: bool,
+) -> Result<()> {
+    validate_requires(member_relationship, "member_relationship", member, "member")?;
+    validate_requires(include_friends, "include_friends", member, "member")?;
+    validate_requires(tagger, "tagger", tag_code, "tag_code")?;
+    validate_requires(
+        include_tagger_friends,
+        "include_tagger_friends",
+        tagger,
+        "tagger",
+    )
+}
+
+impl FilmsRequest {
+    /// Creates a builder of a request.
+    pub fn builder() -> FilmsRequestBuilder {
+        FilmsRequestBuilder::default()
+    }
+
+    /// Checks the documented constraints of the parameters.
+    pub fn validate(&self) -> Result<()> {
+        validate_per_page(self.per_page)?;
+        validate_decade(self.decade)?;
+        validate_member_and_tagger(
+            self.member.is_some(),
+            self.member_relationship.is_some(),
+            self.include_friends.is_some(),
+            self.tag_code.is_some(),
+            self.tagger.is_some(),
+            self.include_tagger_friends.is_some(),
+        )
+    }
+}
+
+impl ListEntriesRequest {
+    /// Creates a builder of a request.
+    pub fn builder() -> ListEntriesRequestBuilder {
+        ListEntriesRequestBuilder::default()
+    }
+
+    /// Checks the documented constraints of the parameters.
+    pub fn validate(&self) -> Result<()> {
+        validate_per_page(self.per_page)?;
+        validate_decade(self.decade)?;
+        validate_member_and_tagger(
+            self.member.is_some(),
+            self.member_relationship.is_some(),
+            self.include_friends.is_some(),
+            self.tag_code.is_some(),
+            self.tagger.is_some(),
+            self.include_tagger_friends.is_some(),
+        )
+    }
+}
+
+impl ListsRequest {
+    /// Creates a builder of a request.
+    pub fn builder() -> ListsRequestBuilder {
+        ListsRequestBuilder::default()
+    }
+
+    /// Checks the documented constraints of the parameters.
+    pub fn validate(&self) -> Result<()> {
+        validate_per_page(self.per_page)?;
+        let member = self.member.is_some();
+        let relationship = self.member_relationship.is_some();
+        validate_requires(relationship, "member_relationship", member, "member")?;
+        let include_friends = self.include_friends.is_some();
+        validate_requires(include_friends, "include_friends", member, "member")?;
+        // the member relationship defaults to `Owner`
+        let owner = member
+            && matches!(
+                self.member_relationship,
+                None | Some(ListMemberRelationship::Owner)
+            );
+        let tag_code = self.tag_code.is_some();
+        validate_requires(
+            tag_code,
+            "tag_code",
+            owner,
+            "member and member_relationship Owner",
+        )
+    }
+}
+
+/// Builder of a [`FilmsRequest`](../struct.FilmsRequest.html).
+#[derive(Debug, Clone, Default)]
+pub struct FilmsRequestBuilder {
+    request: FilmsRequest,
+}
+
+impl FilmsRequestBuilder {
+    /// Sets the pagination cursor.
+    pub fn cursor<S: Into<Cursor>>(mut self, cursor: S) -> Self {
+        self.request.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Sets the number of items per page (at most 100).
+    pub fn per_page(mut self, per_page: usize) -> Self {
+        self.request.per_page = Some(per_page);
+        self
+    }
+
+    /// Sets the order of the films.
+    pub fn sort(mut self, sort: FilmRequestSort) -> Self {
+        self.request.sort = Some(sort);
+        self
+    }
+
+    /// Limits the films to a genre by LID.
+    pub fn genre<S: Into<String>>(mut self, genre: S) -> Self {
+        self.request.genre = Some(genre.into());
+        self
+    }
+
+    /// Limits the films to a decade by its starting year (must end in 0).
+    pub fn decade(mut self, decade: u16) -> Self {
+        self.request.decade = Some(decade);
+        self
+    }
+
+    /// Limits the films to a release year.
+    pub fn year(mut self, year: u16) -> Self {
+        self.request.year = Some(year);
+        self
+    }
+
+    /// Limits the films to those available from a service by ID.
+    pub fn service<S: Into<String>>(mut self, service: S) -> Self {
+        self.request.service = Some(service.into());
+        self
+    }
+
+    /// Adds a status the films must have.
+    pub fn where_film_status(mut self, status: FilmStatus) -> Self {
+        self.request.where_film_status.push(status);
+        self
+    }
+
+    /// Limits the films according to their relationship with a member by LID.
+    pub fn member<S: Into<String>>(mut self, member: S) -> Self {
+        self.request.member = Some(member.into());
+        self
+    }
+
+    /// Sets the relationship with the member (requires `member`).
+    pub fn member_relationship(mut self, relationship: FilmRelationshipType) -> Self {
+        self.request.member_relationship = Some(relationship);
+        self
+    }
+
+    /// Includes the films of the member's friends (requires `member`).
+    pub fn include_friends(mut self, include_friends: IncludeFriends) -> Self {
+        self.request.include_friends = Some(include_friends);
+        self
+    }
+
+    /// Limits the films to those tagged with a tag code.
+    pub fn tag_code<S: Into<String>>(mut self, tag_code: S) -> Self {
+        self.request.tag_code = Some(tag_code.into());
+        self
+    }
+
+    /// Focuses the tag filter on a member by LID (requires `tag_code`).
+    pub fn tagger<S: Into<String>>(mut self, tagger: S) -> Self {
+        self.request.tagger = Some(tagger.into());
+        self
+    }
+
+    /// Includes the tags of the tagger's friends (requires `tagger`).
+    pub fn include_tagger_friends(mut self, include_friends: IncludeFriends) -> Self {
+        self.request.include_tagger_friends = Some(include_friends);
+        self
+    }
+
+    /// Validates and returns the request.
+    pub fn build(self) -> Result<FilmsRequest> {
+        self.request.validate()?;
+        Ok(self.request)
+    }
+}
+
+/// Builder of a [`ListEntriesRequest`](../struct.ListEntriesRequest.html).
+#[derive(Debug, Clone, Default)]
+pub struct ListEntriesRequestBuilder {
+    request: ListEntriesRequest,
+}
+
+impl ListEntriesRequestBuilder {
+    /// Sets the pagination cursor.
+    pub fn cursor<S: Into<Cursor>>(mut self, cursor: S) -> Self {
+        self.request.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Sets the number of items per page (at most 100).
+    pub fn per_page(mut self, per_page: usize) -> Self {
+        self.request.per_page = Some(per_page);
+        self
+    }
+
+    /// Sets the order of the entries.
+    pub fn sort(mut self, sort: ListEntriesRequestSort) -> Self {
+        self.request.sort = Some(sort);
+        self
+    }
+
+    /// Limits the entries to films of a genre by LID.
+    pub fn genre<S: Into<String>>(mut self, genre: S) -> Self {
+        self.request.genre = Some(genre.into());
+        self
+    }
+
+    /// Limits the entries to films of a decade by its starting year (must end in 0).
+    pub fn decade(mut self, decade: u16) -> Self {
+        self.request.decade = Some(decade);
+        self
+    }
+
+    /// Limits the entries to films of a release year.
+    pub fn year(mut self, year: u16) -> Self {
+        self.request.year = Some(year);
+        self
+    }
+
+    /// Limits the entries to films available from a service by ID.
+    pub fn service<S: Into<String>>(mut self, service: S) -> Self {
+        self.request.service = Some(service.into());
+        self
+    }
+
+    /// Adds a status the films must have.
+    pub fn where_film_status(mut self, status: FilmStatus) -> Self {
+        self.request.where_film_status.push(status);
+        self
+    }
+
+    /// Limits the entries according to the relationship of their films with a member by LID.
+    pub fn member<S: Into<String>>(mut self, member: S) -> Self {
+        self.request.member = Some(member.into());
+        self
+    }
+
+    /// Sets the relationship with the member (requires `member`).
+    pub fn member_relationship(mut self, relationship: FilmRelationshipType) -> Self {
+        self.request.member_relationship = Some(relationship);
+        self
+    }
+
+    /// Includes the films of the member's friends (requires `member`).
+    pub fn include_friends(mut self, include_friends: IncludeFriends) -> Self {
+        self.request.include_friends = Some(include_friends);
+        self
+    }
+
+    /// Limits the entries to films tagged with a tag code.
+    pub fn tag_code<S: Into<String>>(mut self, tag_code: S) -> Self {
+        self.request.tag_code = Some(tag_code.into());
+        self
+    }
+
+    /// Focuses the tag filter on a member by LID (requires `tag_code`).
+    pub fn tagger<S: Into<String>>(mut self, tagger: S) -> Self {
+        self.request.tagger = Some(tagger.into());
+        self
+    }
+
+    /// Includes the tags of the tagger's friends (requires `tagger`).
+    pub fn include_tagger_friends(mut self, include_friends: IncludeFriends) -> Self {
+        self.request.include_tagger_friends = Some(include_friends);
+        self
+    }
+
+    /// Validates and returns the request.
+    pub fn build(self) -> Result<ListEntriesRequest> {
+        self.request.validate()?;
+        Ok(self.request)
+    }
+}
+
+/// Builder of a [`ListsRequest`](../struct.ListsRequest.html).
+#[derive(Debug, Clone, Default)]
+pub struct ListsRequestBuilder {
+    request: ListsRequest,
+}
+
+impl ListsRequestBuilder {
+    /// Sets the pagination cursor.
+    pub fn cursor<S: Into<Cursor>>(mut self, cursor: S) -> Self {
+        self.request.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Sets the number of items per page (at most 100).
+    pub fn per_page(mut self, per_page: usize) -> Self {
+        self.request.per_page = Some(per_page);
+        self
+    }
+
+    /// Sets the order of the lists.
+    pub fn sort(mut self, sort: ListRequestSort) -> Self {
+        self.request.sort = Some(sort);
+        self
+    }
+
+    /// Limits the lists to those including a film by LID.
+    pub fn film<S: Into<String>>(mut self, film: S) -> Self {
+        self.request.film = Some(film.into());
+        self
+    }
+
+    /// Limits the lists to those cloned from a list by LID.
+    pub fn cloned_from<S: Into<String>>(mut self, list: S) -> Self {
+        self.request.cloned_from = Some(list.into());
+        self
+    }
+
+    /// Limits the lists to those tagged with a tag code (requires `member` and the relationship
+    /// `Owner`).
+    pub fn tag_code<S: Into<String>>(mut self, tag_code: S) -> Self {
+        self.request.tag_code = Some(tag_code.into());
+        self
+    }
+
+    /// Limits the lists to those owned or liked by a member by LID.
+    pub fn member<S: Into<String>>(mut self, member: S) -> Self {
+        self.request.member = Some(member.into());
+        self
+    }
+
+    /// Sets the relationship with the member (requires `member`).
+    pub fn member_relationship(mut self, relationship: ListMemberRelationship) -> Self {
+        self.request.member_relationship = Some(relationship);
+        self
+    }
+
+    /// Includes the lists of the member's friends (requires `member`).
+    pub fn include_friends(mut self, include_friends: IncludeFriends) -> Self {
+        self.request.include_friends = Some(include_friends);
+        self
+    }
+
+    /// Adds a status the lists must have.
+    pub fn where_list_status(mut self, status: ListStatus) -> Self {
+        self.request.where_list_status.push(status);
+        self
+    }
+
+    /// Adds a filter of the lists.
+    pub fn filter(mut self, filter: ListRequestFilter) -> Self {
+        self.request.filter.push(filter);
+        self
+    }
+
+    /// Validates and returns the request.
+    pub fn build(self) -> Result<ListsRequest> {
+        self.request.validate()?;
+        Ok(self.request)
+    }
+}
@@ -0,0 +1,406 @@
+//! Caching of GET responses.
+//!
+//! A [`ResponseCache`](struct.ResponseCache.html) is set on a client with
+//! [`Client::set_cache`](../struct.Client.html#method.set_cache), or added as any other
+//! [middleware](../middleware/index.html). Only GET requests to endpoints
+//! with a time-to-live are cached. When an entry is expired and the server returned an `ETag`
+//! for it, the request is revalidated with `If-None-Match`; on `304 Not Modified` the cached
+//! response is used and kept for another time-to-live. If the entry was evicted in the meantime,
+//! the request is sent again without `If-None-Match`.
+//!
+//! Entries are stored in a [`CacheBackend`](trait.CacheBackend.html). The crate provides an
+//! in-memory LRU backend ([`MemoryCache`](struct.MemoryCache.html)) and an on-disk backend
+//! ([`DiskCache`](struct.DiskCache.html)).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::cache::{MemoryCache, ResponseCache};
+//! use std::time::Duration;
+//!
+//! let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//! let mut client = letterboxd::Client::new(api_key_pair);
+//! let cache = ResponseCache::reference_data(MemoryCache::new(1000))
+//!     .with_ttl("film/*/statistics", Duration::from_secs(60 * 60));
+//! client.set_cache(Some(cache));
+//! ```
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::middleware::{
+    header, BoxFuture, Method, Middleware, Next, Request, Response, StatusCode,
+};
+use crate::signing::SIGNING_PARAMS;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time-to-live of the reference data in
+/// [`ResponseCache::reference_data`](struct.ResponseCache.html#method.reference_data).
+const REFERENCE_DATA_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A cached response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// The body of the response.
+    #[serde(skip)]
+    pub body: Vec<u8>,
+    /// The `ETag` header of the response, if any.
+    pub etag: Option<String>,
+    /// Time after which the entry must be revalidated, in seconds since the Unix epoch.
+    pub expires: u64,
+}
+
+impl CacheEntry {
+    /// Creates a new entry which expires after the given time-to-live.
+    pub fn new(body: Vec<u8>, etag: Option<String>, ttl: Duration) -> Self {
+        Self {
+            body,
+            etag,
+            expires: now() + ttl.as_secs(),
+        }
+    }
+
+    /// Returns true if the entry has not expired yet.
+    pub fn is_fresh(&self) -> bool {
+        now() < self.expires
+    }
+}
+
+/// Storage of cached responses.
+///
+/// Errors of a backend are not fatal for a request. Therefore, a backend should treat entries
+/// which cannot be read as missing.
+pub trait CacheBackend: Send + Sync {
+    /// Returns the entry for the given key.
+    fn get(&self, key: &str) -> Option<CacheEntry>;
+    /// Stores an entry, replacing an existing entry for the same key.
+    fn put(&self, key: &str, entry: CacheEntry);
+    /// Removes the entry for the given key.
+    fn remove(&self, key: &str);
+}
+
+/// In-memory cache which evicts the least recently used entries.
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    inner: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// Entries by key together with the tick of their last use.
+    entries: HashMap<String, (CacheEntry, u64)>,
+    /// Keys by tick of their last use.
+    uses: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &str) -> Option<&CacheEntry> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (entry, last_use) = self.entries.get_mut(key)?;
+        self.uses.remove(last_use);
+        self.uses.insert(tick, key.to_string());
+        *last_use = tick;
+        Some(entry)
+    }
+}
+
+impl MemoryCache {
+    /// Creates a new cache holding at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Returns the number of cached entries.
+    pub fn len(&self) -> usize {
+        self.inner.lock().expect("poisoned lock").entries.len()
+    }
+
+    /// Returns true if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        let mut lru = self.inner.lock().expect("poisoned lock");
+        lru.touch(key).cloned()
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lru = self.inner.lock().expect("poisoned lock");
+        lru.tick += 1;
+        let tick = lru.tick;
+        if let Some((_, last_use)) = lru.entries.insert(key.to_string(), (entry, tick)) {
+            lru.uses.remove(&last_use);
+        }
+        lru.uses.insert(tick, key.to_string());
+        while lru.entries.len() > self.capacity {
+            let oldest = match lru.uses.keys().next() {
+                Some(&oldest) => oldest,
+                None => break,
+            };
+            if let Some(key) = lru.uses.remove(&oldest) {
+                lru.entries.remove(&key);
+            }
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let mut lru = self.inner.lock().expect("poisoned lock");
+        if let Some((_, last_use)) = lru.entries.remove(key) {
+            lru.uses.remove(&last_use);
+        }
+    }
+}
+
+/// On-disk cache storing one file per entry in a directory.
+///
+/// The file name is the SHA-256 hash of the key. A file starts with a line containing the
+/// metadata of the entry as JSON, followed by the body of the response.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Creates a new cache in the given directory, creating it if it does not exist.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(hex::encode(Sha256::digest(key.as_bytes())))
+    }
+
+    fn read(&self, key: &str) -> io::Result<CacheEntry> {
+        let mut reader = io::BufReader::new(fs::File::open(self.path(key))?);
+        let mut metadata = String::new();
+        reader.read_line(&mut metadata)?;
+        let mut entry: CacheEntry = serde_json::from_str(&metadata)?;
+        reader.read_to_end(&mut entry.body)?;
+        Ok(entry)
+    }
+
+    fn write(&self, key: &str, entry: &CacheEntry) -> io::Result<()> {
+        // write to a temporary file first, so readers never see a partial entry
+        let path = self.path(key);
+        let tmp_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path)?;
+        serde_json::to_writer(&mut file, entry)?;
+        file.write_all(b"\n")?;
+        file.write_all(&entry.body)?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+impl CacheBackend for DiskCache {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.read(key).ok()
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) {
+        // a failing cache must not fail the request
+        let _ = self.write(key, &entry);
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key));
+    }
+}
+
+/// Cache of GET responses with per-endpoint time-to-live.
+pub struct ResponseCache {
+    backend: Box<dyn CacheBackend>,
+    ttls: Vec<(String, Duration)>,
+}
+
+impl ResponseCache {
+    /// Creates a new cache without any cached endpoints.
+    pub fn new<B: CacheBackend + 'static>(backend: B) -> Self {
+        Self {
+            backend: Box::new(backend),
+            ttls: Vec::new(),
+        }
+    }
+
+    /// Creates a new cache for reference data, which rarely changes.
+    ///
+    /// The genres (`films/genres`), services (`films/film-services`), film details
+    /// (`film/*`) and pronouns (`members/pronouns`) are cached for 24 hours.
+    pub fn reference_data<B: CacheBackend + 'static>(backend: B) -> Self {
+        Self::new(backend)
+            .with_ttl("films/genres", REFERENCE_DATA_TTL)
+            .with_ttl("films/film-services", REFERENCE_DATA_TTL)
+            .with_ttl("film/*", REFERENCE_DATA_TTL)
+            .with_ttl("members/pronouns", REFERENCE_DATA_TTL)
+    }
+
+    /// Caches responses of an endpoint for the given time-to-live.
+    ///
+    /// The endpoint is a path relative to the API base url, e.g. `films/genres`. A `*` segment
+    /// matches any single segment, e.g. `film/*` matches `film/2a9q` but not
+    /// `film/2a9q/statistics`. Setting the time-to-live of an endpoint again replaces it.
+    pub fn with_ttl(mut self, endpoint: &str, ttl: Duration) -> Self {
+        let endpoint = endpoint.trim_matches('/').to_string();
+        self.ttls.retain(|(pattern, _)| *pattern != endpoint);
+        self.ttls.push((endpoint, ttl));
+        self
+    }
+
+    /// Returns the time-to-live of responses of an endpoint, or `None` if they are not cached.
+    ///
+    /// If several patterns match, the last added one is used.
+    pub fn ttl(&self, endpoint_path: &str) -> Option<Duration> {
+        let path: Vec<&str> = endpoint_path.trim_matches('/').split('/').collect();
+        self.ttls
+            .iter()
+            .rev()
+            .find(|(pattern, _)| {
+                let pattern: Vec<&str> = pattern.split('/').collect();
+                pattern.len() == path.len()
+                    && pattern
+                        .iter()
+                        .zip(&path)
+                        .all(|(pattern, segment)| *pattern == "*" || pattern == segment)
+            })
+            .map(|&(_, ttl)| ttl)
+    }
+
+    /// Returns the backend storing the entries.
+    pub fn backend(&self) -> &dyn CacheBackend {
+        self.backend.as_ref()
+    }
+
+    /// Returns the cache key of a request, which is independent of the API base url.
+    fn key(&self, request: &Request) -> String {
+        let mut url = Url::parse(Client::API_BASE_URL)
+            .and_then(|base| base.join(&request.endpoint_path))
+            .unwrap_or_else(|_| request.url.clone());
+        url.set_query(request.url.query());
+        let token = request
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        cache_key(&url, token)
+    }
+}
+
+impl Middleware for ResponseCache {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let response = next.run(request.clone()).await?;
+            // `on_response` answers a revalidation from the entry, unless it was evicted since
+            // the request was sent
+            if response.status == StatusCode::NOT_MODIFIED {
+                return next.run(request).await;
+            }
+            Ok(response)
+        })
+    }
+
+    fn after_sign(&self, request: &mut Request) -> Option<Response> {
+        if request.method != Method::GET {
+            return None;
+        }
+        self.ttl(&request.endpoint_path)?;
+        let entry = self.backend.get(&self.key(request))?;
+        if entry.is_fresh() {
+            return Some(Response::ok(entry.body));
+        }
+        let etag = entry
+            .etag
+            .and_then(|etag| header::HeaderValue::from_str(&etag).ok());
+        if let Some(etag) = etag {
+            request.headers.insert(header::IF_NONE_MATCH, etag);
+        }
+        None
+    }
+
+    fn on_response(&self, request: &Request, response: &mut Response) {
+        if request.method != Method::GET {
+            return;
+        }
+        let ttl = match self.ttl(&request.endpoint_path) {
+            Some(ttl) => ttl,
+            None => return,
+        };
+        let key = self.key(request);
+        let etag = response
+            .headers
+            .get(header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(String::from);
+        if response.status == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = self.backend.get(&key) {
+                response.status = StatusCode::OK;
+                response.body = entry.body.clone();
+                let etag = etag.or(entry.etag);
+                self.backend
+                    .put(&key, CacheEntry::new(entry.body, etag, ttl));
+            }
+        } else if response.status.is_success() {
+            self.backend
+                .put(&key, CacheEntry::new(response.body.clone(), etag, ttl));
+        }
+    }
+}
+
+impl fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("ttls", &self.ttls)
+            .finish()
+    }
+}
+
+/// Returns the cache key of a request.
+///
+/// The signing parameters `apikey`, `nonce`, `timestamp` and `signature` are removed from the
+/// url. The
+/// authorization is part of the key, since responses include data of the authenticated member.
+pub fn cache_key(url: &Url, authorization: Option<&str>) -> String {
+    let mut url = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| !SIGNING_PARAMS.contains(&name.as_ref()))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    match authorization {
+        Some(authorization) => format!("{} {}", url, authorization),
+        None => url.into(),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("SystemTime::duration_since failed")
+        .as_secs()
+}
@@ -1,5 +1,10 @@
+use crate::cache::ResponseCache;
 use crate::defs;
+use crate::endpoint::{self, Endpoint};
 use crate::error::{Error, Result};
+use crate::middleware::{self, BoxFuture, Middleware, Next};
+use crate::signing;
+use crate::token_store::TokenStore;
 
 use futures::stream::StreamExt;
 use hyper::{
@@ -12,6 +17,8 @@ use serde::{de::DeserializeOwned, Serialize};
 use url::Url;
 
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 /// API key/secret pair.
 ///
@@ -19,8 +26,8 @@ use std::fmt;
 /// `LETTERBOXD_API_KEY` and `LETTERBOXD_API_SECRET`.
 #[derive(Debug, Clone)]
 pub struct ApiKeyPair {
-    api_key: String,
-    api_secret: String,
+    pub(crate) api_key: String,
+    pub(crate) api_secret: String,
 }
 
 impl ApiKeyPair {
@@ -69,10 +76,14 @@ pub struct Client {
     api_key_pair: ApiKeyPair,
     token: Option<defs::AccessToken>,
     http_client: hyper::Client<HttpsConnector<HttpConnector>>,
+    cache: Option<ResponseCache>,
+    token_store: Option<Box<dyn TokenStore>>,
+    middlewares: Vec<Box<dyn Middleware>>,
+    api_base_url: Url,
 }
 
 impl Client {
-    const API_BASE_URL: &'static str = "https://api.letterboxd.com/api/v0/";
+    pub(crate) const API_BASE_URL: &'static str = "https://api.letterboxd.com/api/v0/";
 
     /// Creates a new client without authentication.
     pub fn new(api_key_pair: ApiKeyPair) -> Self {
@@ -83,6 +94,10 @@ impl Client {
             api_key_pair,
             token: None,
             http_client,
+            cache: None,
+            token_store: None,
+            middlewares: Vec::new(),
+            api_base_url: Url::parse(Self::API_BASE_URL).expect("invalid base url"),
         }
     }
 
@@ -96,33 +111,83 @@ impl Client {
             api_key_pair,
             token: Some(token),
             http_client,
+            cache: None,
+            token_store: None,
+            middlewares: Vec::new(),
+            api_base_url: Url::parse(Self::API_BASE_URL).expect("invalid base url"),
         }
     }
 
+    /// Creates a new client with the token loaded from a token store.
+    ///
+    /// If the store contains no token, the client is not authenticated. Tokens obtained by
+    /// `login` or `refresh` are saved to the store.
+    pub fn from_store<S>(api_key_pair: ApiKeyPair, store: S) -> Result<Self>
+    where
+        S: TokenStore + 'static,
+    {
+        let mut client = Self::new(api_key_pair);
+        client.token = store.load()?;
+        client.token_store = Some(Box::new(store));
+        Ok(client)
+    }
+
     /// Authenticates and creates a new client from given username/password.
     pub async fn authenticate(
         api_key_pair: ApiKeyPair,
         username: &str,
         password: &str,
     ) -> Result<Self> {
-        let content_type = HeaderValue::from_static("application/x-www-form-urlencoded");
-
-        #[derive(Debug, Serialize)]
-        struct AuthRequest<'a> {
-            grant_type: &'static str,
-            username: &'a str,
-            password: &'a str,
-        }
+        let mut client = Self::new(api_key_pair);
+        client.login(username, password).await?;
+        Ok(client)
+    }
 
+    /// Authenticates the client with given username/password.
+    ///
+    /// The obtained token is saved to the token store, if any.
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<()> {
         let request = AuthRequest {
             grant_type: "password",
-            username,
-            password,
+            username: Some(username),
+            password: Some(password),
+            refresh_token: None,
         };
-        let body = serde_url_params::to_vec(&request)?;
+        let token = self.request_token(&request).await?;
+        self.save_token(token)
+    }
 
-        let mut client = Self::new(api_key_pair);
-        let bytes = client
+    /// Obtains a new access token with the refresh token of the current token.
+    ///
+    /// The obtained token is saved to the token store, if any.
+    pub async fn refresh(&mut self) -> Result<()> {
+        let refresh_token = match self.token {
+            Some(ref token) => token.refresh_token.clone(),
+            None => return Err(Error::not_authenticated()),
+        };
+        let request = AuthRequest {
+            grant_type: "refresh_token",
+            username: None,
+            password: None,
+            refresh_token: Some(&refresh_token),
+        };
+        let token = self.request_token(&request).await?;
+        self.save_token(token)
+    }
+
+    /// Removes the token from the client and the token store, if any.
+    pub fn logout(&mut self) -> Result<()> {
+        self.token = None;
+        match self.token_store {
+            Some(ref store) => store.clear(),
+            None => Ok(()),
+        }
+    }
+
+    async fn request_token(&self, request: &AuthRequest<'_>) -> Result<defs::AccessToken> {
+        let content_type = HeaderValue::from_static("application/x-www-form-urlencoded");
+        let body = serde_url_params::to_vec(request)?;
+        let bytes = self
             .request_bytes::<()>(
                 Method::POST,
                 "auth/token",
@@ -131,8 +196,15 @@ impl Client {
                 Some(body),
             )
             .await?;
-        client.set_token(Some(serde_json::from_slice(&bytes)?));
-        Ok(client)
+        from_json(&bytes)
+    }
+
+    fn save_token(&mut self, token: defs::AccessToken) -> Result<()> {
+        if let Some(ref store) = self.token_store {
+            store.save(&token)?;
+        }
+        self.token = Some(token);
+        Ok(())
     }
 
     /// Returns if the client has a token.
@@ -154,6 +226,38 @@ impl Client {
         self.token = token;
     }
 
+    /// Returns the cache used for GET requests.
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_ref()
+    }
+
+    /// Sets a cache which will be used for GET requests.
+    ///
+    /// The cache is called before all other middlewares. Setting `None` disables caching.
+    pub fn set_cache(&mut self, cache: Option<ResponseCache>) {
+        self.cache = cache;
+    }
+
+    /// Returns the base url of the API.
+    pub fn api_base_url(&self) -> &Url {
+        &self.api_base_url
+    }
+
+    /// Sets the base url of the API, e.g. of a proxy or a mock server.
+    ///
+    /// The path of the url should end with a `/`, since the endpoint paths are joined to it.
+    pub fn set_api_base_url(&mut self, url: Url) {
+        self.api_base_url = url;
+    }
+
+    /// Appends a middleware to the middlewares called for each request.
+    pub fn add_middleware<M>(&mut self, middleware: M)
+    where
+        M: Middleware + 'static,
+    {
+        self.middlewares.push(Box::new(middleware));
+    }
+
     // API endpoints
 
     // film
@@ -164,7 +268,7 @@ impl Client {
     /// relationships for the signed-in member and the member indicated by the member LID if
     /// specified.
     pub async fn films(&self, request: &defs::FilmsRequest) -> Result<defs::FilmsResponse> {
-        self.get_with_query("films", request).await
+        self.execute(&endpoint::GetFilms { request }).await
     }
 
     /// Get a list of services supported by the /films endpoint.
@@ -172,29 +276,29 @@ impl Client {
     /// Services are returned in alphabetical order. Some services are only available to paying
     /// members, so results will vary based on the authenticated member’s status.
     pub async fn film_services(&self) -> Result<defs::FilmServicesResponse> {
-        self.get("films/film-services").await
+        self.execute(&endpoint::GetFilmServices).await
     }
 
     /// Get a list of genres supported by the `films` function.
     ///
     /// Genres are returned in alphabetical order.
     pub async fn film_genres(&self) -> Result<defs::GenresResponse> {
-        self.get("films/genres").await
+        self.execute(&endpoint::GetFilmGenres).await
     }
 
     /// Get details about a film by ID.
     pub async fn film(&self, id: &str) -> Result<defs::Film> {
-        self.get(&format!("film/{}", id)).await
+        self.execute(&endpoint::GetFilm { id }).await
     }
 
     /// Get availability data about a film by ID.
     pub async fn film_availability(&self, id: &str) -> Result<defs::FilmAvailabilityResponse> {
-        self.get(&format!("film/{}/availability", id)).await
+        self.execute(&endpoint::GetFilmAvailability { id }).await
     }
 
     /// Get details of the authenticated member’s relationship with a film by ID.
-    pub async fn film_relationship(&self, id: &str) -> Result<defs::FilmAvailabilityResponse> {
-        self.get(&format!("film/{}/me", id)).await
+    pub async fn film_relationship(&self, id: &str) -> Result<defs::FilmRelationship> {
+        self.execute(&endpoint::GetFilmRelationship { id }).await
     }
 
     /// Update the authenticated member’s relationship with a film by ID.
@@ -203,7 +307,8 @@ impl Client {
         id: &str,
         request: &defs::FilmRelationshipUpdateRequest,
     ) -> Result<defs::FilmRelationshipUpdateResponse> {
-        self.patch(&format!("film/{}/me", id), request).await
+        self.execute(&endpoint::UpdateFilmRelationship { id, request })
+            .await
     }
 
     /// Get details of the authenticated member’s relationship with a film by ID.
@@ -212,7 +317,7 @@ impl Client {
         id: &str,
         request: &defs::MemberFilmRelationshipsRequest,
     ) -> Result<defs::MemberFilmRelationshipsResponse> {
-        self.get_with_query(&format!("film/{}/members", id), request)
+        self.execute(&endpoint::GetFilmRelationshipMembers { id, request })
             .await
     }
 
@@ -220,7 +325,7 @@ impl Client {
 
     /// Get statistical data about a film by ID.
     pub async fn film_statistics(&self, id: &str) -> Result<defs::FilmStatistics> {
-        self.get(&format!("film/{}/statistics", id)).await
+        self.execute(&endpoint::GetFilmStatistics { id }).await
     }
 
     // list
@@ -229,7 +334,7 @@ impl Client {
     ///
     /// Use the ‘next’ cursor to move through the list.
     pub async fn lists(&self, request: &defs::ListsRequest) -> Result<defs::ListsResponse> {
-        self.get_with_query("lists", request).await
+        self.execute(&endpoint::GetLists { request }).await
     }
 
     /// Create a list.
@@ -237,12 +342,12 @@ impl Client {
         &self,
         request: &defs::ListCreationRequest,
     ) -> Result<defs::ListCreateResponse> {
-        self.post("lists", request).await
+        self.execute(&endpoint::CreateList { request }).await
     }
 
     /// Get details of a list by ID.
     pub async fn list(&self, id: &str) -> Result<defs::List> {
-        self.get(&format!("list/{}", id)).await
+        self.execute(&endpoint::GetList { id }).await
     }
 
     /// Update a list by ID.
@@ -251,12 +356,12 @@ impl Client {
         id: &str,
         request: &defs::ListUpdateRequest,
     ) -> Result<defs::ListUpdateResponse> {
-        self.patch(&format!("list/{}", id), request).await
+        self.execute(&endpoint::UpdateList { id, request }).await
     }
 
     /// Delete a list by ID.
     pub async fn delete_list(&self, id: &str) -> Result<()> {
-        self.delete(&format!("list/{}", id)).await
+        self.execute(&endpoint::DeleteList { id }).await
     }
 
     //     /list/{id}/comments
@@ -267,7 +372,7 @@ impl Client {
         id: &str,
         request: &defs::ListEntriesRequest,
     ) -> Result<defs::ListEntriesResponse> {
-        self.get_with_query(&format!("list/{}/entries", id), request)
+        self.execute(&endpoint::GetListEntries { id, request })
             .await
     }
 
@@ -278,7 +383,28 @@ impl Client {
 
     // log-entry
 
-    //     /log-entries
+    /// A cursored window over the log entries for a film or member.
+    ///
+    /// A log entry is a review and/or diary entry. Use the ‘next’ cursor to move through the
+    /// list.
+    pub async fn log_entries(
+        &self,
+        request: &defs::LogEntriesRequest,
+    ) -> Result<defs::LogEntriesResponse> {
+        self.execute(&endpoint::GetLogEntries { request }).await
+    }
+
+    /// Create a log entry.
+    ///
+    /// A log entry is either a diary entry (must have a date) or a review (must have review text).
+    /// Log entries can be both a diary entry and a review if they satisfy both criteria.
+    pub async fn create_log_entry(
+        &self,
+        request: &defs::LogEntryCreationRequest,
+    ) -> Result<defs::LogEntry> {
+        self.execute(&endpoint::CreateLogEntry { request }).await
+    }
+
     //     /log-entry/{id}
     //     /log-entry/{id}/comments
     //     /log-entry/{id}/me
@@ -293,10 +419,26 @@ impl Client {
     // member
 
     //     /members
-    //     /members/pronouns
+    /// Get a list of the pronoun sets supported by the API.
+    pub async fn member_pronouns(&self) -> Result<defs::PronounsResponse> {
+        self.execute(&endpoint::GetMemberPronouns).await
+    }
+
     //     /members/register
     //     /member/{id}
-    //     /member/{id}/activity
+
+    /// A cursored window over the activity for a member.
+    ///
+    /// Use the ‘next’ cursor to move through the list.
+    pub async fn member_activity(
+        &self,
+        id: &str,
+        request: &defs::ActivityRequest,
+    ) -> Result<defs::ActivityResponse> {
+        self.execute(&endpoint::GetMemberActivity { id, request })
+            .await
+    }
+
     //     /member/{id}/list-tags
     //     /member/{id}/list-tags-2
     //     /member/{id}/log-entry-tags
@@ -305,81 +447,64 @@ impl Client {
     //     /member/{id}/review-tags
     //     /member/{id}/review-tags-2
     //     /member/{id}/statistics
-    //     /member/{id}/watchlist
+
+    /// A cursored window over the films in a member’s watchlist.
+    ///
+    /// Use the ‘next’ cursor to move through the list.
+    pub async fn member_watchlist(
+        &self,
+        id: &str,
+        request: &defs::WatchlistRequest,
+    ) -> Result<defs::FilmsResponse> {
+        self.execute(&endpoint::GetMemberWatchlist { id, request })
+            .await
+    }
 
     // search
 
     /// Search for any data.
     pub async fn search(&self, request: &defs::SearchRequest) -> Result<defs::SearchResponse> {
-        self.get_with_query("search", request).await
+        self.execute(&endpoint::Search { request }).await
     }
 
     // helper methods
 
-    // request helper
-
-    async fn get<R>(&self, endpoint_path: &str) -> Result<R>
-    where
-        R: DeserializeOwned + 'static,
-    {
-        self.request::<(), (), _>(Method::GET, endpoint_path, None, None)
-            .await
-    }
-
-    async fn get_with_query<Q, R>(&self, endpoint_path: &str, query: &Q) -> Result<R>
-    where
-        Q: Serialize,
-        R: DeserializeOwned + 'static,
-    {
-        self.request::<_, (), _>(Method::GET, endpoint_path, Some(query), None)
-            .await
-    }
-
-    async fn patch<B, R>(&self, endpoint_path: &str, body: &B) -> Result<R>
-    where
-        B: Serialize,
-        R: DeserializeOwned + 'static,
-    {
-        self.request::<(), _, _>(Method::PATCH, endpoint_path, None, Some(body))
-            .await
-    }
-
-    async fn post<B, R>(&self, endpoint_path: &str, body: &B) -> Result<R>
-    where
-        B: Serialize,
-        R: DeserializeOwned + 'static,
-    {
-        self.request::<(), _, _>(Method::POST, endpoint_path, None, Some(body))
-            .await
-    }
-
-    async fn delete(&self, endpoint_path: &str) -> Result<()> {
-        self.request_bytes::<()>(Method::DELETE, endpoint_path, None, None, None)
-            .await?;
-        Ok(())
-    }
-
-    async fn request<Q, B, R>(
-        &self,
-        method: Method,
-        endpoint_path: &str,
-        query: Option<&Q>,
-        body: Option<&B>,
-    ) -> Result<R>
-    where
-        Q: Serialize,
-        B: Serialize,
-        R: DeserializeOwned + 'static,
-    {
-        let content_type = HeaderValue::from_static("application/json");
-        let body = body.map(serde_json::to_vec).transpose()?;
+    /// Runs a request to an endpoint.
+    ///
+    /// This is the generic counterpart of the methods above, and can also be used to call
+    /// routes which are not wrapped by the client, see the [`endpoint`](endpoint/index.html)
+    /// module.
+    pub async fn execute<E: Endpoint>(&self, endpoint: &E) -> Result<E::Response> {
+        endpoint.validate()?;
+        let path = endpoint::render_path(E::PATH, &endpoint.path_params())?;
+        let body = endpoint.body().map(serde_json::to_vec).transpose()?;
+        let content_type = body
+            .as_ref()
+            .map(|_| HeaderValue::from_static("application/json"));
         let bytes = self
-            .request_bytes(method, endpoint_path, query, Some(content_type), body)
+            .request_bytes(E::METHOD, &path, endpoint.query(), content_type, body)
             .await?;
-        let res = serde_json::from_slice(&bytes)?;
-        Ok(res)
+        if bytes.is_empty() {
+            from_json(b"null")
+        } else {
+            from_json(&bytes)
+        }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "letterboxd_request",
+            skip(self, query, content_type, body),
+            fields(
+                url = tracing::field::Empty,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                response_size = tracing::field::Empty,
+                retries = tracing::field::Empty,
+            ),
+        )
+    )]
     async fn request_bytes<Q>(
         &self,
         method: Method,
@@ -391,45 +516,154 @@ impl Client {
     where
         Q: Serialize,
     {
-        let mut url = Url::parse(Self::API_BASE_URL)
-            .unwrap()
-            .join(endpoint_path)
-            .unwrap(); // TODO
+        let mut url = self.api_base_url.join(endpoint_path)?;
         let query = query.map(serde_url_params::to_string).transpose()?;
         url.set_query(query.as_ref().map(|s| s.as_ref()));
 
-        let body = body.unwrap_or_default();
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static("application/json"),
+        );
+        if let Some(content_type) = content_type {
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+        if let Some(token) = self.token.as_ref() {
+            headers.insert(
+                header::AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token.access_token))
+                    .expect("invalid header value"),
+            );
+        }
+        let mut request = middleware::Request {
+            method,
+            endpoint_path: endpoint_path.to_string(),
+            url,
+            headers,
+            body: body.unwrap_or_default(),
+        };
+
+        let middlewares: Vec<&dyn Middleware> = self
+            .cache
+            .iter()
+            .map(|cache| cache as &dyn Middleware)
+            .chain(self.middlewares.iter().map(|m| m.as_ref()))
+            .collect();
+
+        // index of the short-circuiting middleware together with its response
+        let mut short_circuit = None;
+        for (index, middleware) in middlewares.iter().enumerate() {
+            if let Some(response) = middleware.before_sign(&mut request) {
+                short_circuit = Some((index, response));
+                break;
+            }
+        }
+        // the url of the last signed request, if any
+        let signed_url = Mutex::new(None);
+        // number of times the request was signed and sent
+        let sends = AtomicUsize::new(0);
+        let response = match short_circuit {
+            Some((index, mut response)) => {
+                for middleware in middlewares[..index].iter().rev() {
+                    middleware.on_response(&request, &mut response);
+                }
+                response
+            }
+            None => {
+                let sign_and_send = |request| -> BoxFuture<Result<middleware::Response>> {
+                    sends.fetch_add(1, Ordering::Relaxed);
+                    Box::pin(self.sign_and_send(&middlewares, request, &signed_url))
+                };
+                Next::new(&middlewares, &sign_and_send)
+                    .run(request.clone())
+                    .await?
+            }
+        };
+        if let Some(url) = signed_url.into_inner().expect("poisoned lock") {
+            request.url = url;
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::Span::current();
+            span.record("status", response.status.as_u16());
+            span.record("response_size", response.body.len());
+            if let Some(retries) = sends.into_inner().checked_sub(1) {
+                span.record("retries", retries);
+            }
+        }
+
+        if !response.status.is_success() {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("request failed with status {}", response.status);
+            let content = String::from_utf8_lossy(&response.body);
+            return Err(Error::server_error(
+                response.status,
+                content.to_string(),
+                request.url.as_str().parse()?,
+            ));
+        }
+
+        Ok(response.body)
+    }
 
-        let signed_url = self.sign_url(url, &method, &body);
+    /// Signs a request and sends it, unless a middleware short-circuits it after signing.
+    async fn sign_and_send(
+        &self,
+        middlewares: &[&dyn Middleware],
+        mut request: middleware::Request,
+        signed_url: &Mutex<Option<Url>>,
+    ) -> Result<middleware::Response> {
+        request.url = signing::sign(
+            &request.method,
+            request.url,
+            &request.body,
+            &self.api_key_pair,
+        );
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("url", redact_url(&request.url).as_str());
+        *signed_url.lock().expect("poisoned lock") = Some(request.url.clone());
+
+        // index of the short-circuiting middleware together with its response
+        let mut short_circuit = None;
+        for (index, middleware) in middlewares.iter().enumerate() {
+            if let Some(response) = middleware.after_sign(&mut request) {
+                short_circuit = Some((index, response));
+                break;
+            }
+        }
+        let (called, mut response) = match short_circuit {
+            Some((index, response)) => (index, response),
+            None => (middlewares.len(), self.send(&request).await?),
+        };
+        for middleware in middlewares[..called].iter().rev() {
+            middleware.on_response(&request, &mut response);
+        }
+        Ok(response)
+    }
 
+    /// Sends a request.
+    async fn send(&self, request: &middleware::Request) -> Result<middleware::Response> {
         let mut req = Request::builder()
-            .method(method)
-            .uri(signed_url.as_str())
-            .header(
-                header::ACCEPT_ENCODING,
-                HeaderValue::from_static("application/json"),
-            )
+            .method(request.method.clone())
+            .uri(request.url.as_str())
             .header(
                 header::CONTENT_LENGTH,
-                HeaderValue::from_str(&format!("{}", body.len())).expect("invalid header value"),
+                HeaderValue::from_str(&format!("{}", request.body.len()))
+                    .expect("invalid header value"),
             );
-
         if let Some(headers) = req.headers_mut() {
-            if let Some(content_type) = content_type {
-                headers.insert(header::CONTENT_TYPE, content_type);
-            }
-            if let Some(token) = self.token.as_ref() {
-                headers.insert(
-                    header::AUTHORIZATION,
-                    HeaderValue::from_str(&format!("Bearer {}", token.access_token))
-                        .expect("invalid header value"),
-                );
-            }
+            headers.extend(request.headers.clone());
         }
 
-        let req = req.body(Body::from(body)).expect("invalid body");
+        let req = req
+            .body(Body::from(request.body.clone()))
+            .expect("invalid body");
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
         let resp = self.http_client.request(req).await?;
         let status = resp.status();
+        let headers = resp.headers().clone();
 
         let mut body = resp.into_body();
         let mut bytes = Vec::new();
@@ -437,65 +671,74 @@ impl Client {
             let chunk = next?;
             bytes.extend(chunk);
         }
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("latency_ms", start.elapsed().as_millis() as u64);
 
-        if !status.is_success() {
-            let content = String::from_utf8_lossy(&bytes);
-            return Err(Error::server_error(
-                status,
-                content.to_string(),
-                signed_url.as_str().parse()?,
-            ));
-        }
-
-        Ok(bytes)
+        Ok(middleware::Response {
+            status,
+            headers,
+            body: bytes,
+        })
     }
+}
 
-    /// Signs the request based on a random and unique nonce, timestamp, and
-    /// client id and secret.
-    ///
-    /// The client id, nonce, timestamp and signature are added to the url's
-    /// query.
-    ///
-    /// See http://api-docs.letterboxd.com/#signing.
-    fn sign_url(&self, mut url: Url, method: &Method, body: &[u8]) -> Url {
-        use crypto::{hmac, mac::Mac, sha2};
-        use hex::ToHex;
-        use std::time;
-
-        let nonce = uuid::Uuid::new_v4(); // use UUID as random and unique nonce
-
-        let timestamp = time::SystemTime::now()
-            .duration_since(time::UNIX_EPOCH)
-            .expect("SystemTime::duration_since failed")
-            .as_secs();
-
-        url.query_pairs_mut()
-            .append_pair("apikey", &self.api_key_pair.api_key)
-            .append_pair("nonce", &format!("{}", nonce))
-            .append_pair("timestamp", &format!("{}", timestamp));
-
-        // create signature
-        let mut hmac =
-            hmac::Hmac::new(sha2::Sha256::new(), self.api_key_pair.api_secret.as_bytes());
-        hmac.input(method.as_str().as_bytes());
-        hmac.input(&[b'\0']);
-        hmac.input(url.as_str().as_bytes());
-        hmac.input(&[b'\0']);
-        hmac.input(body);
-        let signature: String = hmac.result().code().encode_hex();
-
-        url.query_pairs_mut().append_pair("signature", &signature);
-
-        url
+/// Deserializes a JSON response.
+///
+/// With the `tracing` feature, the path of the value which failed to deserialize is logged.
+fn from_json<R: DeserializeOwned>(bytes: &[u8]) -> Result<R> {
+    #[cfg(feature = "tracing")]
+    {
+        let mut de = serde_json::Deserializer::from_slice(bytes);
+        let res = serde_path_to_error::deserialize(&mut de).map_err(|e| {
+            tracing::error!(path = %e.path(), "failed to deserialize response: {}", e.inner());
+            e.into_inner()
+        })?;
+        de.end()?;
+        Ok(res)
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        Ok(serde_json::from_slice(bytes)?)
     }
 }
 
+/// Returns the url with the values of the api key and signature replaced.
+#[cfg(feature = "tracing")]
+fn redact_url(url: &Url) -> String {
+    let mut redacted = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| match name.as_ref() {
+            "apikey" | "signature" => (name.into_owned(), "[redacted]".to_string()),
+            _ => (name.into_owned(), value.into_owned()),
+        })
+        .collect();
+    if !pairs.is_empty() {
+        redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    redacted.into()
+}
+
+#[derive(Debug, Serialize)]
+struct AuthRequest<'a> {
+    grant_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<&'a str>,
+}
+
 impl fmt::Debug for Client {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Client")
             .field("api_key_pair", &"[hidden]")
             .field("token", &self.token)
             .field("http_client", &self.http_client)
+            .field("cache", &self.cache)
+            .field("token_store", &self.token_store.as_ref().map(|_| "[store]"))
+            .field("middlewares", &self.middlewares.len())
             .finish()
     }
 }
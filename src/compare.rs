@@ -0,0 +1,233 @@
+//! Taste compatibility of two members.
+//!
+//! A [`Comparison`](struct.Comparison.html) is computed from the films watched by two members,
+//! including each member's relationship to the films. It contains the films both members
+//! watched, the correlation of their ratings, their agreement on likes, and the films one member
+//! loved which the other member has not watched yet.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::compare::Comparison;
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let client = letterboxd::Client::new(api_key_pair);
+//!
+//!     let comparison = Comparison::fetch(&client, "2bbs", "1Jw").await?;
+//!     if let Some(correlation) = comparison.rating_correlation {
+//!         println!("correlation of ratings: {:.2}", correlation);
+//!     }
+//!     for shared in comparison.disagreements(5) {
+//!         println!("{}: {:?} vs. {:?}", shared.film.name, shared.rating_a, shared.rating_b);
+//!     }
+//!     for film in &comparison.recommendations_for_b {
+//!         println!("loved by the first member: {}", film.name);
+//!     }
+//!     Ok::<_, letterboxd::Error>(())
+//! };
+//! ```
+
+use crate::client::Client;
+use crate::defs::{FilmRelationship, FilmRelationshipType, FilmSummary, FilmsRequest};
+use crate::error::Result;
+
+use std::collections::{HashMap, HashSet};
+
+/// Page size used when fetching the films of the members.
+const PER_PAGE: usize = 100;
+
+/// Minimum rating of a film to count as loved; liked films without a rating are loved as well.
+pub const LOVED_RATING: f32 = 4.5;
+
+/// A film watched by both members.
+#[derive(Debug, Clone)]
+pub struct SharedFilm {
+    /// The film.
+    pub film: FilmSummary,
+    /// The rating of the first member, if any.
+    pub rating_a: Option<f32>,
+    /// The rating of the second member, if any.
+    pub rating_b: Option<f32>,
+    /// Whether the first member liked the film.
+    pub liked_a: bool,
+    /// Whether the second member liked the film.
+    pub liked_b: bool,
+}
+
+impl SharedFilm {
+    /// Returns the absolute difference of the ratings, if both members rated the film.
+    pub fn rating_difference(&self) -> Option<f32> {
+        Some((self.rating_a? - self.rating_b?).abs())
+    }
+}
+
+/// The comparison of the tastes of two members.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    /// The LID of the first member.
+    pub member_a: String,
+    /// The LID of the second member.
+    pub member_b: String,
+    /// The films watched by both members, in the order of the first member's films.
+    pub shared: Vec<SharedFilm>,
+    /// The Pearson correlation of the ratings of the films rated by both members, between -1.0
+    /// and 1.0.
+    ///
+    /// `None` if fewer than two films were rated by both, or if either member gave all of
+    /// them the same rating.
+    pub rating_correlation: Option<f64>,
+    /// The share of the shared films liked by either member which are liked by both, between
+    /// 0.0 and 1.0.
+    ///
+    /// `None` if neither member liked a shared film.
+    pub like_agreement: Option<f64>,
+    /// Films loved by the first member which the second member has not watched.
+    pub recommendations_for_b: Vec<FilmSummary>,
+    /// Films loved by the second member which the first member has not watched.
+    pub recommendations_for_a: Vec<FilmSummary>,
+}
+
+impl Comparison {
+    /// Fetches the films watched by both members and compares them.
+    ///
+    /// Ratings and likes are only visible as far as the members made them public, unless the
+    /// client is authenticated as one of the members.
+    pub async fn fetch(client: &Client, member_a: &str, member_b: &str) -> Result<Self> {
+        let watched_a = fetch_watched(client, member_a).await?;
+        let watched_b = fetch_watched(client, member_b).await?;
+        Ok(Self::new(member_a, &watched_a, member_b, &watched_b))
+    }
+
+    /// Compares the films watched by two members.
+    ///
+    /// The films must include the relationship of the respective member, as returned by
+    /// `Client::films` with the member and the `Watched` relationship.
+    pub fn new(
+        member_a: &str,
+        watched_a: &[FilmSummary],
+        member_b: &str,
+        watched_b: &[FilmSummary],
+    ) -> Self {
+        let relationships_b: HashMap<&str, Option<&FilmRelationship>> = watched_b
+            .iter()
+            .map(|film| (film.id.as_str(), relationship(film, member_b)))
+            .collect();
+        let ids_a: HashSet<&str> = watched_a.iter().map(|film| film.id.as_str()).collect();
+
+        let mut shared = Vec::new();
+        let mut recommendations_for_b = Vec::new();
+        for film in watched_a {
+            let relationship_a = relationship(film, member_a);
+            match relationships_b.get(film.id.as_str()) {
+                Some(relationship_b) => shared.push(SharedFilm {
+                    film: film.clone(),
+                    rating_a: relationship_a.and_then(|r| r.rating),
+                    rating_b: relationship_b.and_then(|r| r.rating),
+                    liked_a: relationship_a.is_some_and(|r| r.liked),
+                    liked_b: relationship_b.is_some_and(|r| r.liked),
+                }),
+                None if is_loved(relationship_a) => recommendations_for_b.push(film.clone()),
+                None => (),
+            }
+        }
+        let recommendations_for_a = watched_b
+            .iter()
+            .filter(|film| !ids_a.contains(film.id.as_str()))
+            .filter(|film| is_loved(relationship(film, member_b)))
+            .cloned()
+            .collect();
+
+        let ratings: Vec<(f64, f64)> = shared
+            .iter()
+            .filter_map(|film| Some((film.rating_a? as f64, film.rating_b? as f64)))
+            .collect();
+        let liked_by_either = shared.iter().filter(|f| f.liked_a || f.liked_b).count();
+        let liked_by_both = shared.iter().filter(|f| f.liked_a && f.liked_b).count();
+        let like_agreement = if liked_by_either > 0 {
+            Some(liked_by_both as f64 / liked_by_either as f64)
+        } else {
+            None
+        };
+
+        Self {
+            member_a: member_a.to_string(),
+            member_b: member_b.to_string(),
+            shared,
+            rating_correlation: correlation(&ratings),
+            like_agreement,
+            recommendations_for_b,
+            recommendations_for_a,
+        }
+    }
+
+    /// Returns the films rated by both members with the biggest differences of ratings first,
+    /// at most `n` films.
+    ///
+    /// Films with the same difference stay in the order of the first member's films.
+    pub fn disagreements(&self, n: usize) -> Vec<&SharedFilm> {
+        let mut rated: Vec<(f32, &SharedFilm)> = self
+            .shared
+            .iter()
+            .filter_map(|film| Some((film.rating_difference()?, film)))
+            .filter(|(difference, _)| *difference > 0.0)
+            .collect();
+        rated.sort_by(|a, b| b.0.total_cmp(&a.0));
+        rated.into_iter().take(n).map(|(_, film)| film).collect()
+    }
+}
+
+async fn fetch_watched(client: &Client, member: &str) -> Result<Vec<FilmSummary>> {
+    let mut films = Vec::new();
+    let mut req = FilmsRequest {
+        per_page: Some(PER_PAGE),
+        member: Some(member.to_string()),
+        member_relationship: Some(FilmRelationshipType::Watched),
+        ..Default::default()
+    };
+    loop {
+        let resp = client.films(&req).await?;
+        films.extend(resp.items);
+        match resp.next {
+            Some(next) => req.cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(films)
+}
+
+fn relationship<'a>(film: &'a FilmSummary, member: &str) -> Option<&'a FilmRelationship> {
+    film.relationships
+        .iter()
+        .find(|r| r.member.id == member)
+        .map(|r| &r.relationship)
+}
+
+fn is_loved(relationship: Option<&FilmRelationship>) -> bool {
+    relationship.is_some_and(|r| match r.rating {
+        Some(rating) => rating >= LOVED_RATING,
+        None => r.liked,
+    })
+}
+
+/// Returns the Pearson correlation coefficient of pairs of values.
+fn correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_a = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_b = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let mut covariance = 0.0;
+    let mut variance_a = 0.0;
+    let mut variance_b = 0.0;
+    for (a, b) in pairs {
+        covariance += (a - mean_a) * (b - mean_b);
+        variance_a += (a - mean_a).powi(2);
+        variance_b += (b - mean_b).powi(2);
+    }
+    if variance_a == 0.0 || variance_b == 0.0 {
+        return None;
+    }
+    Some((covariance / (variance_a * variance_b).sqrt()).clamp(-1.0, 1.0))
+}
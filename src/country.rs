@@ -0,0 +1,230 @@
+//! ISO 3166-1 metadata of countries.
+//!
+//! [`Country`](../enum.Country.html) identifies the regional store of a
+//! [`FilmAvailability`](../struct.FilmAvailability.html) by its alpha-3 code. Its methods map
+//! between the codes, provide the English short name, and iterate over all countries.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use letterboxd::Country;
+//!
+//! let locale = "en-GB";
+//! let country = locale
+//!     .rsplit('-')
+//!     .next()
+//!     .and_then(Country::from_alpha2)
+//!     .unwrap();
+//! assert_eq!(country, Country::GBR);
+//! assert_eq!(country.name(), "United Kingdom");
+//! assert_eq!(country.to_string(), "GBR");
+//! assert_eq!("de".parse::<Country>().unwrap(), Country::DEU);
+//! ```
+
+use crate::defs::Country;
+
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! countries {
+    ($($alpha3:ident => $alpha2:literal, $name:literal;)*) => {
+        impl Country {
+            /// All countries, ordered by alpha-3 code.
+            pub const ALL: &'static [Country] = &[$(Country::$alpha3),*];
+
+            /// Returns the ISO 3166-1 alpha-2 code, e.g. `GB`.
+            pub fn alpha2(self) -> &'static str {
+                match self {
+                    $(Country::$alpha3 => $alpha2,)*
+                }
+            }
+
+            /// Returns the ISO 3166-1 alpha-3 code, e.g. `GBR`.
+            pub fn alpha3(self) -> &'static str {
+                match self {
+                    $(Country::$alpha3 => stringify!($alpha3),)*
+                }
+            }
+
+            /// Returns the English short name, e.g. `United Kingdom`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Country::$alpha3 => $name,)*
+                }
+            }
+        }
+    };
+}
+
+countries! {
+    AIA => "AI", "Anguilla";
+    ARE => "AE", "United Arab Emirates";
+    ARG => "AR", "Argentina";
+    ARM => "AM", "Armenia";
+    ATG => "AG", "Antigua and Barbuda";
+    AUS => "AU", "Australia";
+    AUT => "AT", "Austria";
+    AZE => "AZ", "Azerbaijan";
+    BEL => "BE", "Belgium";
+    BFA => "BF", "Burkina Faso";
+    BGR => "BG", "Bulgaria";
+    BHR => "BH", "Bahrain";
+    BHS => "BS", "Bahamas";
+    BLR => "BY", "Belarus";
+    BLZ => "BZ", "Belize";
+    BMU => "BM", "Bermuda";
+    BOL => "BO", "Bolivia";
+    BRA => "BR", "Brazil";
+    BRB => "BB", "Barbados";
+    BRN => "BN", "Brunei Darussalam";
+    BWA => "BW", "Botswana";
+    CAN => "CA", "Canada";
+    CHE => "CH", "Switzerland";
+    CHL => "CL", "Chile";
+    CHN => "CN", "China";
+    COL => "CO", "Colombia";
+    CPV => "CV", "Cabo Verde";
+    CRI => "CR", "Costa Rica";
+    CYM => "KY", "Cayman Islands";
+    CYP => "CY", "Cyprus";
+    CZE => "CZ", "Czechia";
+    DEU => "DE", "Germany";
+    DMA => "DM", "Dominica";
+    DNK => "DK", "Denmark";
+    DOM => "DO", "Dominican Republic";
+    ECU => "EC", "Ecuador";
+    EGY => "EG", "Egypt";
+    ESP => "ES", "Spain";
+    EST => "EE", "Estonia";
+    FIN => "FI", "Finland";
+    FJI => "FJ", "Fiji";
+    FRA => "FR", "France";
+    FSM => "FM", "Micronesia";
+    GBR => "GB", "United Kingdom";
+    GHA => "GH", "Ghana";
+    GMB => "GM", "Gambia";
+    GNB => "GW", "Guinea-Bissau";
+    GRC => "GR", "Greece";
+    GRD => "GD", "Grenada";
+    GTM => "GT", "Guatemala";
+    HKG => "HK", "Hong Kong";
+    HND => "HN", "Honduras";
+    HUN => "HU", "Hungary";
+    IDN => "ID", "Indonesia";
+    IND => "IN", "India";
+    IRL => "IE", "Ireland";
+    ISR => "IL", "Israel";
+    ITA => "IT", "Italy";
+    JOR => "JO", "Jordan";
+    JPN => "JP", "Japan";
+    KAZ => "KZ", "Kazakhstan";
+    KEN => "KE", "Kenya";
+    KGZ => "KG", "Kyrgyzstan";
+    KHM => "KH", "Cambodia";
+    KNA => "KN", "Saint Kitts and Nevis";
+    LAO => "LA", "Laos";
+    LBN => "LB", "Lebanon";
+    LKA => "LK", "Sri Lanka";
+    LTU => "LT", "Lithuania";
+    LUX => "LU", "Luxembourg";
+    LVA => "LV", "Latvia";
+    MAC => "MO", "Macao";
+    MDA => "MD", "Moldova";
+    MEX => "MX", "Mexico";
+    MLT => "MT", "Malta";
+    MNG => "MN", "Mongolia";
+    MOZ => "MZ", "Mozambique";
+    MUS => "MU", "Mauritius";
+    MYS => "MY", "Malaysia";
+    NAM => "NA", "Namibia";
+    NER => "NE", "Niger";
+    NGA => "NG", "Nigeria";
+    NIC => "NI", "Nicaragua";
+    NLD => "NL", "Netherlands";
+    NOR => "NO", "Norway";
+    NPL => "NP", "Nepal";
+    NZL => "NZ", "New Zealand";
+    OMN => "OM", "Oman";
+    PAN => "PA", "Panama";
+    PER => "PE", "Peru";
+    PHL => "PH", "Philippines";
+    PNG => "PG", "Papua New Guinea";
+    POL => "PL", "Poland";
+    PRT => "PT", "Portugal";
+    PRY => "PY", "Paraguay";
+    QAT => "QA", "Qatar";
+    ROU => "RO", "Romania";
+    RUS => "RU", "Russia";
+    SAU => "SA", "Saudi Arabia";
+    SGP => "SG", "Singapore";
+    SLV => "SV", "El Salvador";
+    SVK => "SK", "Slovakia";
+    SVN => "SI", "Slovenia";
+    SWE => "SE", "Sweden";
+    SWZ => "SZ", "Eswatini";
+    THA => "TH", "Thailand";
+    TJK => "TJ", "Tajikistan";
+    TKM => "TM", "Turkmenistan";
+    TTO => "TT", "Trinidad and Tobago";
+    TUR => "TR", "Türkiye";
+    TWN => "TW", "Taiwan";
+    UGA => "UG", "Uganda";
+    UKR => "UA", "Ukraine";
+    USA => "US", "United States";
+    UZB => "UZ", "Uzbekistan";
+    VEN => "VE", "Venezuela";
+    VGB => "VG", "British Virgin Islands";
+    VNM => "VN", "Viet Nam";
+    ZAF => "ZA", "South Africa";
+    ZWE => "ZW", "Zimbabwe";
+}
+
+impl Country {
+    /// Returns an iterator over all countries, ordered by alpha-3 code.
+    pub fn iter() -> impl Iterator<Item = Country> {
+        Self::ALL.iter().copied()
+    }
+
+    /// Returns the country with an ISO 3166-1 alpha-2 code, ignoring case.
+    pub fn from_alpha2(code: &str) -> Option<Country> {
+        Self::iter().find(|country| country.alpha2().eq_ignore_ascii_case(code))
+    }
+
+    /// Returns the country with an ISO 3166-1 alpha-3 code, ignoring case.
+    pub fn from_alpha3(code: &str) -> Option<Country> {
+        Self::iter().find(|country| country.alpha3().eq_ignore_ascii_case(code))
+    }
+}
+
+/// Formats the alpha-3 code, like the country is serialized.
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.alpha3())
+    }
+}
+
+/// Parses an alpha-2 or alpha-3 code, ignoring case.
+impl FromStr for Country {
+    type Err = ParseCountryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.len() {
+            2 => Self::from_alpha2(s),
+            3 => Self::from_alpha3(s),
+            _ => None,
+        }
+        .ok_or_else(|| ParseCountryError(s.to_string()))
+    }
+}
+
+/// Error returned when parsing an unknown country code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCountryError(String);
+
+impl fmt::Display for ParseCountryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown country code: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseCountryError {}
@@ -0,0 +1,312 @@
+//! Cross-referencing of films with IMDb and TMDB.
+//!
+//! The `links` of a film point to the same film on other sites. The methods
+//! [`Film::imdb_id`](../struct.Film.html#method.imdb_id),
+//! [`Film::tmdb_id`](../struct.Film.html#method.tmdb_id) and
+//! [`Film::letterboxd_url`](../struct.Film.html#method.letterboxd_url) return them.
+//!
+//! In the other direction, a [`FilmResolver`](struct.FilmResolver.html) looks up the LID of a
+//! film by its IMDb or TMDB ID with the `imdb:` and `tmdb:` identifiers accepted by the
+//! `film/{id}` endpoint, and caches the mapping, including films which are not known to
+//! Letterboxd until they are forgotten with `forget_missing`. The mapping can be saved to a file
+//! and loaded again.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::crossref::FilmResolver;
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let client = letterboxd::Client::new(api_key_pair);
+//!     let resolver = FilmResolver::load("imdb.json")?;
+//!
+//!     for imdb_id in &["tt0137523", "tt6751668"] {
+//!         match resolver.resolve_imdb(&client, imdb_id).await? {
+//!             Some(lid) => println!("{} is {}", imdb_id, lid),
+//!             None => println!("{} is not on Letterboxd", imdb_id),
+//!         }
+//!     }
+//!     resolver.save("imdb.json")
+//! };
+//! ```
+
+use crate::client::Client;
+use crate::defs::{Film, FilmSummary, Link};
+use crate::error::{Error, Kind, Result};
+
+use hyper::StatusCode;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+impl Link {
+    /// Returns the ID of the linked entity on the destination site.
+    pub fn id(&self) -> &str {
+        match self {
+            Link::Letterboxd { id, .. }
+            | Link::Tmdb { id, .. }
+            | Link::Imdb { id, .. }
+            | Link::Gwi { id, .. } => id,
+        }
+    }
+
+    /// Returns the URL of the linked entity on the destination site.
+    pub fn url(&self) -> &str {
+        match self {
+            Link::Letterboxd { url, .. }
+            | Link::Tmdb { url, .. }
+            | Link::Imdb { url, .. }
+            | Link::Gwi { url, .. } => url,
+        }
+    }
+}
+
+fn imdb_id(links: &[Link]) -> Option<&str> {
+    links.iter().find_map(|link| match link {
+        Link::Imdb { id, .. } => Some(id.as_str()),
+        _ => None,
+    })
+}
+
+fn tmdb_id(links: &[Link]) -> Option<&str> {
+    links.iter().find_map(|link| match link {
+        Link::Tmdb { id, .. } => Some(id.as_str()),
+        _ => None,
+    })
+}
+
+pub(crate) fn letterboxd_url(links: &[Link]) -> Option<&str> {
+    links.iter().find_map(|link| match link {
+        Link::Letterboxd { url, .. } => Some(url.as_str()),
+        _ => None,
+    })
+}
+
+impl Film {
+    /// Returns the IMDb ID of the film, like `tt0137523`.
+    pub fn imdb_id(&self) -> Option<&str> {
+        imdb_id(&self.links)
+    }
+
+    /// Returns the TMDB ID of the film, like `550`.
+    pub fn tmdb_id(&self) -> Option<&str> {
+        tmdb_id(&self.links)
+    }
+
+    /// Returns the URL of the film on Letterboxd.
+    pub fn letterboxd_url(&self) -> Option<&str> {
+        letterboxd_url(&self.links)
+    }
+}
+
+impl FilmSummary {
+    /// Returns the IMDb ID of the film, like `tt0137523`.
+    pub fn imdb_id(&self) -> Option<&str> {
+        imdb_id(self.links.as_deref()?)
+    }
+
+    /// Returns the TMDB ID of the film, like `550`.
+    pub fn tmdb_id(&self) -> Option<&str> {
+        tmdb_id(self.links.as_deref()?)
+    }
+
+    /// Returns the URL of the film on Letterboxd.
+    pub fn letterboxd_url(&self) -> Option<&str> {
+        letterboxd_url(self.links.as_deref()?)
+    }
+}
+
+/// The ID of a film on another site.
+///
+/// Formats and parses as the identifier accepted by the API in place of a LID, e.g.
+/// `imdb:tt0137523` or `tmdb:550`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ExternalId {
+    /// An IMDb ID, like `tt0137523`.
+    Imdb(String),
+    /// A TMDB movie ID, like `550`.
+    Tmdb(String),
+}
+
+impl ExternalId {
+    /// Creates an IMDb ID, which must be `tt` followed by digits.
+    pub fn imdb(id: &str) -> Result<Self> {
+        let id = id.trim().to_ascii_lowercase();
+        match id.strip_prefix("tt") {
+            Some(digits) if is_number(digits) => Ok(ExternalId::Imdb(id)),
+            _ => Err(Error::invalid_request(format!("invalid IMDb ID: {:?}", id))),
+        }
+    }
+
+    /// Creates a TMDB ID, which must be a number.
+    pub fn tmdb(id: &str) -> Result<Self> {
+        let id = id.trim();
+        if is_number(id) {
+            Ok(ExternalId::Tmdb(id.to_string()))
+        } else {
+            Err(Error::invalid_request(format!("invalid TMDB ID: {:?}", id)))
+        }
+    }
+
+    /// Returns the ID without the site prefix.
+    pub fn id(&self) -> &str {
+        match self {
+            ExternalId::Imdb(id) | ExternalId::Tmdb(id) => id,
+        }
+    }
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+impl fmt::Display for ExternalId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExternalId::Imdb(id) => write!(f, "imdb:{}", id),
+            ExternalId::Tmdb(id) => write!(f, "tmdb:{}", id),
+        }
+    }
+}
+
+impl FromStr for ExternalId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            Some(("imdb", id)) => ExternalId::imdb(id),
+            Some(("tmdb", id)) => ExternalId::tmdb(id),
+            _ => Err(Error::invalid_request(format!(
+                "invalid external film ID: {:?}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Resolver of IMDb and TMDB IDs to LIDs of films, with a cache of the mapping.
+#[derive(Debug, Default)]
+pub struct FilmResolver {
+    /// The LIDs by external ID, `None` if the film is not known to Letterboxd.
+    mapping: Mutex<HashMap<ExternalId, Option<String>>>,
+}
+
+impl FilmResolver {
+    /// Creates a resolver with an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a resolver with the mapping saved by [`save`](#method.save).
+    ///
+    /// A missing file results in an empty cache.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let saved: BTreeMap<String, Option<String>> = serde_json::from_slice(&bytes)?;
+        let mapping = saved
+            .into_iter()
+            .map(|(id, lid)| Ok((id.parse()?, lid)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            mapping: Mutex::new(mapping),
+        })
+    }
+
+    /// Saves the mapping as a JSON object from external IDs to LIDs.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let saved: BTreeMap<String, Option<String>> = self
+            .mapping
+            .lock()
+            .expect("poisoned lock")
+            .iter()
+            .map(|(id, lid)| (id.to_string(), lid.clone()))
+            .collect();
+        fs::write(path, serde_json::to_vec_pretty(&saved)?)?;
+        Ok(())
+    }
+
+    /// Returns the number of cached mappings.
+    pub fn len(&self) -> usize {
+        self.mapping.lock().expect("poisoned lock").len()
+    }
+
+    /// Returns whether no mapping is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the cached IDs of films which were not known to Letterboxd, so that they are
+    /// looked up again, e.g. since the films were added in the meantime.
+    ///
+    /// Returns the number of removed IDs.
+    pub fn forget_missing(&self) -> usize {
+        let mut mapping = self.mapping.lock().expect("poisoned lock");
+        let len = mapping.len();
+        mapping.retain(|_, lid| lid.is_some());
+        len - mapping.len()
+    }
+
+    /// Caches the IMDb and TMDB IDs of a film fetched otherwise, e.g. by `Client::film`.
+    pub fn record(&self, film: &Film) {
+        let mut mapping = self.mapping.lock().expect("poisoned lock");
+        let ids = film
+            .imdb_id()
+            .and_then(|id| ExternalId::imdb(id).ok())
+            .into_iter()
+            .chain(film.tmdb_id().and_then(|id| ExternalId::tmdb(id).ok()));
+        for id in ids {
+            mapping.insert(id, Some(film.id.clone()));
+        }
+    }
+
+    /// Returns the LID of a film by its IMDb or TMDB ID, or `None` if the film is not known to
+    /// Letterboxd.
+    ///
+    /// Only IDs which are not cached yet are looked up with the API. The other IDs of a looked
+    /// up film are cached as well.
+    pub async fn resolve(&self, client: &Client, id: &ExternalId) -> Result<Option<String>> {
+        if let Some(lid) = self.mapping.lock().expect("poisoned lock").get(id) {
+            return Ok(lid.clone());
+        }
+        match client.film(&id.to_string()).await {
+            Ok(film) => {
+                self.record(&film);
+                let mut mapping = self.mapping.lock().expect("poisoned lock");
+                mapping.insert(id.clone(), Some(film.id.clone()));
+                Ok(Some(film.id))
+            }
+            Err(e) if is_not_found(&e) => {
+                self.mapping
+                    .lock()
+                    .expect("poisoned lock")
+                    .insert(id.clone(), None);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the LID of a film by its IMDb ID, like `tt0137523`.
+    pub async fn resolve_imdb(&self, client: &Client, imdb_id: &str) -> Result<Option<String>> {
+        self.resolve(client, &ExternalId::imdb(imdb_id)?).await
+    }
+
+    /// Returns the LID of a film by its TMDB ID, like `550`.
+    pub async fn resolve_tmdb(&self, client: &Client, tmdb_id: &str) -> Result<Option<String>> {
+        self.resolve(client, &ExternalId::tmdb(tmdb_id)?).await
+    }
+}
+
+fn is_not_found(err: &Error) -> bool {
+    matches!(err.kind(), Kind::ServerError(status, _) if *status == StatusCode::NOT_FOUND)
+}
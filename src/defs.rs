@@ -13,10 +13,10 @@
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all_fields = "camelCase")]
 #[serde(tag = "type")]
-enum AbstractActivity {
+pub enum AbstractActivity {
     /// Common fields:
     /// member The member associated with the activity.
     /// when_created The timestamp of the activity, in ISO 8601 format with UTC
@@ -117,7 +117,7 @@ enum AbstractActivity {
     },
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "type")]
 enum AbstractComment {
@@ -195,7 +195,7 @@ enum AbstractComment {
     },
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 #[serde(tag = "type")]
 pub enum AbstractSearchItem {
     /// Common fields:
@@ -229,7 +229,7 @@ pub enum AbstractSearchItem {
     },
 }
 
-#[derive(Deserialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct AccessToken {
     /// The access token that grants the member access. Combine this with the
     /// token_type to form the Authorization header.
@@ -246,8 +246,8 @@ pub struct AccessToken {
     pub expires_in: usize,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-enum ActivityClass {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ActivityClass {
     OwnActivity,
     NotOwnActivity,
     IncomingActivity,
@@ -255,13 +255,13 @@ enum ActivityClass {
     NetworkActivity,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
-struct ActivityRequest {
+pub struct ActivityRequest {
     /// The pagination cursor.
-    cursor: Option<Cursor>,
+    pub cursor: Option<Cursor>,
     /// The number of items to include per page (default is 20, maximum is 100).
-    per_page: Option<usize>,
+    pub per_page: Option<usize>,
     /// Only supported for paying members.
     /// Use include to specify the subset of activity to be returned. If
     /// neither include nor exclude is set, the activity types included depend
@@ -273,7 +273,8 @@ struct ActivityRequest {
     /// FilmRatingActivity, FollowActivity, RegistrationActivity and
     /// InvitationAcceptedActivity is included.
     /// These defaults mimic those shown on the website.
-    include: Option<Vec<ActivityType>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<ActivityType>,
     /// Use where to reduce the subset of activity to be returned. If where is
     /// not set, all default activity types relating to the member are
     /// returned. If multiple values are supplied, only activity matching all
@@ -287,11 +288,11 @@ struct ActivityRequest {
     /// to the member’s content from members outside their network (e.g.
     /// comments and likes on the member’s lists and reviews).
     #[serde(rename = "where")]
-    where_activity: Option<Vec<ActivityClass>>,
+    pub where_activity: Vec<ActivityClass>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-enum ActivityType {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ActivityType {
     ReviewActivity,
     ReviewCommentActivity,
     ReviewLikeActivity,
@@ -308,12 +309,12 @@ enum ActivityType {
     InvitationAcceptedActivity,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-struct ActivityResponse {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActivityResponse {
     /// The cursor to the next page of results.
-    next: Option<Cursor>,
+    pub next: Option<Cursor>,
     /// The list of activity items.
-    items: Vec<AbstractActivity>,
+    pub items: Vec<AbstractActivity>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -324,7 +325,13 @@ pub struct CommentCreationRequest {
     comment: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+impl CommentCreationRequest {
+    pub fn new(comment: String) -> Self {
+        Self { comment }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum CommentUpdateMessageCode {
     MissingComment,
     CommentOnContentYouBlocked,
@@ -334,7 +341,7 @@ enum CommentUpdateMessageCode {
     CommentTooLong,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 enum CommentUpdateMessage {
     Error {
@@ -346,7 +353,7 @@ enum CommentUpdateMessage {
     Success,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct CommentUpdateRequest {
     /// The message portion of the comment in LBML. May contain the following
     /// HTML tags: `<br>` `<strong>` `<em>` `<b>` `<i>` `<a href="">`
@@ -354,7 +361,7 @@ struct CommentUpdateRequest {
     comment: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct CommentUpdateResponse {
     /// The response object.
     data: AbstractComment,
@@ -363,13 +370,13 @@ struct CommentUpdateResponse {
 }
 
 // TODO: Ordering
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum CommentsRequestSort {
     Date,
     Updates,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct CommentsRequest {
     /// The pagination cursor.
@@ -385,7 +392,7 @@ struct CommentsRequest {
     include_deletions: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct ContributionStatistics {
     /// The type of contribution.
@@ -395,7 +402,7 @@ struct ContributionStatistics {
     film_count: usize,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ContributionType {
     Director,
     Actor,
@@ -412,7 +419,7 @@ pub enum ContributionType {
     Studio,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Contributor {
     /// The LID of the contributor.
     pub id: String,
@@ -426,13 +433,13 @@ pub struct Contributor {
     pub links: Vec<Link>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ContributorStatistics {
     // The statistics for each contribution type.
     contributions: Vec<ContributionStatistics>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ContributorSummary {
     /// The LID of the contributor.
@@ -448,7 +455,7 @@ pub struct ContributorSummary {
 /// opaque value — don’t change it.
 pub type Cursor = String;
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DiaryDetails {
     /// The date the film was watched, if specified, in ISO 8601 format, i.e.
@@ -459,7 +466,7 @@ pub struct DiaryDetails {
     pub rewatch: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Film {
     /// The LID of the film.
@@ -500,7 +507,7 @@ pub struct Film {
     pub links: Vec<Link>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct FilmAutocompleteRequest {
     /// The number of items to include per page (default is 20, maximum is 100).
@@ -509,7 +516,7 @@ struct FilmAutocompleteRequest {
     input: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum FilmAvailabilityService {
     Amazon,
     AmazonVideo,
@@ -519,7 +526,7 @@ pub enum FilmAvailabilityService {
     Netflix,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FilmAvailability {
     /// The service.
@@ -535,8 +542,8 @@ pub struct FilmAvailability {
     pub url: String,
 }
 
-// TODO: order
-#[derive(Deserialize, Debug, Clone)]
+/// A country by its ISO 3166-1 alpha-3 code, see the `country` module.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Country {
     AIA,
     ARE,
@@ -660,7 +667,7 @@ pub enum Country {
     ZWE,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FilmAvailabilityResponse {
     /// The list of stores where the film is available for streaming or
@@ -669,7 +676,7 @@ pub struct FilmAvailabilityResponse {
     pub items: Option<Vec<FilmAvailability>>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct FilmContribution {
     /// The type of contribution.
@@ -681,10 +688,11 @@ struct FilmContribution {
     character_name: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FilmContributions {
     /// The type of contribution.
+    #[serde(rename = "type")]
     pub contribution_type: Option<ContributionType>,
     /// The list of contributors of the specified type for the film.
     pub contributors: Vec<ContributorSummary>,
@@ -786,7 +794,7 @@ struct FilmContributionsRequest {
     include_tagger_friends: IncludeFriends,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct FilmContributionsResponse {
     /// The cursor to the next page of results.
     next: Option<Cursor>,
@@ -794,7 +802,7 @@ struct FilmContributionsResponse {
     items: Vec<FilmContribution>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FilmIdentifier {
     /// The LID of the film.
     pub id: String,
@@ -823,13 +831,13 @@ pub struct FilmRelationship {
     pub diary_entries: Vec<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum FilmRelationshipUpdateMessageCode {
     InvalidRatingValue,
     UnableToRemoveWatch,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum FilmRelationshipUpdateMessage {
     Error {
@@ -866,7 +874,7 @@ pub struct FilmRelationshipUpdateRequest {
     pub rating: Option<f32>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FilmRelationshipUpdateResponse {
     /// The response object.
     pub data: FilmRelationship,
@@ -874,13 +882,13 @@ pub struct FilmRelationshipUpdateResponse {
     pub messages: Vec<FilmRelationshipUpdateMessage>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FilmServicesResponse {
     // The list of film services.
     pub items: Vec<Service>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FilmStatistics {
     /// The film for which statistics were requested.
@@ -895,7 +903,7 @@ pub struct FilmStatistics {
     pub ratings_histogram: Vec<RatingsHistogramBar>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FilmStatisticsCounts {
     /// The number of members who have watched the film.
     pub watches: usize,
@@ -911,7 +919,7 @@ pub struct FilmStatisticsCounts {
     pub reviews: usize,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FilmSummary {
     /// The LID of the film.
@@ -933,9 +941,12 @@ pub struct FilmSummary {
     /// Relationships to the film for the authenticated member (if any) and
     /// other members where relevant.
     pub relationships: Vec<MemberFilmRelationship>,
+    /// A list of relevant URLs to this entity, on Letterboxd and external
+    /// sites.
+    pub links: Option<Vec<Link>>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FilmTrailer {
     /// The YouTube ID of the trailer. "ICp4g9p_rgo".
     pub id: String,
@@ -944,7 +955,7 @@ pub struct FilmTrailer {
     pub url: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct FilmsAutocompleteResponse {
     // The list of films.
     items: Vec<FilmSummary>,
@@ -1022,7 +1033,7 @@ pub struct FilmsRequest {
     pub include_tagger_friends: Option<IncludeFriends>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FilmsResponse {
     /// The cursor to the next page of results.
     pub next: Option<Cursor>,
@@ -1030,13 +1041,13 @@ pub struct FilmsResponse {
     pub items: Vec<FilmSummary>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct ForgottenPasswordRequest {
     email_address: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Genre {
     /// The LID of the genre.
     pub id: String,
@@ -1044,19 +1055,19 @@ pub struct Genre {
     pub name: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GenresResponse {
     /// The list of genres.
     pub items: Vec<Genre>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Image {
     /// The available sizes for the image.
     pub sizes: Vec<ImageSize>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ImageSize {
     /// The image width in pixels.
     pub width: usize,
@@ -1066,7 +1077,7 @@ pub struct ImageSize {
     pub url: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum Link {
     Letterboxd {
@@ -1095,7 +1106,7 @@ pub enum Link {
     },
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct List {
     /// The LID of the list.
@@ -1142,46 +1153,46 @@ pub struct List {
     pub description: Option<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct ListComment {
+pub struct ListComment {
     /// The LID of the comment.
-    id: String,
+    pub id: String,
     /// The member who posted the comment.
-    member: MemberSummary,
+    pub member: MemberSummary,
     /// ISO 8601 format with UTC timezone, i.e. YYYY-MM-DDThh:mm:ssZ
     /// "1997-08-29T07:14:00Z"
-    when_created: String,
+    pub when_created: String,
     /// ISO 8601 format with UTC timezone, i.e. YYYY-MM-DDThh:mm:ssZ
     /// "1997-08-29T07:14:00Z"
-    when_updated: String,
+    pub when_updated: String,
     /// The message portion of the comment in LBML. May contain the following
     /// HTML tags: `<br>` `<strong>` `<em>` `<b>` `<i>` `<a href="">`
     /// `<blockquote>`.
-    comment_lbml: String,
+    pub comment_lbml: String,
     /// If Letterboxd moderators have removed the comment from the site,
     /// removedByAdmin will be true and comment will not be included.
-    removed_by_admin: bool,
+    pub removed_by_admin: bool,
     /// If the comment owner has removed the comment from the site, deleted
     /// will be true and comment will not be included.
-    deleted: bool,
+    pub deleted: bool,
     /// If the authenticated member has blocked the commenter, blocked will be
     /// true and comment will not be included.
-    blocked: bool,
+    pub blocked: bool,
     /// If the list owner has blocked the commenter, blockedByOwner will be
     /// true and comment will not be included.
-    blocked_by_owner: bool,
+    pub blocked_by_owner: bool,
     /// If the authenticated member posted this comment, and the comment is
     /// still editable, this value shows the number of seconds remaining until
     /// the editing window closes.
-    editable_window_expires_in: Option<usize>,
+    pub editable_window_expires_in: Option<usize>,
     /// The list on which the comment was posted.
-    list: ListIdentifier,
+    pub list: ListIdentifier,
     /// The message portion of the comment formatted as HTML.
-    comment: String,
+    pub comment: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ListCommentsResponse {
     /// The cursor to the next page of results.
     next: Option<Cursor>,
@@ -1193,19 +1204,33 @@ struct ListCommentsResponse {
 #[serde(rename_all = "camelCase")]
 pub struct ListCreateEntry {
     /// The LID of the film.
-    film: String,
+    pub film: String,
     /// The entry’s rank in the list, numbered from 1. If not set, the entry
     /// will be appended to the end of the list. Sending two or more
     /// ListCreateEntrys with the same rank will return an error.
-    rank: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<usize>,
     /// The notes for the list entry in LBML. May contain the following HTML tags: `<br>` `<strong>` `<em>` `<b>` `<i>` `<a href="">` `<blockquote>`.
-    notes: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
     /// Set to true if the member has indicated that the notes field contains
     /// plot spoilers for the film.
-    contains_spoilers: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains_spoilers: Option<bool>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+impl ListCreateEntry {
+    pub fn new(film: String) -> ListCreateEntry {
+        ListCreateEntry {
+            film,
+            rank: None,
+            notes: None,
+            contains_spoilers: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ListCreateMessageCode {
     ListNameIsBlank,
     UnknownFilmCode,
@@ -1219,7 +1244,7 @@ pub enum ListCreateMessageCode {
     ListEntryNotesTooLong,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum ListCreateMessage {
     Error {
@@ -1231,7 +1256,7 @@ pub enum ListCreateMessage {
     Success,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ListCreateResponse {
     /// The response object.
     pub data: List,
@@ -1242,29 +1267,29 @@ pub struct ListCreateResponse {
 #[derive(Serialize, Debug, Clone)]
 pub struct ListCreationRequest {
     /// The name of the list.
-    name: String,
+    pub name: String,
     /// Set to true if the owner has elected to publish the list for other
     /// members to see.
-    published: bool,
+    pub published: bool,
     /// Set to true if the owner has elected to make this a ranked list.
-    ranked: bool,
+    pub ranked: bool,
     /// The list description in LBML. May contain the following HTML tags:
     /// `<br>` `<strong>` `<em>` `<b>` `<i>` `<a href="">` `<blockquote>`. This
     /// field has a maximum size of 100,000 characters.
     #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
+    pub description: Option<String>,
     /// The LID of a list to clone from. Only supported for paying members.
     #[serde(skip_serializing_if = "Option::is_none")]
-    cloned_from: Option<String>,
+    pub cloned_from: Option<String>,
     // The tags for the list.
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    tags: Vec<String>,
+    pub tags: Vec<String>,
     /// The films that comprise the list. Required unless source is set.
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    entries: Vec<ListCreateEntry>,
+    pub entries: Vec<ListCreateEntry>,
     /// The third-party service or services to which this list should be shared. Valid options are found in the MemberAccount.authorizedSharingServicesForLists (see the /me endpoint).
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    share: Vec<ThirdPartyService>,
+    pub share: Vec<ThirdPartyService>,
 }
 
 impl ListCreationRequest {
@@ -1349,7 +1374,7 @@ pub struct ListEntriesRequest {
     pub include_tagger_friends: Option<IncludeFriends>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ListEntriesResponse {
     ///     The cursor to the next page of results.
     pub next: Option<Cursor>,
@@ -1357,7 +1382,7 @@ pub struct ListEntriesResponse {
     pub items: Vec<ListEntry>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ListEntry {
     /// The entry’s rank in the list, numbered from 1.
@@ -1374,7 +1399,7 @@ pub struct ListEntry {
     pub notes: Option<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ListEntrySummary {
     /// The entry’s rank in the list, numbered from 1.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1383,13 +1408,13 @@ pub struct ListEntrySummary {
     pub film: FilmSummary,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ListIdentifier {
     /// The LID of the list.
     pub id: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct ListRelationship {
     /// Will be true if the member likes the list (via the ‘heart’ icon). A
@@ -1410,7 +1435,7 @@ struct ListRelationship {
     comment_thread_state: CommentThreadState,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum ListRelationshipUpdateMessageCode {
     LikeBlockedContent,
     LikeOwnList,
@@ -1419,7 +1444,7 @@ enum ListRelationshipUpdateMessageCode {
     SubscribeToBlockedContent,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 enum ListRelationshipUpdateMessage {
     Error {
@@ -1431,7 +1456,7 @@ enum ListRelationshipUpdateMessage {
     Success,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ListRelationshipUpdateRequest {
     /// Set to true if the member likes the list (via the ‘heart’ icon). A
     /// member may not like their own list.
@@ -1440,7 +1465,7 @@ struct ListRelationshipUpdateRequest {
     subscribed: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ListRelationshipUpdateResponse {
     /// The response object.
     data: ListRelationship,
@@ -1448,7 +1473,7 @@ struct ListRelationshipUpdateResponse {
     messages: Vec<ListRelationshipUpdateMessage>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ListStatistics {
     /// The list for which statistics were requested.
     list: ListIdentifier,
@@ -1456,7 +1481,7 @@ struct ListStatistics {
     counts: ListStatisticsCounts,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ListStatisticsCounts {
     /// The number of comments for the list.
     comments: usize,
@@ -1464,7 +1489,7 @@ struct ListStatisticsCounts {
     likes: usize,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSummary {
     /// The LID of the list.
@@ -1531,7 +1556,7 @@ impl ListUpdateEntry {
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ListUpdateMessageCode {
     ListNameIsBlank,
     UnknownFilmCode,
@@ -1544,7 +1569,7 @@ pub enum ListUpdateMessageCode {
     ListEntryNotesTooLong,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum ListUpdateMessage {
     Error {
@@ -1605,7 +1630,7 @@ impl ListUpdateRequest {
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ListUpdateResponse {
     /// The response object.
     pub data: List,
@@ -1691,16 +1716,16 @@ pub struct ListsRequest {
     pub filter: Vec<ListRequestFilter>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ListsResponse {
     /// The cursor to the next page of results.
-    next: Option<Cursor>,
+    pub next: Option<Cursor>,
     /// The list of lists.
-    items: Vec<ListSummary>,
+    pub items: Vec<ListSummary>,
 }
 
 #[derive(Serialize, Debug, Clone)]
-enum LogEntriesRequestSort {
+pub enum LogEntriesRequestSort {
     WhenAdded,
     Date,
     RatingHighToLow,
@@ -1729,13 +1754,13 @@ enum LogEntriesRequestSort {
 }
 
 #[derive(Serialize, Debug, Clone)]
-enum LogEntryRelationshipType {
+pub enum LogEntryRelationshipType {
     Owner,
     Liked,
 }
 
 #[derive(Serialize, Debug, Clone)]
-enum LogEntryStatus {
+pub enum LogEntryStatus {
     HasDiaryDate,
     HasReview,
     Clean,
@@ -1753,16 +1778,17 @@ enum LogEntryStatus {
 }
 
 #[derive(Serialize, Debug, Clone)]
-enum LogEntryFilter {
+pub enum LogEntryFilter {
     NoDuplicateMembers,
 }
 
-#[derive(Serialize, Debug, Clone)]
-struct LogEntriesRequest {
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntriesRequest {
     /// The pagination cursor.
-    cursor: Option<Cursor>,
+    pub cursor: Option<Cursor>,
     /// The number of items to include per page (default is 20, maximum is 100).
-    per_page: Option<usize>,
+    pub per_page: Option<usize>,
     /// The order in which the log entries should be returned. Defaults to
     /// WhenAdded, which orders by creation date, unless you specify
     /// where=HasDiaryDate in which case the default is Date.
@@ -1776,75 +1802,75 @@ struct LogEntriesRequest {
     /// You may not specify a film when using ReleaseDateLatestFirst,
     /// ReleaseDateEarliestFirst, FilmName, FilmDurationShortestFirst,
     /// FilmDurationLongestFirst, or any of the FilmPopularity options.
-    sort: LogEntriesRequestSort,
+    pub sort: Option<LogEntriesRequestSort>,
     /// Specify the LID of a film to return log entries for that film. Must not
     /// be included if the sort value is ReleaseDateLatestFirst,
     /// ReleaseDateEarliestFirst, FilmName, FilmDurationShortestFirst,
     /// FilmDurationLongestFirst, or any of the FilmPopularity options.
-    film: String,
+    pub film: Option<String>,
     /// Specify the LID of a member to limit the returned log entries according
     /// to the value set in memberRelationship.
-    member: String,
+    pub member: Option<String>,
     /// Must be used in conjunction with member. Use Owner to limit the
     /// returned log entries to those created by the specified member. Use
     /// Liked to limit the returned reviews to those liked by the specified
     /// member (implies where=HasReview).
-    member_relationship: LogEntryRelationshipType,
+    pub member_relationship: Option<LogEntryRelationshipType>,
     /// Must be used in conjunction with member. Specify the type of
     /// relationship to limit the returned films accordingly. e.g. Use Liked to
     /// limit the returned reviews to those for films liked by the member.
-    film_member_relationship: FilmRelationshipType,
+    pub film_member_relationship: Option<FilmRelationshipType>,
     /// Must be used in conjunction with member. Defaults to None, which only
     /// returns log entries created or liked by the member. Use Only to return
     /// log entries created or liked by the member’s friends, and All to return
     /// log entries created or liked by both the member and their friends.
-    include_friends: IncludeFriends,
+    pub include_friends: Option<IncludeFriends>,
     /// If set, limits the returned log entries to those with date that falls
     /// during the specified year.
-    year: u16,
+    pub year: Option<u16>,
     /// Accepts values of 1 through 12. Must be used with year. If set, limits
     /// the returned log entries to those with a date that falls during the
     /// specified month and year.
-    month: u16,
+    pub month: Option<u16>,
     /// Accepts values of 1 through 52. Must be used with year. If set, limits
     /// the returned log entries to those with a date that falls during the
     /// specified week and year.
-    week: u16,
+    pub week: Option<u16>,
     /// Accepts values of 1 through 31. Must be used with month and year. If
     /// set, limits the returned log entries to those with a date that falls on
     /// the specified day, month and year.
-    day: u16,
+    pub day: Option<u16>,
     /// Allowable values are between 0.5 and 5.0, with increments of 0.5. If
     /// set, limits the returned log entries to those with a rating equal to or
     /// higher than the specified rating.
-    min_rating: f32,
+    pub min_rating: Option<f32>,
     /// Allowable values are between 0.5 and 5.0, with increments of 0.5. If
     /// set, limits the returned log entries to those with a rating equal to or
     /// lower than the specified rating.
-    max_rating: f32,
+    pub max_rating: Option<f32>,
     /// Specify the starting year of a decade (must end in 0) to limit films to
     /// those released during the decade. 1990
-    film_decade: u16,
+    pub film_decade: Option<u16>,
     /// Specify a year to limit films to those released during that year. 1994
-    film_year: u16,
+    pub film_year: Option<u16>,
     /// The LID of the genre. If set, limits the returned log entries to those
     /// for films that match the specified genre.
-    genre: String,
+    pub genre: Option<String>,
     /// Specify a tag code to limit the returned log entries to those tagged
     /// accordingly.
-    tag_code: String,
+    pub tag_code: Option<String>,
     /// Must be used with tag. Specify the LID of a member to focus the tag
     /// filter on the member.
-    tagger: String,
+    pub tagger: Option<String>,
     /// Must be used in conjunction with tagger. Defaults to None, which
     /// filters tags set by the member. Use Only to filter tags set by the
     /// member’s friends, and All to filter tags set by both the member and
     /// their friends.
-    include_tagger_friends: IncludeFriends,
+    pub include_tagger_friends: Option<IncludeFriends>,
     /// Specify the ID of a supported service to limit films to those available
     /// from that service. The list of available services can be found by using
     /// the /films/film-services endpoint.
-    service: String,
+    pub service: Option<String>,
     /// Specify one or more values to limit the returned log entries
     /// accordingly. All values except HasDiaryDate, HasReview, Clean and
     /// NoSpoilers refer to properties of the associated film rather than to
@@ -1855,21 +1881,21 @@ struct LogEntriesRequest {
     /// exclude reviews where the owner has indicated that the review text
     /// contains plot spoilers for the film. where=Clean&where=NoSpoilers
     #[serde(rename = "where")]
-    where_logentry_status: Vec<LogEntryStatus>,
+    pub where_logentry_status: Vec<LogEntryStatus>,
     /// Specify NoDuplicateMembers to return only the first log entry for each
     /// member. filter=NoDuplicateMembers
-    filter: Vec<LogEntryFilter>,
+    pub filter: Vec<LogEntryFilter>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-struct LogEntriesResponse {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogEntriesResponse {
     /// The cursor to the next page of results.
-    next: Option<Cursor>,
-    // The list of log entries.
-    items: Vec<LogEntry>,
+    pub next: Option<Cursor>,
+    /// The list of log entries.
+    pub items: Vec<LogEntry>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LogEntry {
     /// The LID of the log entry.
@@ -1895,8 +1921,9 @@ pub struct LogEntry {
     /// "1997-08-29T07:14:00Z"
     pub when_updated: String,
     /// The member’s rating for the film. Allowable values are between 0.5 and
-    /// 5.0, with increments of 0.5.
-    pub rating: f32,
+    /// 5.0, with increments of 0.5. Not present if the member did not rate
+    /// the film with this log entry.
+    pub rating: Option<f32>,
     /// Will be true if the member likes the film (via the ‘heart’ icon).
     pub like: bool,
     /// Will be true if the log entry can have comments.
@@ -1906,52 +1933,71 @@ pub struct LogEntry {
     pub links: Vec<Link>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct LogEntryCreationRequest {
+pub struct LogEntryCreationRequest {
     /// The film being logged.
-    film_id: String,
+    pub film_id: String,
     /// Information about this log entry if adding to the member’s diary.
-    diary_details: LogEntryCreationRequestDiaryDetails,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diary_details: Option<LogEntryCreationRequestDiaryDetails>,
     /// Information about the review if adding a review.
-    review: LogEntryCreationRequestReview,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review: Option<LogEntryCreationRequestReview>,
     ///  The tags for the log entry.
-    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// Allowable values are between 0.5 and 5.0, with increments of 0.5.
-    rating: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<f32>,
     /// Set to true if the member likes the film (via the ‘heart’ icon).
-    like: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub like: Option<bool>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+impl LogEntryCreationRequest {
+    pub fn new(film_id: String) -> LogEntryCreationRequest {
+        LogEntryCreationRequest {
+            film_id,
+            diary_details: None,
+            review: None,
+            tags: Vec::new(),
+            rating: None,
+            like: None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct LogEntryCreationRequestDiaryDetails {
+pub struct LogEntryCreationRequestDiaryDetails {
     /// The date the film was watched, if specified, in ISO 8601 format, i.e.
     /// YYYY-MM-DD
-    diary_date: String,
+    pub diary_date: String,
     /// Set to true if the member has indicated (or it can be otherwise
     /// determined) that the member has seen the film prior to this date.
-    rewatch: bool,
+    pub rewatch: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct LogEntryCreationRequestReview {
+pub struct LogEntryCreationRequestReview {
     /// The review text in LBML. May contain the following HTML tags: `<br>`
     /// `<strong>` `<em>` `<b>` `<i>` `<a href="">` `<blockquote>`. This field
     /// has a maximum size of 100,000 characters.
-    text: String,
+    pub text: String,
     /// Set to true if the member has indicated that the review field contains
     /// plot spoilers for the film.
-    contains_spoilers: bool,
+    pub contains_spoilers: bool,
     /// The third-party service or services to which this review should be
     /// shared. Valid options are found in the
     /// MemberAccount.authorizedSharingServicesForReviews (see the /me
     /// endpoint).
-    share: Vec<ThirdPartyService>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub share: Vec<ThirdPartyService>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum LogEntryUpdateMessageCode {
     InvalidRatingValue,
     InvalidDiaryDate,
@@ -1960,7 +2006,7 @@ enum LogEntryUpdateMessageCode {
     LogEntryWithNoReviewOrDiaryDetails,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 enum LogEntryUpdateMessage {
     Error {
@@ -1972,7 +2018,7 @@ enum LogEntryUpdateMessage {
     Success,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct LogEntryUpdateRequest {
     /// Information about this log entry if adding to the member’s diary. Set
@@ -1990,7 +2036,7 @@ struct LogEntryUpdateRequest {
     like: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct LogEntryUpdateRequestDiaryDetails {
     /// The date the film was watched, if specified, in ISO 8601 format, i.e.
@@ -2001,7 +2047,7 @@ struct LogEntryUpdateRequestDiaryDetails {
     rewatch: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct LogEntryUpdateRequestReview {
     /// The review text in LBML. May contain the following HTML tags: `<br>`
@@ -2014,7 +2060,7 @@ struct LogEntryUpdateRequestReview {
     share: Vec<ThirdPartyService>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct Member {
     /// The LID of the member.
@@ -2069,7 +2115,7 @@ struct Member {
     bio: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct MemberAccount {
     /// The member’s email address.
@@ -2129,7 +2175,7 @@ struct MemberAccount {
     member: Member,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MemberFilmRelationship {
     /// The member.
     pub member: MemberSummary,
@@ -2138,7 +2184,7 @@ pub struct MemberFilmRelationship {
 }
 
 // TODO: dedup and order
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum MemberRelationshipType {
     IsFollowing,
     IsFollowedBy,
@@ -2194,7 +2240,7 @@ pub struct MemberFilmRelationshipsRequest {
     pub film_relationship: Option<FilmRelationshipType>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MemberFilmRelationshipsResponse {
     /// The cursor to the next page of results.
     pub next: Cursor,
@@ -2202,13 +2248,13 @@ pub struct MemberFilmRelationshipsResponse {
     pub items: Vec<MemberFilmRelationship>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct MemberIdentifier {
     /// The LID of the member.
     id: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct MemberRelationship {
     /// Will be true if the authenticated member follows the member identified
@@ -2225,7 +2271,7 @@ struct MemberRelationship {
     blocked_by: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum MemberRelationshipUpdateMessageCode {
     BlockYourself,
     FollowYourself,
@@ -2233,7 +2279,7 @@ enum MemberRelationshipUpdateMessageCode {
     FollowMemberYouBlocked,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 enum MemberRelationshipUpdateMessage {
     Error {
@@ -2245,7 +2291,7 @@ enum MemberRelationshipUpdateMessage {
     Success,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct MemberRelationshipUpdateRequest {
     /// Set to true if the authenticated member wishes to follow the member
     /// identified by ID, or false if they wish to unfollow. A member may not
@@ -2258,7 +2304,7 @@ struct MemberRelationshipUpdateRequest {
     blocking: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct MemberRelationshipUpdateResponse {
     /// The response object.
     data: MemberRelationship,
@@ -2266,7 +2312,7 @@ struct MemberRelationshipUpdateResponse {
     messages: Vec<MemberRelationshipUpdateMessage>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum MemberSettingsUpdateMessageCode {
     IncorrectCurrentPassword,
     BlankPassword,
@@ -2276,7 +2322,7 @@ enum MemberSettingsUpdateMessageCode {
     InvalidPronounOption,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 enum MemberSettingsUpdateMessage {
     Error {
@@ -2288,7 +2334,7 @@ enum MemberSettingsUpdateMessage {
     Success,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct MemberSettingsUpdateRequest {
     /// The member’s email address.
@@ -2336,7 +2382,7 @@ struct MemberSettingsUpdateRequest {
     email_rushes: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct MemberSettingsUpdateResponse {
     /// The response object.
     data: MemberAccount,
@@ -2344,7 +2390,7 @@ struct MemberSettingsUpdateResponse {
     messages: Vec<MemberSettingsUpdateMessage>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct MemberStatistics {
     /// The member for which statistics were requested.
@@ -2361,7 +2407,7 @@ struct MemberStatistics {
     years_in_review: Vec<u16>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct MemberStatisticsCounts {
     /// The number of films the member has liked.
@@ -2405,7 +2451,7 @@ struct MemberStatisticsCounts {
     film_tags: usize,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 pub enum MemberStatus {
     Crew,
     Patron,
@@ -2413,7 +2459,7 @@ pub enum MemberStatus {
     Member,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MemberSummary {
     /// The LID of the member.
@@ -2445,7 +2491,7 @@ pub struct MemberSummary {
     pub member_status: MemberStatus,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct MemberTag {
     /// The tag code.
@@ -2456,7 +2502,7 @@ struct MemberTag {
     counts: MemberTagCounts,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct MemberTagCounts {
     /// The number of films the member has used this tag on.
@@ -2478,7 +2524,7 @@ struct MemberTagsRequest {
     input: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 struct MemberTagsResponse {
     /// The list of tag items, ordered by frequency of use.
     items: Vec<MemberTag>,
@@ -2550,7 +2596,7 @@ struct MembersRequest {
     review: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 struct MembersResponse {
     /// The cursor to the next page of results.
     next: Option<Cursor>,
@@ -2558,13 +2604,13 @@ struct MembersResponse {
     items: Vec<MemberSummary>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 struct OAuthError {
     /// The error code, usually invalid_grant.
     error: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Pronoun {
     /// The LID for this pronoun set.
@@ -2590,14 +2636,14 @@ pub struct Pronoun {
     pub reflexive: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct PronounsResponse {
+pub struct PronounsResponse {
     /// The list of pronouns.
-    items: Vec<Pronoun>,
+    pub items: Vec<Pronoun>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Serialize, Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RatingsHistogramBar {
     /// The rating increment between 0.5 and 5.0.
@@ -2714,7 +2760,7 @@ pub enum ThirdPartyService {
     Facebook,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Review {
     /// The review text in LBML. May contain the following HTML tags: `<br>`
@@ -2736,46 +2782,46 @@ pub struct Review {
     pub text: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct ReviewComment {
+pub struct ReviewComment {
     /// The LID of the comment.
-    id: String,
+    pub id: String,
     /// The member who posted the comment.
-    member: MemberSummary,
+    pub member: MemberSummary,
     /// ISO 8601 format with UTC timezone, i.e. YYYY-MM-DDThh:mm:ssZ
     /// "1997-08-29T07:14:00Z"
-    when_created: String,
+    pub when_created: String,
     /// ISO 8601 format with UTC timezone, i.e. YYYY-MM-DDThh:mm:ssZ
     /// "1997-08-29T07:14:00Z"
-    when_updated: String,
+    pub when_updated: String,
     /// The message portion of the comment in LBML. May contain the following
     /// HTML tags: `<br>` `<strong>` `<em>` `<b>` `<i>` `<a href="">`
     /// `<blockquote>`.
-    comment_lbml: String,
+    pub comment_lbml: String,
     /// If Letterboxd moderators have removed the comment from the site,
     /// removedByAdmin will be true and comment will not be included.
-    removed_by_admin: bool,
+    pub removed_by_admin: bool,
     /// If the comment owner has removed the comment from the site, deleted
     /// will be true and comment will not be included.
-    deleted: bool,
+    pub deleted: bool,
     /// If the authenticated member has blocked the commenter, blocked will be
     /// true and comment will not be included.
-    blocked: bool,
+    pub blocked: bool,
     /// If the review owner has blocked the commenter, blockedByOwner will be
     /// true and comment will not be included.
-    blocked_by_owner: bool,
+    pub blocked_by_owner: bool,
     /// If the authenticated member posted this comment, and the comment is
     /// still editable, this value shows the number of seconds remaining until
     /// the editing window closes.
-    editable_window_expires_in: Option<usize>,
+    pub editable_window_expires_in: Option<usize>,
     /// The review on which the comment was posted.
-    review: ReviewIdentifier,
+    pub review: ReviewIdentifier,
     /// The message portion of the comment formatted as HTML.
-    comment: String,
+    pub comment: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ReviewCommentsResponse {
     /// The cursor to the next page of results.
     next: Option<Cursor>,
@@ -2783,14 +2829,14 @@ struct ReviewCommentsResponse {
     items: Vec<ReviewComment>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-struct ReviewIdentifier {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReviewIdentifier {
     /// The LID of the log entry.
-    id: String,
+    pub id: String,
 }
 
 // TODO: order
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum CommentThreadState {
     /// `CanComment` means the authenticated member is authorized to add a
     /// comment. All other
@@ -2813,14 +2859,14 @@ enum CommentThreadState {
 /// indicate to the member how their subscription state will be affected
 /// if/when they post a
 /// comment.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum SubscriptionState {
     Subscribed,
     NotSubscribed,
     Unsubscribed,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct ReviewRelationship {
     /// Will be true if the member likes the review (via the ‘heart’ icon). A
@@ -2841,7 +2887,7 @@ struct ReviewRelationship {
     comment_thread_state: CommentThreadState,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum ReviewRelationshipUpdateMessageCode {
     LikeBlockedContent,
     LikeOwnReview,
@@ -2851,7 +2897,7 @@ enum ReviewRelationshipUpdateMessageCode {
     SubscribeToBlockedContent,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 enum ReviewRelationshipUpdateMessage {
     Error {
@@ -2875,7 +2921,7 @@ struct ReviewRelationshipUpdateRequest {
     subscribed: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ReviewRelationshipUpdateResponse {
     /// The response object.
     data: ReviewRelationship,
@@ -2883,7 +2929,7 @@ struct ReviewRelationshipUpdateResponse {
     messages: Vec<ReviewRelationshipUpdateMessage>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct ReviewStatistics {
     /// The log entry for which statistics were requested.
@@ -2892,7 +2938,7 @@ struct ReviewStatistics {
     counts: ReviewStatisticsCounts,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ReviewStatisticsCounts {
     /// The number of comments for the review.
     comments: usize,
@@ -2900,7 +2946,7 @@ struct ReviewStatisticsCounts {
     likes: usize,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ReviewUpdateResponse {
     /// The response object.
     data: LogEntry,
@@ -2947,7 +2993,7 @@ impl SearchRequest {
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SearchResponse {
     /// The cursor to the next page of results.
     pub next: Option<Cursor>,
@@ -2966,7 +3012,7 @@ pub enum SearchResultType {
     TagSearchItem,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Service {
     /// The LID of the service.
     pub id: String,
@@ -2974,7 +3020,7 @@ pub struct Service {
     pub name: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Tag {
     /// The tag code.
@@ -2983,13 +3029,13 @@ pub struct Tag {
     pub display_tag: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct TagsResponse {
     /// The list of tags, ordered by frequency of use.
     items: Vec<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 enum UsernameCheckResult {
     Available,
     NotAvailable,
@@ -2998,7 +3044,7 @@ enum UsernameCheckResult {
     Invalid,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct UsernameCheckResponse {
     /// Will be Available if the username is available to register, or
     /// NotAvailable if used by another member (or attached to a deactivated
@@ -3033,55 +3079,55 @@ pub enum IncludeFriends {
     Only,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
-struct WatchlistRequest {
+pub struct WatchlistRequest {
     /// The pagination cursor.
-    cursor: Option<Cursor>,
+    pub cursor: Option<Cursor>,
     /// The number of items to include per page (default is 20, maximum is 100).
-    per_page: Option<usize>,
+    pub per_page: Option<usize>,
     /// The order in which the entries should be returned. Defaults to Added,
     /// which is the order that the films were added to the watchlist, most
     /// recent first.
-    sort: WatchlistSort,
+    pub sort: Option<WatchlistSort>,
     /// Specify the LID of a genre to limit films to those within the specified
     /// genre.
-    genre: String,
+    pub genre: Option<String>,
     /// Specify the starting year of a decade (must end in 0) to limit films to
     /// those released during the decade. 1990
-    decade: u16,
+    pub decade: Option<u16>,
     /// Specify a year to limit films to those released during that year. 1994
-    year: u16,
+    pub year: Option<u16>,
     /// Specify the ID of a supported service to limit films to those available
     /// from that service. The list of available services can be found by using
     /// the /films/film-services endpoint.
-    service: String,
+    pub service: Option<String>,
     /// Specify one or more values to limit the list of films accordingly.
     /// where=Watched&where=Released
     #[serde(rename = "where")]
-    where_film_status: Vec<FilmStatus>,
+    pub where_film_status: Vec<FilmStatus>,
     /// Specify the LID of a member to limit the returned films according to
     /// the value set in memberRelationship. The member and memberRelationship
     /// parameters can be used to compute comparisons between the watchlist
     /// owner and another member.
-    member: String,
+    pub member: Option<String>,
     /// Must be used in conjunction with member. Defaults to Watched. Specify
     /// the type of relationship to limit the returned films accordingly.
-    member_relationship: FilmRelationshipType,
+    pub member_relationship: Option<FilmRelationshipType>,
     /// Must be used in conjunction with member. Defaults to None, which only
     /// returns films from the member’s account. Use Only to return films from
     /// the member’s friends, and All to return films from both the member and
     /// their friends.
-    include_friends: IncludeFriends,
+    pub include_friends: Option<IncludeFriends>,
     /// Specify a tag code to limit the returned films to those tagged
     /// accordingly.
-    tag_code: String,
+    pub tag_code: Option<String>,
     /// Must be used with tag. Specify the LID of a member to focus the tag
     /// filter on the member.
-    tagger: String,
+    pub tagger: Option<String>,
     /// Must be used in conjunction with tagger. Defaults to None, which
     /// filters tags set by the member. Use Only to filter tags set by the
     /// member’s friends, and All to filter tags set by both the member and
     /// their friends.
-    include_tagger_friends: IncludeFriends,
+    pub include_tagger_friends: Option<IncludeFriends>,
 }
@@ -0,0 +1,610 @@
+//! Declarative description of API routes.
+//!
+//! Each route of the API is described by a type implementing [`Endpoint`](trait.Endpoint.html),
+//! which ties together its method, path, query or body, and response type. An endpoint is run
+//! with [`Client::execute`](../struct.Client.html#method.execute); the convenience methods of
+//! the client are thin wrappers around it.
+//!
+//! Routes not covered by this module can be called by implementing `Endpoint` for a custom type.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::endpoint::{Endpoint, GetFilmRelationship};
+//! use letterboxd::middleware::Method;
+//!
+//! /// Statistical data about a member.
+//! struct GetMemberStatistics<'a> {
+//!     id: &'a str,
+//! }
+//!
+//! impl Endpoint for GetMemberStatistics<'_> {
+//!     const METHOD: Method = Method::GET;
+//!     const PATH: &'static str = "member/{id}/statistics";
+//!     type Query = ();
+//!     type Body = ();
+//!     type Response = serde_json::Value;
+//!
+//!     fn path_params(&self) -> Vec<&str> {
+//!         vec![self.id]
+//!     }
+//! }
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let client = letterboxd::Client::authenticate(api_key_pair, "username", "password").await?;
+//!     let relationship = client.execute(&GetFilmRelationship { id: "2a9q" }).await?;
+//!     let statistics = client.execute(&GetMemberStatistics { id: "11Ht" }).await?;
+//!     println!("{:?} {}", relationship, statistics);
+//!     Ok::<_, letterboxd::Error>(())
+//! };
+//! ```
+
+use crate::defs;
+use crate::error::{Error, Result};
+use crate::lbml;
+
+use hyper::Method;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A route of the API.
+pub trait Endpoint {
+    /// The HTTP method.
+    const METHOD: Method;
+    /// The path template relative to the API base url, e.g. `film/{id}`.
+    ///
+    /// Each `{...}` placeholder is replaced by the corresponding value of `path_params`.
+    const PATH: &'static str;
+
+    /// The type of the query parameters, `()` if there are none.
+    type Query: Serialize;
+    /// The type of the JSON body, `()` if there is none.
+    type Body: Serialize;
+    /// The type of the JSON response. An empty response is deserialized from `null`.
+    type Response: DeserializeOwned + 'static;
+
+    /// The values of the placeholders in the path template, in order.
+    ///
+    /// Each value is percent-encoded as a single path segment.
+    fn path_params(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    /// The query parameters, if any.
+    fn query(&self) -> Option<&Self::Query> {
+        None
+    }
+
+    /// The body, if any.
+    fn body(&self) -> Option<&Self::Body> {
+        None
+    }
+
+    /// Checks the parameters before the request is sent.
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Characters which are percent-encoded in path segments.
+///
+/// Besides the characters which are not allowed in a path segment, this includes `/`, `?`, `#`
+/// and `%`, so that a parameter is always a single segment.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Percent-encodes a parameter as a single path segment.
+///
+/// Empty parameters and the dot segments `.` and `..` are rejected, since they would change the
+/// path of the request.
+pub fn encode_path_segment(segment: &str) -> Result<String> {
+    // `%` is encoded, so encoded dot segments like `%2e%2e` are not an issue
+    if segment.is_empty() || segment == "." || segment == ".." {
+        return Err(Error::invalid_path_segment(segment));
+    }
+    Ok(utf8_percent_encode(segment, PATH_SEGMENT).to_string())
+}
+
+/// Renders a path template by replacing its placeholders with the encoded parameters.
+///
+/// The number of parameters must match the number of placeholders.
+pub(crate) fn render_path(template: &str, params: &[&str]) -> Result<String> {
+    let mut path = String::with_capacity(template.len());
+    let mut params = params.iter();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        path.push_str(&rest[..start]);
+        let param = params.next().ok_or_else(|| {
+            Error::invalid_request(format!(
+                "missing path parameter {} of {}",
+                &rest[start..=end],
+                template
+            ))
+        })?;
+        path.push_str(&encode_path_segment(param)?);
+        rest = &rest[end + 1..];
+    }
+    if params.next().is_some() {
+        return Err(Error::invalid_request(format!(
+            "too many path parameters for {}",
+            template
+        )));
+    }
+    path.push_str(rest);
+    Ok(path)
+}
+
+// film
+
+/// A cursored window over the list of films.
+#[derive(Debug, Clone, Copy)]
+pub struct GetFilms<'a> {
+    /// The request.
+    pub request: &'a defs::FilmsRequest,
+}
+
+impl Endpoint for GetFilms<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "films";
+    type Query = defs::FilmsRequest;
+    type Body = ();
+    type Response = defs::FilmsResponse;
+
+    fn validate(&self) -> Result<()> {
+        self.request.validate()
+    }
+
+    fn query(&self) -> Option<&Self::Query> {
+        Some(self.request)
+    }
+}
+
+/// The list of services supported by the `films` endpoint.
+#[derive(Debug, Clone, Copy)]
+pub struct GetFilmServices;
+
+impl Endpoint for GetFilmServices {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "films/film-services";
+    type Query = ();
+    type Body = ();
+    type Response = defs::FilmServicesResponse;
+}
+
+/// The list of genres supported by the `films` endpoint.
+#[derive(Debug, Clone, Copy)]
+pub struct GetFilmGenres;
+
+impl Endpoint for GetFilmGenres {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "films/genres";
+    type Query = ();
+    type Body = ();
+    type Response = defs::GenresResponse;
+}
+
+/// Details about a film by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct GetFilm<'a> {
+    /// The LID of the film.
+    pub id: &'a str,
+}
+
+impl Endpoint for GetFilm<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "film/{id}";
+    type Query = ();
+    type Body = ();
+    type Response = defs::Film;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+}
+
+/// Availability data about a film by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct GetFilmAvailability<'a> {
+    /// The LID of the film.
+    pub id: &'a str,
+}
+
+impl Endpoint for GetFilmAvailability<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "film/{id}/availability";
+    type Query = ();
+    type Body = ();
+    type Response = defs::FilmAvailabilityResponse;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+}
+
+/// The authenticated member’s relationship with a film by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct GetFilmRelationship<'a> {
+    /// The LID of the film.
+    pub id: &'a str,
+}
+
+impl Endpoint for GetFilmRelationship<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "film/{id}/me";
+    type Query = ();
+    type Body = ();
+    type Response = defs::FilmRelationship;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+}
+
+/// Update of the authenticated member’s relationship with a film by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct UpdateFilmRelationship<'a> {
+    /// The LID of the film.
+    pub id: &'a str,
+    /// The update.
+    pub request: &'a defs::FilmRelationshipUpdateRequest,
+}
+
+impl Endpoint for UpdateFilmRelationship<'_> {
+    const METHOD: Method = Method::PATCH;
+    const PATH: &'static str = "film/{id}/me";
+    type Query = ();
+    type Body = defs::FilmRelationshipUpdateRequest;
+    type Response = defs::FilmRelationshipUpdateResponse;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+
+    fn body(&self) -> Option<&Self::Body> {
+        Some(self.request)
+    }
+}
+
+/// A cursored window over the relationships of members with a film by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct GetFilmRelationshipMembers<'a> {
+    /// The LID of the film.
+    pub id: &'a str,
+    /// The request.
+    pub request: &'a defs::MemberFilmRelationshipsRequest,
+}
+
+impl Endpoint for GetFilmRelationshipMembers<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "film/{id}/members";
+    type Query = defs::MemberFilmRelationshipsRequest;
+    type Body = ();
+    type Response = defs::MemberFilmRelationshipsResponse;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+
+    fn query(&self) -> Option<&Self::Query> {
+        Some(self.request)
+    }
+}
+
+/// Statistical data about a film by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct GetFilmStatistics<'a> {
+    /// The LID of the film.
+    pub id: &'a str,
+}
+
+impl Endpoint for GetFilmStatistics<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "film/{id}/statistics";
+    type Query = ();
+    type Body = ();
+    type Response = defs::FilmStatistics;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+}
+
+// list
+
+/// A cursored window over a list of lists.
+#[derive(Debug, Clone, Copy)]
+pub struct GetLists<'a> {
+    /// The request.
+    pub request: &'a defs::ListsRequest,
+}
+
+impl Endpoint for GetLists<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "lists";
+    type Query = defs::ListsRequest;
+    type Body = ();
+    type Response = defs::ListsResponse;
+
+    fn validate(&self) -> Result<()> {
+        self.request.validate()
+    }
+
+    fn query(&self) -> Option<&Self::Query> {
+        Some(self.request)
+    }
+}
+
+/// Creation of a list.
+#[derive(Debug, Clone, Copy)]
+pub struct CreateList<'a> {
+    /// The request.
+    pub request: &'a defs::ListCreationRequest,
+}
+
+impl Endpoint for CreateList<'_> {
+    const METHOD: Method = Method::POST;
+    const PATH: &'static str = "lists";
+    type Query = ();
+    type Body = defs::ListCreationRequest;
+    type Response = defs::ListCreateResponse;
+
+    fn validate(&self) -> Result<()> {
+        self.request
+            .description
+            .as_deref()
+            .map_or(Ok(()), lbml::validate)
+    }
+
+    fn body(&self) -> Option<&Self::Body> {
+        Some(self.request)
+    }
+}
+
+/// Details of a list by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct GetList<'a> {
+    /// The LID of the list.
+    pub id: &'a str,
+}
+
+impl Endpoint for GetList<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "list/{id}";
+    type Query = ();
+    type Body = ();
+    type Response = defs::List;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+}
+
+/// Update of a list by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct UpdateList<'a> {
+    /// The LID of the list.
+    pub id: &'a str,
+    /// The update.
+    pub request: &'a defs::ListUpdateRequest,
+}
+
+impl Endpoint for UpdateList<'_> {
+    const METHOD: Method = Method::PATCH;
+    const PATH: &'static str = "list/{id}";
+    type Query = ();
+    type Body = defs::ListUpdateRequest;
+    type Response = defs::ListUpdateResponse;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.request
+            .description
+            .as_deref()
+            .map_or(Ok(()), lbml::validate)
+    }
+
+    fn body(&self) -> Option<&Self::Body> {
+        Some(self.request)
+    }
+}
+
+/// Deletion of a list by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct DeleteList<'a> {
+    /// The LID of the list.
+    pub id: &'a str,
+}
+
+impl Endpoint for DeleteList<'_> {
+    const METHOD: Method = Method::DELETE;
+    const PATH: &'static str = "list/{id}";
+    type Query = ();
+    type Body = ();
+    type Response = ();
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+}
+
+/// A cursored window over the entries of a list by ID.
+#[derive(Debug, Clone, Copy)]
+pub struct GetListEntries<'a> {
+    /// The LID of the list.
+    pub id: &'a str,
+    /// The request.
+    pub request: &'a defs::ListEntriesRequest,
+}
+
+impl Endpoint for GetListEntries<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "list/{id}/entries";
+    type Query = defs::ListEntriesRequest;
+    type Body = ();
+    type Response = defs::ListEntriesResponse;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.request.validate()
+    }
+
+    fn query(&self) -> Option<&Self::Query> {
+        Some(self.request)
+    }
+}
+
+// log-entry
+
+/// A cursored window over the log entries for a film or member.
+#[derive(Debug, Clone, Copy)]
+pub struct GetLogEntries<'a> {
+    /// The request.
+    pub request: &'a defs::LogEntriesRequest,
+}
+
+impl Endpoint for GetLogEntries<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "log-entries";
+    type Query = defs::LogEntriesRequest;
+    type Body = ();
+    type Response = defs::LogEntriesResponse;
+
+    fn query(&self) -> Option<&Self::Query> {
+        Some(self.request)
+    }
+}
+
+/// Creation of a log entry.
+#[derive(Debug, Clone, Copy)]
+pub struct CreateLogEntry<'a> {
+    /// The request.
+    pub request: &'a defs::LogEntryCreationRequest,
+}
+
+impl Endpoint for CreateLogEntry<'_> {
+    const METHOD: Method = Method::POST;
+    const PATH: &'static str = "log-entries";
+    type Query = ();
+    type Body = defs::LogEntryCreationRequest;
+    type Response = defs::LogEntry;
+
+    fn validate(&self) -> Result<()> {
+        self.request
+            .review
+            .as_ref()
+            .map_or(Ok(()), |review| lbml::validate(&review.text))
+    }
+
+    fn body(&self) -> Option<&Self::Body> {
+        Some(self.request)
+    }
+}
+
+// member
+
+/// The list of pronoun sets supported by the API.
+#[derive(Debug, Clone, Copy)]
+pub struct GetMemberPronouns;
+
+impl Endpoint for GetMemberPronouns {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "members/pronouns";
+    type Query = ();
+    type Body = ();
+    type Response = defs::PronounsResponse;
+}
+
+/// A cursored window over the activity for a member.
+#[derive(Debug, Clone, Copy)]
+pub struct GetMemberActivity<'a> {
+    /// The LID of the member.
+    pub id: &'a str,
+    /// The request.
+    pub request: &'a defs::ActivityRequest,
+}
+
+impl Endpoint for GetMemberActivity<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "member/{id}/activity";
+    type Query = defs::ActivityRequest;
+    type Body = ();
+    type Response = defs::ActivityResponse;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+
+    fn query(&self) -> Option<&Self::Query> {
+        Some(self.request)
+    }
+}
+
+/// A cursored window over the films in a member’s watchlist.
+#[derive(Debug, Clone, Copy)]
+pub struct GetMemberWatchlist<'a> {
+    /// The LID of the member.
+    pub id: &'a str,
+    /// The request.
+    pub request: &'a defs::WatchlistRequest,
+}
+
+impl Endpoint for GetMemberWatchlist<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "member/{id}/watchlist";
+    type Query = defs::WatchlistRequest;
+    type Body = ();
+    type Response = defs::FilmsResponse;
+
+    fn path_params(&self) -> Vec<&str> {
+        vec![self.id]
+    }
+
+    fn query(&self) -> Option<&Self::Query> {
+        Some(self.request)
+    }
+}
+
+// search
+
+/// Search for any data.
+#[derive(Debug, Clone, Copy)]
+pub struct Search<'a> {
+    /// The request.
+    pub request: &'a defs::SearchRequest,
+}
+
+impl Endpoint for Search<'_> {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "search";
+    type Query = defs::SearchRequest;
+    type Body = ();
+    type Response = defs::SearchResponse;
+
+    fn query(&self) -> Option<&Self::Query> {
+        Some(self.request)
+    }
+}
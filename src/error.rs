@@ -9,14 +9,49 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
 pub struct Error {
     kind: Kind,
-    url: Option<Uri>,
+    url: Option<Box<Uri>>,
 }
 
 impl Error {
     pub(crate) fn server_error(status: StatusCode, resp: String, url: Uri) -> Error {
         Error {
             kind: Kind::ServerError(status, resp),
-            url: Some(url),
+            url: Some(Box::new(url)),
+        }
+    }
+
+    pub(crate) fn not_authenticated() -> Error {
+        Error {
+            kind: Kind::NotAuthenticated,
+            url: None,
+        }
+    }
+
+    pub(crate) fn invalid_signature(reason: &'static str) -> Error {
+        Error {
+            kind: Kind::InvalidSignature(reason),
+            url: None,
+        }
+    }
+
+    pub(crate) fn invalid_request(message: String) -> Error {
+        Error {
+            kind: Kind::InvalidRequest(message),
+            url: None,
+        }
+    }
+
+    pub(crate) fn invalid_path_segment(segment: &str) -> Error {
+        Error {
+            kind: Kind::InvalidPathSegment(segment.to_string()),
+            url: None,
+        }
+    }
+
+    pub(crate) fn invalid_lbml(error: crate::lbml::ValidationError) -> Error {
+        Error {
+            kind: Kind::InvalidLbml(error),
+            url: None,
         }
     }
 
@@ -27,19 +62,46 @@ impl Error {
 
     /// Returns the url (if any) for which the error occurred.
     pub fn url(&self) -> Option<&Uri> {
-        self.url.as_ref()
+        self.url.as_deref()
     }
 }
 
 /// Different kinds of error which might occur.
 #[derive(Debug)]
 pub enum Kind {
+    /// An HTTP error of the underlying client.
     Http(hyper::Error),
+    /// An invalid URI.
     Uri(hyper::http::uri::InvalidUri),
+    /// An invalid URL, e.g. a request path which could not be joined to the API base url.
+    Url(url::ParseError),
+    /// A segment of a request path which can't be encoded, like `..`.
+    InvalidPathSegment(String),
+    /// A JSON (de)serialization error.
     Json(serde_json::Error),
+    /// A response which is not valid UTF-8.
     Utf8Error(std::str::Utf8Error),
+    /// An error encoding query parameters.
     UrlEncoding(serde_url_params::Error),
+    /// An unsuccessful response of the API with its status code and body.
     ServerError(StatusCode, String /* response */),
+    /// An I/O error.
+    Io(std::io::Error),
+    /// A CSV error.
+    Csv(csv::Error),
+    /// A ZIP archive error.
+    Zip(zip::result::ZipError),
+    /// A request requiring authentication was made by an unauthenticated client.
+    NotAuthenticated,
+    /// A request with invalid parameters, with a description of the violated constraint.
+    InvalidRequest(String),
+    /// LBML which violates the length limit or the markup rules, with the position.
+    InvalidLbml(crate::lbml::ValidationError),
+    /// A request signature which could not be verified, with the reason.
+    InvalidSignature(&'static str /* reason */),
+    /// An SQLite error of the mirror.
+    #[cfg(feature = "mirror")]
+    Sqlite(rusqlite::Error),
 }
 
 impl fmt::Display for Error {
@@ -51,12 +113,25 @@ impl fmt::Display for Error {
         match self.kind {
             Kind::Http(ref e) => fmt::Display::fmt(e, f),
             Kind::Uri(ref e) => fmt::Display::fmt(e, f),
+            Kind::Url(ref e) => fmt::Display::fmt(e, f),
+            Kind::InvalidPathSegment(ref segment) => {
+                write!(f, "invalid path segment: {:?}", segment)
+            }
             Kind::Json(ref e) => fmt::Display::fmt(e, f),
             Kind::Utf8Error(ref e) => fmt::Display::fmt(e, f),
             Kind::UrlEncoding(ref e) => fmt::Display::fmt(e, f),
             Kind::ServerError(ref code, ref resp) => {
                 write!(f, "Server Error: {}, Response: {}", code, resp)
             }
+            Kind::Io(ref e) => fmt::Display::fmt(e, f),
+            Kind::Csv(ref e) => fmt::Display::fmt(e, f),
+            Kind::Zip(ref e) => fmt::Display::fmt(e, f),
+            Kind::NotAuthenticated => f.write_str("client is not authenticated"),
+            Kind::InvalidRequest(ref message) => write!(f, "invalid request: {}", message),
+            Kind::InvalidLbml(ref e) => write!(f, "invalid LBML: {}", e),
+            Kind::InvalidSignature(reason) => write!(f, "invalid request signature: {}", reason),
+            #[cfg(feature = "mirror")]
+            Kind::Sqlite(ref e) => fmt::Display::fmt(e, f),
         }
     }
 }
@@ -81,6 +156,15 @@ impl From<hyper::Error> for Error {
     }
 }
 
+impl From<url::ParseError> for Error {
+    fn from(err: url::ParseError) -> Self {
+        Self {
+            kind: Kind::Url(err),
+            url: None,
+        }
+    }
+}
+
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
         Self {
@@ -107,3 +191,40 @@ impl From<serde_url_params::Error> for Error {
         }
     }
 }
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self {
+            kind: Kind::Io(err),
+            url: None,
+        }
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(err: csv::Error) -> Self {
+        Self {
+            kind: Kind::Csv(err),
+            url: None,
+        }
+    }
+}
+
+impl From<zip::result::ZipError> for Error {
+    fn from(err: zip::result::ZipError) -> Self {
+        Self {
+            kind: Kind::Zip(err),
+            url: None,
+        }
+    }
+}
+
+#[cfg(feature = "mirror")]
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        Self {
+            kind: Kind::Sqlite(err),
+            url: None,
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! Export of member data in the CSV layout of the Letterboxd account export.
+//!
+//! The files written by [`Export::write_to_dir`](struct.Export.html#method.write_to_dir) mirror
+//! the ones contained in the ZIP archive which Letterboxd offers in the account settings:
+//!
+//! * `diary.csv`: log entries with a diary date,
+//! * `reviews.csv`: log entries with a review,
+//! * `ratings.csv`: films rated by the member,
+//! * `watched.csv`: films watched by the member,
+//! * `watchlist.csv`: films in the member's watchlist,
+//! * `lists/<name>-<LID>.csv`: one file per list owned by the member.
+//!
+//! The API does not tell when a film was rated, watched or added to the watchlist. Therefore,
+//! the `Date` column of `ratings.csv`, `watched.csv` and `watchlist.csv` is left empty.
+
+use crate::client::Client;
+use crate::crossref;
+use crate::defs::{
+    FilmRelationshipType, FilmSummary, FilmsRequest, Link, List, ListEntriesRequest, ListEntry,
+    ListMemberRelationship, ListsRequest, LogEntriesRequest, LogEntry, LogEntryRelationshipType,
+    WatchlistRequest,
+};
+use crate::error::Result;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const DIARY_HEADER: [&str; 8] = [
+    "Date",
+    "Name",
+    "Year",
+    "Letterboxd URI",
+    "Rating",
+    "Rewatch",
+    "Tags",
+    "Watched Date",
+];
+const REVIEWS_HEADER: [&str; 9] = [
+    "Date",
+    "Name",
+    "Year",
+    "Letterboxd URI",
+    "Rating",
+    "Rewatch",
+    "Review",
+    "Tags",
+    "Watched Date",
+];
+const RATINGS_HEADER: [&str; 5] = ["Date", "Name", "Year", "Letterboxd URI", "Rating"];
+const FILMS_HEADER: [&str; 4] = ["Date", "Name", "Year", "Letterboxd URI"];
+const LIST_VERSION: &str = "Letterboxd list export v7";
+const LIST_HEADER: [&str; 5] = ["Date", "Name", "Tags", "URL", "Description"];
+const LIST_ENTRIES_HEADER: [&str; 5] = ["Position", "Name", "Year", "URL", "Description"];
+
+/// Page size used when fetching data for an export.
+const PER_PAGE: usize = 100;
+
+/// Member data which can be written in the Letterboxd export layout.
+#[derive(Debug, Clone, Default)]
+pub struct Export {
+    /// The LID of the member whose data is exported.
+    pub member: String,
+    /// All log entries created by the member.
+    pub log_entries: Vec<LogEntry>,
+    /// Films watched by the member. Must include the member's relationship to get ratings.
+    pub watched: Vec<FilmSummary>,
+    /// Films in the member's watchlist.
+    pub watchlist: Vec<FilmSummary>,
+    /// Lists owned by the member.
+    pub lists: Vec<ExportList>,
+}
+
+/// A list together with all of its entries.
+#[derive(Debug, Clone)]
+pub struct ExportList {
+    /// Details of the list.
+    pub list: List,
+    /// All entries of the list.
+    pub entries: Vec<ListEntry>,
+}
+
+impl Export {
+    /// Fetches all data of a member which is needed for an export.
+    ///
+    /// Private data (e.g. unpublished lists) is only included if the client is authenticated
+    /// as the member.
+    pub async fn fetch(client: &Client, member: &str) -> Result<Self> {
+        let mut log_entries = Vec::new();
+        let mut req = LogEntriesRequest {
+            per_page: Some(PER_PAGE),
+            member: Some(member.to_string()),
+            member_relationship: Some(LogEntryRelationshipType::Owner),
+            ..Default::default()
+        };
+        loop {
+            let resp = client.log_entries(&req).await?;
+            log_entries.extend(resp.items);
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let mut watched = Vec::new();
+        let mut req = FilmsRequest {
+            per_page: Some(PER_PAGE),
+            member: Some(member.to_string()),
+            member_relationship: Some(FilmRelationshipType::Watched),
+            ..Default::default()
+        };
+        loop {
+            let resp = client.films(&req).await?;
+            watched.extend(resp.items);
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let mut watchlist = Vec::new();
+        let mut req = WatchlistRequest {
+            per_page: Some(PER_PAGE),
+            ..Default::default()
+        };
+        loop {
+            let resp = client.member_watchlist(member, &req).await?;
+            watchlist.extend(resp.items);
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let mut lists = Vec::new();
+        let mut req = ListsRequest {
+            per_page: Some(PER_PAGE),
+            member: Some(member.to_string()),
+            member_relationship: Some(ListMemberRelationship::Owner),
+            ..Default::default()
+        };
+        loop {
+            let resp = client.lists(&req).await?;
+            for summary in resp.items {
+                let list = client.list(&summary.id).await?;
+                let entries = fetch_list_entries(client, &list.id).await?;
+                lists.push(ExportList { list, entries });
+            }
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(Self {
+            member: member.to_string(),
+            log_entries,
+            watched,
+            watchlist,
+            lists,
+        })
+    }
+
+    /// Writes all export files into the given directory.
+    ///
+    /// The directory and its `lists` subdirectory are created if they do not exist. Existing
+    /// files are overwritten.
+    pub fn write_to_dir<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir.join("lists"))?;
+
+        write_diary(fs::File::create(dir.join("diary.csv"))?, &self.log_entries)?;
+        write_reviews(
+            fs::File::create(dir.join("reviews.csv"))?,
+            &self.log_entries,
+        )?;
+        write_ratings(
+            fs::File::create(dir.join("ratings.csv"))?,
+            &self.member,
+            &self.watched,
+        )?;
+        write_watched(fs::File::create(dir.join("watched.csv"))?, &self.watched)?;
+        write_watchlist(
+            fs::File::create(dir.join("watchlist.csv"))?,
+            &self.watchlist,
+        )?;
+
+        for export_list in &self.lists {
+            let path = dir.join("lists").join(list_file_name(&export_list.list));
+            write_list(
+                fs::File::create(path)?,
+                &export_list.list,
+                &export_list.entries,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+async fn fetch_list_entries(client: &Client, id: &str) -> Result<Vec<ListEntry>> {
+    let mut entries = Vec::new();
+    let mut req = ListEntriesRequest {
+        per_page: Some(PER_PAGE),
+        ..Default::default()
+    };
+    loop {
+        let resp = client.list_entries(id, &req).await?;
+        entries.extend(resp.items);
+        match resp.next {
+            Some(next) => req.cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(entries)
+}
+
+/// Writes `diary.csv` from all log entries which have a diary date.
+pub fn write_diary<W: io::Write>(writer: W, log_entries: &[LogEntry]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(DIARY_HEADER)?;
+    for entry in log_entries {
+        let diary_details = match entry.diary_details {
+            Some(ref diary_details) => diary_details,
+            None => continue,
+        };
+        wtr.write_record([
+            date(&entry.when_created),
+            &entry.film.name,
+            &year(&entry.film),
+            &letterboxd_uri(&entry.links, &entry.id),
+            &rating(entry.rating),
+            rewatch(diary_details.rewatch),
+            &tags(entry),
+            &diary_details.diary_date,
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Writes `reviews.csv` from all log entries which have a review.
+///
+/// The review is written in LBML.
+pub fn write_reviews<W: io::Write>(writer: W, log_entries: &[LogEntry]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(REVIEWS_HEADER)?;
+    for entry in log_entries {
+        let review = match entry.review {
+            Some(ref review) => review,
+            None => continue,
+        };
+        let diary_details = entry.diary_details.as_ref();
+        wtr.write_record([
+            date(&entry.when_created),
+            &entry.film.name,
+            &year(&entry.film),
+            &letterboxd_uri(&entry.links, &entry.id),
+            &rating(entry.rating),
+            rewatch(diary_details.is_some_and(|d| d.rewatch)),
+            &review.lbml,
+            &tags(entry),
+            diary_details.map_or("", |d| &d.diary_date),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Writes `ratings.csv` from all films which are rated by the given member.
+///
+/// The rating is taken from the film's relationship to the member.
+pub fn write_ratings<W: io::Write>(writer: W, member: &str, films: &[FilmSummary]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(RATINGS_HEADER)?;
+    for film in films {
+        let relationship = film.relationships.iter().find(|r| r.member.id == member);
+        let rating = match relationship.and_then(|r| r.relationship.rating) {
+            Some(rating) => rating,
+            None => continue,
+        };
+        wtr.write_record([
+            "",
+            &film.name,
+            &year(film),
+            &film_uri(film),
+            &rating.to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Writes `watched.csv` from the given watched films.
+pub fn write_watched<W: io::Write>(writer: W, films: &[FilmSummary]) -> Result<()> {
+    write_films(writer, films)
+}
+
+/// Writes `watchlist.csv` from the given watchlist films.
+pub fn write_watchlist<W: io::Write>(writer: W, films: &[FilmSummary]) -> Result<()> {
+    write_films(writer, films)
+}
+
+fn write_films<W: io::Write>(writer: W, films: &[FilmSummary]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(FILMS_HEADER)?;
+    for film in films {
+        wtr.write_record(["", &film.name, &year(film), &film_uri(film)])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Writes a list file from a list and its entries.
+///
+/// The list file starts with the list details followed by an empty line and the entries. The
+/// description of the list and the notes of the entries are written in LBML.
+pub fn write_list<W: io::Write>(mut writer: W, list: &List, entries: &[ListEntry]) -> Result<()> {
+    writeln!(writer, "{}", LIST_VERSION)?;
+
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(LIST_HEADER)?;
+    let tags: Vec<&str> = list.tags2.iter().map(|t| t.display_tag.as_str()).collect();
+    wtr.write_record([
+        date(&list.when_created),
+        &list.name,
+        &tags.join(", "),
+        &letterboxd_uri(&list.links, &list.id),
+        list.description_lbml.as_deref().unwrap_or(""),
+    ])?;
+    let mut writer = wtr.into_inner().map_err(|e| e.into_error())?;
+    writer.write_all(b"\n")?;
+
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(LIST_ENTRIES_HEADER)?;
+    for (index, entry) in entries.iter().enumerate() {
+        let position = entry.rank.unwrap_or(index + 1);
+        wtr.write_record([
+            &position.to_string(),
+            &entry.film.name,
+            &year(&entry.film),
+            &film_uri(&entry.film),
+            entry.notes_lbml.as_deref().unwrap_or(""),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Returns the file name of a list in the `lists` directory.
+///
+/// The name is derived from the list name in the same way as the slug of the list on the
+/// Letterboxd website, followed by the LID of the list so that lists with similar names do not
+/// share a file, e.g. `"My Favorite Films!"` becomes `my-favorite-films-1fKte.csv`.
+pub fn list_file_name(list: &List) -> String {
+    let mut slug = String::with_capacity(list.name.len());
+    for c in list.name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    if !slug.is_empty() {
+        slug.push('-');
+    }
+    slug.push_str(&list.id);
+    slug + ".csv"
+}
+
+/// Returns the date portion `YYYY-MM-DD` of an ISO 8601 timestamp.
+fn date(timestamp: &str) -> &str {
+    timestamp.get(..10).unwrap_or(timestamp)
+}
+
+fn year(film: &FilmSummary) -> String {
+    film.release_year
+        .map(|year| year.to_string())
+        .unwrap_or_default()
+}
+
+fn rating(rating: Option<f32>) -> String {
+    rating.map(|r| r.to_string()).unwrap_or_default()
+}
+
+fn rewatch(rewatch: bool) -> &'static str {
+    if rewatch {
+        "Yes"
+    } else {
+        ""
+    }
+}
+
+fn tags(entry: &LogEntry) -> String {
+    let tags: Vec<&str> = entry.tags2.iter().map(|t| t.display_tag.as_str()).collect();
+    tags.join(", ")
+}
+
+fn film_uri(film: &FilmSummary) -> String {
+    letterboxd_uri(film.links.as_deref().unwrap_or(&[]), &film.id)
+}
+
+/// Returns the Letterboxd URL from links, falling back to the shareable boxd.it link of the LID.
+fn letterboxd_uri(links: &[Link], id: &str) -> String {
+    crossref::letterboxd_url(links)
+        .map(String::from)
+        .unwrap_or_else(|| format!("https://boxd.it/{}", id))
+}
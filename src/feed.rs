@@ -0,0 +1,672 @@
+//! Atom 1.0 and RSS 2.0 feeds of diaries, reviews, lists and activity.
+//!
+//! A [`Feed`](struct.Feed.html) collects entries from pages of log entries, list entries or
+//! member activity, and renders them as Atom or RSS document. LBML of reviews, notes and
+//! comments is rendered as HTML, and the poster of a film is attached as enclosure.
+//!
+//! The IDs of the entries are tag URIs derived from the LIDs, like
+//! `tag:letterboxd.com,2011:log-entry/3SBhl`, so that feed readers recognize entries across
+//! regenerations of the feed. Activity has no LID, its ID is derived from the member, the type,
+//! the subject and the timestamp of the activity instead.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::feed::{Feed, Format};
+//! use letterboxd::{LogEntriesRequest, LogEntryRelationshipType};
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let client = letterboxd::Client::new(api_key_pair);
+//!
+//!     let req = LogEntriesRequest {
+//!         member: Some("2bbs".to_string()),
+//!         member_relationship: Some(LogEntryRelationshipType::Owner),
+//!         ..Default::default()
+//!     };
+//!     let mut feed = Feed::new("tag:letterboxd.com,2011:member/2bbs/diary", "Diary")
+//!         .with_link("https://letterboxd.com/2bbs/films/diary/");
+//!     feed.extend_log_entries(&client.log_entries(&req).await?);
+//!     feed.write(std::fs::File::create("diary.atom")?, Format::Atom)
+//! };
+//! ```
+
+use crate::crossref::letterboxd_url;
+use crate::date;
+use crate::defs::{
+    AbstractActivity, ActivityResponse, FilmSummary, List, ListEntriesResponse, ListEntry,
+    LogEntriesResponse, LogEntry, MemberSummary,
+};
+use crate::error::Result;
+use crate::format::film_title;
+use crate::lbml::{self, escape_attribute, escape_html};
+
+use std::io;
+
+/// Prefix of the tag URIs used as IDs of feeds and entries.
+pub const TAG_PREFIX: &str = "tag:letterboxd.com,2011:";
+
+/// Timestamp of feeds without entries.
+const EPOCH: &str = "1970-01-01T00:00:00Z";
+const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Format of a feed document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Atom 1.0 (RFC 4287).
+    Atom,
+    /// RSS 2.0.
+    Rss,
+}
+
+/// A feed of entries, in the order they were added.
+#[derive(Debug, Clone)]
+pub struct Feed {
+    /// The ID of the feed, which must be a URI, e.g. starting with [`TAG_PREFIX`](constant.TAG_PREFIX.html).
+    pub id: String,
+    /// The title of the feed.
+    pub title: String,
+    /// The URL of the web page corresponding to the feed.
+    pub link: Option<String>,
+    /// The description of the feed as plain text.
+    pub description: Option<String>,
+    /// The name of the author of the feed.
+    pub author: Option<String>,
+    /// The entries.
+    pub entries: Vec<Entry>,
+}
+
+/// An entry of a feed.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// The ID of the entry, which must be a URI.
+    pub id: String,
+    /// The title of the entry as plain text.
+    pub title: String,
+    /// The URL of the entry on Letterboxd.
+    pub link: Option<String>,
+    /// The name of the author of the entry.
+    pub author: Option<String>,
+    /// The timestamp of the publication, in ISO 8601 format with UTC timezone, i.e.
+    /// YYYY-MM-DDThh:mm:ssZ.
+    pub published: String,
+    /// The timestamp of the last update, in ISO 8601 format with UTC timezone.
+    pub updated: String,
+    /// The content as HTML.
+    pub content: String,
+    /// The image attached to the entry.
+    pub enclosure: Option<Enclosure>,
+}
+
+/// An image attached to an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enclosure {
+    /// The URL of the image.
+    pub url: String,
+    /// The media type of the image, like `image/jpeg`.
+    pub mime_type: String,
+}
+
+impl Feed {
+    /// Creates an empty feed.
+    pub fn new<I: Into<String>, T: Into<String>>(id: I, title: T) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            link: None,
+            description: None,
+            author: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Creates an empty feed of the entries of a list, with the list's name, description and
+    /// owner.
+    pub fn for_list(list: &List) -> Self {
+        let mut feed = Self::new(format!("{}list/{}", TAG_PREFIX, list.id), &list.name);
+        feed.link = letterboxd_url(&list.links).map(String::from);
+        feed.description = list
+            .description_lbml
+            .as_ref()
+            .map(|description| lbml::parse(description).plain_text())
+            .filter(|description| !description.is_empty());
+        feed.author = Some(list.owner.display_name.clone());
+        feed
+    }
+
+    /// Sets the URL of the web page corresponding to the feed.
+    pub fn with_link<S: Into<String>>(mut self, link: S) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    /// Sets the description of the feed.
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Adds an entry for each log entry of a page.
+    pub fn extend_log_entries(&mut self, page: &LogEntriesResponse) {
+        self.entries
+            .extend(page.items.iter().map(Entry::from_log_entry));
+    }
+
+    /// Adds an entry for each entry of a page of a list.
+    pub fn extend_list_entries(&mut self, list: &List, page: &ListEntriesResponse) {
+        self.entries.extend(
+            page.items
+                .iter()
+                .map(|entry| Entry::from_list_entry(list, entry)),
+        );
+    }
+
+    /// Adds an entry for each activity of a page.
+    pub fn extend_activity(&mut self, page: &ActivityResponse) {
+        self.entries
+            .extend(page.items.iter().map(Entry::from_activity));
+    }
+
+    /// Returns the timestamp of the most recently updated entry.
+    pub fn updated(&self) -> Option<&str> {
+        self.entries
+            .iter()
+            .map(|entry| entry.updated.as_str())
+            .max()
+    }
+
+    /// Writes the feed in the given format.
+    pub fn write<W: io::Write>(&self, mut writer: W, format: Format) -> Result<()> {
+        let document = match format {
+            Format::Atom => self.to_atom(),
+            Format::Rss => self.to_rss(),
+        };
+        writer.write_all(document.as_bytes())?;
+        Ok(())
+    }
+
+    /// Renders the feed as Atom 1.0 document.
+    pub fn to_atom(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        element(&mut out, 1, "id", &self.id);
+        element(&mut out, 1, "title", &self.title);
+        if let Some(description) = self.description.as_ref() {
+            element(&mut out, 1, "subtitle", description);
+        }
+        if let Some(link) = self.link.as_ref() {
+            link_element(&mut out, 1, "alternate", link, None);
+        }
+        element(&mut out, 1, "updated", self.updated().unwrap_or(EPOCH));
+        if let Some(author) = self.author.as_ref() {
+            author_element(&mut out, 1, author);
+        }
+        for entry in &self.entries {
+            out.push_str("  <entry>\n");
+            element(&mut out, 2, "id", &entry.id);
+            element(&mut out, 2, "title", &entry.title);
+            if let Some(link) = entry.link.as_ref() {
+                link_element(&mut out, 2, "alternate", link, None);
+            }
+            if let Some(enclosure) = entry.enclosure.as_ref() {
+                link_element(
+                    &mut out,
+                    2,
+                    "enclosure",
+                    &enclosure.url,
+                    Some(&enclosure.mime_type),
+                );
+            }
+            element(&mut out, 2, "published", &entry.published);
+            element(&mut out, 2, "updated", &entry.updated);
+            if let Some(author) = entry.author.as_ref() {
+                author_element(&mut out, 2, author);
+            }
+            if !entry.content.is_empty() {
+                out.push_str("    <content type=\"html\">");
+                escape_html(&entry.content, &mut out);
+                out.push_str("</content>\n");
+            } else if entry.link.is_none() {
+                // entries without an alternate link must have content
+                out.push_str("    <content type=\"text\">");
+                escape_html(&entry.title, &mut out);
+                out.push_str("</content>\n");
+            }
+            out.push_str("  </entry>\n");
+        }
+        out.push_str("</feed>\n");
+        out
+    }
+
+    /// Renders the feed as RSS 2.0 document.
+    ///
+    /// The authors of the entries are included as `dc:creator`, since the `author` element of
+    /// RSS requires an email address.
+    pub fn to_rss(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        out.push_str("<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+        out.push_str("  <channel>\n");
+        element(&mut out, 2, "title", &self.title);
+        // the link and description of the channel are required
+        element(&mut out, 2, "link", self.link.as_deref().unwrap_or(""));
+        element(
+            &mut out,
+            2,
+            "description",
+            self.description.as_deref().unwrap_or(&self.title),
+        );
+        if let Some(updated) = self.updated() {
+            element(&mut out, 2, "lastBuildDate", &rfc_2822(updated));
+        }
+        if let Some(author) = self.author.as_ref() {
+            element(&mut out, 2, "dc:creator", author);
+        }
+        for entry in &self.entries {
+            out.push_str("    <item>\n");
+            element(&mut out, 3, "title", &entry.title);
+            if let Some(link) = entry.link.as_ref() {
+                element(&mut out, 3, "link", link);
+            }
+            out.push_str("      <guid isPermaLink=\"false\">");
+            escape_html(&entry.id, &mut out);
+            out.push_str("</guid>\n");
+            element(&mut out, 3, "pubDate", &rfc_2822(&entry.published));
+            if let Some(author) = entry.author.as_ref() {
+                element(&mut out, 3, "dc:creator", author);
+            }
+            if !entry.content.is_empty() {
+                element(&mut out, 3, "description", &entry.content);
+            }
+            if let Some(enclosure) = entry.enclosure.as_ref() {
+                // the length of the image is not known, which RSS allows to be given as 0
+                out.push_str("      <enclosure url=\"");
+                escape_attribute(&enclosure.url, &mut out);
+                out.push_str("\" length=\"0\" type=\"");
+                escape_attribute(&enclosure.mime_type, &mut out);
+                out.push_str("\"/>\n");
+            }
+            out.push_str("    </item>\n");
+        }
+        out.push_str("  </channel>\n");
+        out.push_str("</rss>\n");
+        out
+    }
+}
+
+impl AbstractActivity {
+    /// Returns the member who performed the activity.
+    pub fn member(&self) -> &MemberSummary {
+        use AbstractActivity::*;
+
+        match self {
+            DiaryEntryActivity { member, .. }
+            | FilmLikeActivity { member, .. }
+            | FilmRatingActivity { member, .. }
+            | FilmWatchActivity { member, .. }
+            | FollowActivity { member, .. }
+            | InvitationAcceptedActivity { member, .. }
+            | ListActivity { member, .. }
+            | ListCommentActivity { member, .. }
+            | ListLikeActivity { member, .. }
+            | RegistrationActivity { member, .. }
+            | ReviewActivity { member, .. }
+            | ReviewCommentActivity { member, .. }
+            | ReviewLikeActivity { member, .. }
+            | WatchlistActivity { member, .. } => member,
+        }
+    }
+
+    /// Returns the timestamp of the activity, in ISO 8601 format with UTC timezone.
+    pub fn when_created(&self) -> &str {
+        use AbstractActivity::*;
+
+        match self {
+            DiaryEntryActivity { when_created, .. }
+            | FilmLikeActivity { when_created, .. }
+            | FilmRatingActivity { when_created, .. }
+            | FilmWatchActivity { when_created, .. }
+            | FollowActivity { when_created, .. }
+            | InvitationAcceptedActivity { when_created, .. }
+            | ListActivity { when_created, .. }
+            | ListCommentActivity { when_created, .. }
+            | ListLikeActivity { when_created, .. }
+            | RegistrationActivity { when_created, .. }
+            | ReviewActivity { when_created, .. }
+            | ReviewCommentActivity { when_created, .. }
+            | ReviewLikeActivity { when_created, .. }
+            | WatchlistActivity { when_created, .. } => when_created,
+        }
+    }
+}
+
+impl Entry {
+    /// Creates an entry of a log entry, with the review, the diary date and the rating.
+    pub fn from_log_entry(entry: &LogEntry) -> Self {
+        let mut content = String::new();
+        if let Some(diary_details) = entry.diary_details.as_ref() {
+            let verb = if diary_details.rewatch {
+                "Rewatched"
+            } else {
+                "Watched"
+            };
+            content.push_str(&format!("<p>{} on ", verb));
+            escape_html(&diary_details.diary_date, &mut content);
+            content.push_str("</p>");
+        }
+        if let Some(review) = entry.review.as_ref() {
+            push_lbml(&mut content, &review.lbml, review.contains_spoilers);
+        }
+        Self {
+            id: format!("{}log-entry/{}", TAG_PREFIX, entry.id),
+            title: film_title(&entry.film, entry.rating),
+            link: letterboxd_url(&entry.links)
+                .or_else(|| entry.film.letterboxd_url())
+                .map(String::from),
+            author: Some(entry.owner.display_name.clone()),
+            published: entry.when_created.clone(),
+            updated: entry.when_updated.clone(),
+            content,
+            enclosure: poster(&entry.film),
+        }
+    }
+
+    /// Creates an entry of an entry of a list, with the rank and the notes.
+    ///
+    /// List entries have no timestamps, so the entry is published when the list was published.
+    pub fn from_list_entry(list: &List, entry: &ListEntry) -> Self {
+        let mut title = film_title(&entry.film, None);
+        if let Some(rank) = entry.rank {
+            title = format!("{}. {}", rank, title);
+        }
+        let mut content = String::new();
+        if let Some(notes) = entry.notes_lbml.as_ref() {
+            push_lbml(
+                &mut content,
+                notes,
+                entry.contains_spoilers.unwrap_or(false),
+            );
+        }
+        let published = list.when_published.as_ref().unwrap_or(&list.when_created);
+        Self {
+            id: format!("{}list/{}/film/{}", TAG_PREFIX, list.id, entry.film.id),
+            title,
+            link: entry.film.letterboxd_url().map(String::from),
+            author: Some(list.owner.display_name.clone()),
+            published: published.clone(),
+            updated: published.clone(),
+            content,
+            enclosure: poster(&entry.film),
+        }
+    }
+
+    /// Creates an entry of an activity, titled with a sentence describing it, like
+    /// `Ada rated Parasite (2019) ★★★★½`.
+    pub fn from_activity(activity: &AbstractActivity) -> Self {
+        use AbstractActivity::*;
+
+        let member = activity.member();
+        let when_created = activity.when_created();
+        let name = &member.display_name;
+
+        let mut content = String::new();
+        let (kind, subject, title, link, film) = match activity {
+            DiaryEntryActivity { diary_entry, .. } => {
+                if let Some(review) = diary_entry.review.as_ref() {
+                    push_lbml(&mut content, &review.lbml, review.contains_spoilers);
+                }
+                (
+                    "diary-entry",
+                    diary_entry.id.as_str(),
+                    format!(
+                        "{} watched {}",
+                        name,
+                        film_title(&diary_entry.film, diary_entry.rating)
+                    ),
+                    letterboxd_url(&diary_entry.links),
+                    Some(&diary_entry.film),
+                )
+            }
+            FilmLikeActivity { film, .. } => film_activity("film-like", "liked", name, film),
+            FilmRatingActivity { film, rating, .. } => (
+                "film-rating",
+                film.id.as_str(),
+                format!("{} rated {}", name, film_title(film, Some(*rating))),
+                film.letterboxd_url(),
+                Some(film),
+            ),
+            FilmWatchActivity { film, .. } => film_activity("film-watch", "watched", name, film),
+            FollowActivity { followed, .. } => (
+                "follow",
+                followed.id.as_str(),
+                format!("{} followed {}", name, followed.display_name),
+                None,
+                None,
+            ),
+            InvitationAcceptedActivity { invitor, .. } => (
+                "invitation-accepted",
+                invitor.id.as_str(),
+                format!(
+                    "{} accepted an invitation from {}",
+                    name, invitor.display_name
+                ),
+                None,
+                None,
+            ),
+            ListActivity {
+                list, cloned_from, ..
+            } => {
+                if let Some(description) = list.description_lbml.as_ref() {
+                    push_lbml(&mut content, description, false);
+                }
+                let verb = if cloned_from.is_some() {
+                    "cloned"
+                } else {
+                    "published"
+                };
+                (
+                    "list",
+                    list.id.as_str(),
+                    format!("{} {} the list {}", name, verb, list.name),
+                    None,
+                    None,
+                )
+            }
+            ListCommentActivity { list, comment, .. } => {
+                push_lbml(&mut content, &comment.comment_lbml, false);
+                (
+                    "list-comment",
+                    comment.id.as_str(),
+                    format!("{} commented on the list {}", name, list.name),
+                    None,
+                    None,
+                )
+            }
+            ListLikeActivity { list, .. } => (
+                "list-like",
+                list.id.as_str(),
+                format!("{} liked the list {}", name, list.name),
+                None,
+                None,
+            ),
+            RegistrationActivity { member, .. } => (
+                "registration",
+                member.id.as_str(),
+                format!("{} joined Letterboxd", name),
+                None,
+                None,
+            ),
+            ReviewActivity { review, .. } => {
+                if let Some(details) = review.review.as_ref() {
+                    push_lbml(&mut content, &details.lbml, details.contains_spoilers);
+                }
+                (
+                    "review",
+                    review.id.as_str(),
+                    format!(
+                        "{} reviewed {}",
+                        name,
+                        film_title(&review.film, review.rating)
+                    ),
+                    letterboxd_url(&review.links),
+                    Some(&review.film),
+                )
+            }
+            ReviewCommentActivity {
+                review, comment, ..
+            } => {
+                push_lbml(&mut content, &comment.comment_lbml, false);
+                (
+                    "review-comment",
+                    comment.id.as_str(),
+                    format!(
+                        "{} commented on {}",
+                        name,
+                        review_of(&review.owner, &review.film)
+                    ),
+                    letterboxd_url(&review.links),
+                    Some(&review.film),
+                )
+            }
+            ReviewLikeActivity { review, .. } => (
+                "review-like",
+                review.id.as_str(),
+                format!("{} liked {}", name, review_of(&review.owner, &review.film)),
+                letterboxd_url(&review.links),
+                Some(&review.film),
+            ),
+            WatchlistActivity { film, .. } => (
+                "watchlist",
+                film.id.as_str(),
+                format!(
+                    "{} added {} to their watchlist",
+                    name,
+                    film_title(film, None)
+                ),
+                film.letterboxd_url(),
+                Some(film),
+            ),
+        };
+
+        Self {
+            id: format!(
+                "{}member/{}/activity/{}/{}/{}",
+                TAG_PREFIX, member.id, kind, subject, when_created
+            ),
+            title,
+            link: link.map(String::from),
+            author: Some(name.clone()),
+            published: when_created.to_string(),
+            updated: when_created.to_string(),
+            content,
+            enclosure: film.and_then(poster),
+        }
+    }
+}
+
+type ActivityParts<'a> = (
+    &'static str,
+    &'a str,
+    String,
+    Option<&'a str>,
+    Option<&'a FilmSummary>,
+);
+
+fn film_activity<'a>(
+    kind: &'static str,
+    verb: &str,
+    name: &str,
+    film: &'a FilmSummary,
+) -> ActivityParts<'a> {
+    (
+        kind,
+        film.id.as_str(),
+        format!("{} {} {}", name, verb, film_title(film, None)),
+        film.letterboxd_url(),
+        Some(film),
+    )
+}
+
+fn review_of(owner: &MemberSummary, film: &FilmSummary) -> String {
+    format!(
+        "{}’s review of {}",
+        owner.display_name,
+        film_title(film, None)
+    )
+}
+
+/// Appends LBML rendered as HTML, preceded by a warning if it contains spoilers.
+fn push_lbml(content: &mut String, text: &str, contains_spoilers: bool) {
+    if contains_spoilers {
+        content.push_str("<p><em>This text contains spoilers.</em></p>");
+    }
+    content.push_str("<div>");
+    content.push_str(&lbml::parse(text).to_html());
+    content.push_str("</div>");
+}
+
+/// Returns the largest poster of a film.
+fn poster(film: &FilmSummary) -> Option<Enclosure> {
+    let size = film.poster.as_ref()?.largest()?;
+    let path = size.url.split(['?', '#']).next().unwrap_or("");
+    let mime_type = match path.rsplit('.').next().map(str::to_ascii_lowercase) {
+        Some(extension) if extension == "png" => "image/png",
+        Some(extension) if extension == "webp" => "image/webp",
+        Some(extension) if extension == "gif" => "image/gif",
+        _ => "image/jpeg",
+    };
+    Some(Enclosure {
+        url: size.url.clone(),
+        mime_type: mime_type.to_string(),
+    })
+}
+
+/// Appends an element with escaped text, indented by the given level.
+fn element(out: &mut String, level: usize, name: &str, text: &str) {
+    out.push_str(&"  ".repeat(level));
+    out.push_str(&format!("<{}>", name));
+    escape_html(text, out);
+    out.push_str(&format!("</{}>\n", name));
+}
+
+fn link_element(out: &mut String, level: usize, rel: &str, href: &str, mime_type: Option<&str>) {
+    out.push_str(&"  ".repeat(level));
+    out.push_str(&format!("<link rel=\"{}\" href=\"", rel));
+    escape_attribute(href, out);
+    out.push('"');
+    if let Some(mime_type) = mime_type {
+        out.push_str(" type=\"");
+        escape_attribute(mime_type, out);
+        out.push('"');
+    }
+    out.push_str("/>\n");
+}
+
+fn author_element(out: &mut String, level: usize, name: &str) {
+    out.push_str(&"  ".repeat(level));
+    out.push_str("<author>\n");
+    element(out, level + 1, "name", name);
+    out.push_str(&"  ".repeat(level));
+    out.push_str("</author>\n");
+}
+
+/// Converts an ISO 8601 timestamp in UTC like `1997-08-29T07:14:00Z` to the date format of
+/// RSS, like `Fri, 29 Aug 1997 07:14:00 +0000`.
+fn rfc_2822(timestamp: &str) -> String {
+    let (year, month, day) = date::parse(timestamp).unwrap_or((1970, 1, 1));
+    let time = timestamp
+        .get(11..19)
+        .filter(|time| time.bytes().all(|b| b.is_ascii_digit() || b == b':'))
+        .unwrap_or("00:00:00");
+    let day_number = date::day_number(year as i64, month, day);
+    format!(
+        "{}, {:02} {} {:04} {} +0000",
+        WEEKDAYS[day_number.rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        time
+    )
+}
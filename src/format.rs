@@ -0,0 +1,26 @@
+//! Plain text formatting of films, shared by the calendar export and the feeds.
+
+use crate::defs::FilmSummary;
+
+/// Returns the name of a film with its release year and a rating, like `Parasite (2019) ★★★★½`.
+pub(crate) fn film_title(film: &FilmSummary, rating: Option<f32>) -> String {
+    let mut title = film.name.clone();
+    if let Some(release_year) = film.release_year {
+        title.push_str(&format!(" ({})", release_year));
+    }
+    if let Some(rating) = rating {
+        title.push(' ');
+        title.push_str(&stars(rating));
+    }
+    title
+}
+
+/// Formats a rating as stars, like `★★★★½`.
+fn stars(rating: f32) -> String {
+    let halves = (rating * 2.0).round().clamp(0.0, 10.0) as usize;
+    let mut stars = "★".repeat(halves / 2);
+    if halves % 2 == 1 {
+        stars.push('½');
+    }
+    stars
+}
@@ -0,0 +1,236 @@
+//! Export of diary entries as an iCalendar file (RFC 5545).
+//!
+//! Each log entry with a diary date becomes an event on that date, titled with the film's name,
+//! release year and rating. The UID of an event is derived from the LID of the log entry, so a
+//! calendar app which imports or subscribes to a re-export updates its events instead of
+//! duplicating them.
+//!
+//! Events are all-day by default. With [`with_start_time`](struct.IcsExport.html#method.with_start_time),
+//! they start at the given local time and last the runtime of the film, if it is known from
+//! [`with_films`](struct.IcsExport.html#method.with_films).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::ics::IcsExport;
+//! use letterboxd::{LogEntriesRequest, LogEntryRelationshipType, LogEntryStatus};
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let client = letterboxd::Client::new(api_key_pair);
+//!
+//!     let req = LogEntriesRequest {
+//!         member: Some("2bbs".to_string()),
+//!         member_relationship: Some(LogEntryRelationshipType::Owner),
+//!         where_logentry_status: vec![LogEntryStatus::HasDiaryDate],
+//!         ..Default::default()
+//!     };
+//!     let entries = client.log_entries(&req).await?.items;
+//!     let mut films = Vec::new();
+//!     for entry in &entries {
+//!         films.push(client.film(&entry.film.id).await?);
+//!     }
+//!
+//!     let export = IcsExport::new()
+//!         .with_name("My diary")
+//!         .with_start_time(20, 0)
+//!         .with_films(&films);
+//!     export.write(std::fs::File::create("diary.ics")?, &entries)
+//! };
+//! ```
+
+use crate::crossref::letterboxd_url;
+use crate::date;
+use crate::defs::{Film, LogEntry};
+use crate::error::Result;
+use crate::format::film_title;
+
+use std::collections::HashMap;
+use std::io;
+
+/// Identifier of the product which created the calendar.
+const PRODID: &str = "-//letterboxd-rs//Diary//EN";
+/// Maximum length of a content line in octets, excluding the line break.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Settings of an iCalendar export of diary entries.
+#[derive(Debug, Clone, Default)]
+pub struct IcsExport {
+    name: Option<String>,
+    /// Start of timed events in minutes after midnight.
+    start_time: Option<u32>,
+    /// Runtimes of films in minutes by LID.
+    run_times: HashMap<String, u16>,
+}
+
+impl IcsExport {
+    /// Creates an export of all-day events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the calendar shown by calendar apps.
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Lets events start at the given local time and last the runtime of the film.
+    ///
+    /// Events of films with an unknown runtime stay all-day events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hour is not below 24 or the minute is not below 60.
+    pub fn with_start_time(mut self, hour: u32, minute: u32) -> Self {
+        assert!(hour < 24 && minute < 60, "invalid start time");
+        self.start_time = Some(hour * 60 + minute);
+        self
+    }
+
+    /// Adds the runtimes of films, which are included in the events.
+    pub fn with_films(mut self, films: &[Film]) -> Self {
+        for film in films {
+            if film.run_time > 0 {
+                self.run_times.insert(film.id.clone(), film.run_time);
+            }
+        }
+        self
+    }
+
+    /// Writes the calendar with an event for each entry with a diary date.
+    pub fn write<W: io::Write>(&self, mut writer: W, entries: &[LogEntry]) -> Result<()> {
+        writer.write_all(self.to_ics(entries).as_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the calendar with an event for each entry with a diary date.
+    pub fn to_ics(&self, entries: &[LogEntry]) -> String {
+        let mut out = String::new();
+        content_line(&mut out, "BEGIN", "VCALENDAR");
+        content_line(&mut out, "VERSION", "2.0");
+        content_line(&mut out, "PRODID", PRODID);
+        content_line(&mut out, "CALSCALE", "GREGORIAN");
+        content_line(&mut out, "METHOD", "PUBLISH");
+        if let Some(name) = self.name.as_ref() {
+            content_line(&mut out, "X-WR-CALNAME", &escape(name));
+        }
+        for entry in entries {
+            self.write_event(&mut out, entry);
+        }
+        content_line(&mut out, "END", "VCALENDAR");
+        out
+    }
+
+    fn write_event(&self, out: &mut String, entry: &LogEntry) {
+        let diary_details = match entry.diary_details.as_ref() {
+            Some(diary_details) => diary_details,
+            None => return,
+        };
+        let (year, month, day) = match date::parse(&diary_details.diary_date) {
+            Some(date) => date,
+            None => return,
+        };
+        let day = date::day_number(year as i64, month, day);
+        let run_time = self.run_times.get(&entry.film.id).copied();
+
+        content_line(out, "BEGIN", "VEVENT");
+        content_line(out, "UID", &format!("{}@letterboxd.com", entry.id));
+        content_line(out, "DTSTAMP", &utc_timestamp(&entry.when_updated));
+        match (self.start_time, run_time) {
+            (Some(start_time), Some(run_time)) => {
+                let end_time = start_time as i64 + run_time as i64;
+                content_line(out, "DTSTART", &local_time(day, start_time as i64));
+                content_line(out, "DTEND", &local_time(day, end_time));
+            }
+            _ => {
+                content_line(out, "DTSTART;VALUE=DATE", &basic_date(day));
+                content_line(out, "DTEND;VALUE=DATE", &basic_date(day + 1));
+            }
+        }
+
+        let summary = film_title(&entry.film, entry.rating);
+        content_line(out, "SUMMARY", &escape(&summary));
+
+        let mut description = Vec::new();
+        if diary_details.rewatch {
+            description.push("Rewatch".to_string());
+        }
+        if let Some(run_time) = run_time {
+            description.push(format!("Runtime: {} min", run_time));
+        }
+        if !description.is_empty() {
+            content_line(out, "DESCRIPTION", &escape(&description.join("\n")));
+        }
+        let url = letterboxd_url(&entry.links).or_else(|| entry.film.letterboxd_url());
+        if let Some(url) = url {
+            content_line(out, "URL", url);
+        }
+        content_line(out, "END", "VEVENT");
+    }
+}
+
+/// Appends a content line, folded after 75 octets and terminated by CRLF.
+fn content_line(out: &mut String, name: &str, value: &str) {
+    let line = format!("{}:{}", name, value);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            out.push_str("\r\n ");
+            // the space of the continuation line counts towards its length
+            length = 1;
+        }
+        out.push(c);
+        length += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Escapes a TEXT value.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats a day number as DATE value, i.e. YYYYMMDD.
+fn basic_date(day: i64) -> String {
+    let (year, month, day) = date::from_day_number(day);
+    format!("{:04}{:02}{:02}", year, month, day)
+}
+
+/// Formats a floating local DATE-TIME value of minutes after the start of a day.
+fn local_time(day: i64, minutes: i64) -> String {
+    let day = day + minutes.div_euclid(24 * 60);
+    let minutes = minutes.rem_euclid(24 * 60);
+    format!(
+        "{}T{:02}{:02}00",
+        basic_date(day),
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// Converts an ISO 8601 timestamp in UTC like `2020-01-01T10:00:00.123Z` to a UTC DATE-TIME
+/// value like `20200101T100000Z`.
+fn utc_timestamp(timestamp: &str) -> String {
+    let digits: String = timestamp
+        .chars()
+        .take_while(|&c| c != '.' && c != 'Z' && c != '+')
+        .filter(|c| c.is_ascii_digit() || *c == 'T')
+        .collect();
+    if digits.len() == 15 {
+        format!("{}Z", digits)
+    } else {
+        "19700101T000000Z".to_string()
+    }
+}
@@ -0,0 +1,135 @@
+//! Selection of image sizes and cropping of backdrops.
+//!
+//! Images like posters, backdrops and avatars are provided by the API in multiple sizes. The
+//! methods of [`Image`](../struct.Image.html) pick the size fitting a rendered width, or describe
+//! all sizes as a responsive `srcset`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use letterboxd::{Image, ImageSize};
+//!
+//! let size = |width, height| ImageSize {
+//!     width,
+//!     height,
+//!     url: format!("https://a.ltrbxd.com/poster-{}.jpg", width),
+//! };
+//! let poster = Image {
+//!     sizes: vec![size(460, 690), size(70, 105), size(230, 345)],
+//! };
+//!
+//! assert_eq!(poster.best_for_width(230, 1.0).unwrap().width, 230);
+//! assert_eq!(poster.best_for_width(230, 2.0).unwrap().width, 460);
+//! assert_eq!(poster.largest().unwrap().width, 460);
+//! assert_eq!(
+//!     poster.to_srcset(),
+//!     "https://a.ltrbxd.com/poster-70.jpg 70w, \
+//!      https://a.ltrbxd.com/poster-230.jpg 230w, \
+//!      https://a.ltrbxd.com/poster-460.jpg 460w"
+//! );
+//! ```
+
+use crate::defs::{Film, Image, ImageSize};
+
+/// A rectangle in pixels of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CropRect {
+    /// The horizontal offset from the left edge.
+    pub x: usize,
+    /// The vertical offset from the top edge.
+    pub y: usize,
+    /// The width of the rectangle.
+    pub width: usize,
+    /// The height of the rectangle.
+    pub height: usize,
+}
+
+impl Image {
+    /// Returns the size with the largest width, if any.
+    pub fn largest(&self) -> Option<&ImageSize> {
+        self.sizes.iter().max_by_key(|size| size.width)
+    }
+
+    /// Returns the size with the smallest width, if any.
+    pub fn smallest(&self) -> Option<&ImageSize> {
+        self.sizes.iter().min_by_key(|size| size.width)
+    }
+
+    /// Returns the best size for rendering the image at a width in CSS pixels on a display
+    /// with the given device pixel ratio.
+    ///
+    /// This is the smallest size which is at least as wide as the width in device pixels, or
+    /// the largest size if none is wide enough.
+    pub fn best_for_width(&self, width: usize, device_pixel_ratio: f32) -> Option<&ImageSize> {
+        let target = (width as f32 * device_pixel_ratio.max(0.0)).ceil() as usize;
+        self.sizes
+            .iter()
+            .filter(|size| size.width >= target)
+            .min_by_key(|size| size.width)
+            .or_else(|| self.largest())
+    }
+
+    /// Returns the value of an HTML `srcset` attribute with width descriptors, ordered by width.
+    ///
+    /// Sizes with the same width are only included once.
+    pub fn to_srcset(&self) -> String {
+        let mut sizes: Vec<&ImageSize> = self.sizes.iter().collect();
+        sizes.sort_by_key(|size| size.width);
+        sizes.dedup_by_key(|size| size.width);
+        sizes
+            .iter()
+            .map(|size| format!("{} {}w", size.url, size.width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl ImageSize {
+    /// Returns the largest rectangle with the given aspect ratio (width / height) within the
+    /// image, keeping the vertical focal point in view.
+    ///
+    /// The focal point is a proportion of the image's height between 0.0 and 1.0. If the image
+    /// is taller than the aspect ratio, the rectangle is centered on the focal point as far as
+    /// possible. If it is wider, the rectangle is centered horizontally.
+    pub fn crop_rect(&self, aspect_ratio: f32, focal_point: f32) -> CropRect {
+        let (width, height) = (self.width as f32, self.height as f32);
+        if aspect_ratio <= 0.0 || self.width == 0 || self.height == 0 {
+            return CropRect {
+                x: 0,
+                y: 0,
+                width: self.width,
+                height: self.height,
+            };
+        }
+        if width / height > aspect_ratio {
+            let crop_width = (height * aspect_ratio).round().min(width);
+            CropRect {
+                x: ((width - crop_width) / 2.0).round() as usize,
+                y: 0,
+                width: crop_width as usize,
+                height: self.height,
+            }
+        } else {
+            let crop_height = (width / aspect_ratio).round().min(height);
+            let center = height * focal_point.clamp(0.0, 1.0);
+            let y = (center - crop_height / 2.0)
+                .max(0.0)
+                .min(height - crop_height)
+                .round();
+            CropRect {
+                x: 0,
+                y: y as usize,
+                width: self.width,
+                height: crop_height as usize,
+            }
+        }
+    }
+}
+
+impl Film {
+    /// Returns the crop rectangle of a size of the film's backdrop for the given aspect ratio
+    /// (width / height), keeping the backdrop's focal point in view.
+    pub fn backdrop_crop_rect(&self, size: &ImageSize, aspect_ratio: f32) -> CropRect {
+        size.crop_rect(aspect_ratio, self.backdrop_focal_point)
+    }
+}
@@ -0,0 +1,550 @@
+//! Import of a Letterboxd account export into an account.
+//!
+//! An import is done in three steps:
+//!
+//! 1. The CSV files of an export are read into an [`ImportSet`](struct.ImportSet.html), either
+//!    from the ZIP archive or from an extracted directory.
+//! 2. Each row is matched to a film LID by an [`ImportResolver`](struct.ImportResolver.html),
+//!    which searches for the film by name and compares the Letterboxd URI of the row with the
+//!    film links, falling back to name and release year. The URI of diary and review rows is the
+//!    one of the log entry, so these rows are matched by name and release year only.
+//! 3. The rows are compared with the current state of the target account (as fetched by
+//!    [`Export::fetch`](../export/struct.Export.html#method.fetch)) which results in a
+//!    [`Plan`](struct.Plan.html) of relationship updates and log entry creations.
+//!
+//! The plan can be printed as a dry-run report before it is applied.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::{export::Export, import::{ImportSet, Plan}};
+//!
+//! # async fn run(client: letterboxd::Client) -> letterboxd::Result<()> {
+//! let import = ImportSet::from_zip(std::fs::File::open("letterboxd-export.zip")?)?;
+//! let target = Export::fetch(&client, "member-lid").await?;
+//! let plan = Plan::build(&client, &import, &target).await?;
+//! println!("{}", plan); // dry run
+//! plan.apply(&client).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::Client;
+use crate::defs::{
+    AbstractSearchItem, FilmRelationshipUpdateRequest, FilmSummary, Link, LogEntryCreationRequest,
+    LogEntryCreationRequestDiaryDetails, LogEntryCreationRequestReview, SearchRequest,
+    SearchResultType,
+};
+use crate::error::Result;
+use crate::export::Export;
+
+use serde::Deserialize;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A row of one of the CSV files of a Letterboxd export.
+///
+/// The files share most of their columns; columns which are not present in a file are `None`.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Row {
+    /// The date the row was created, i.e. YYYY-MM-DD.
+    #[serde(rename = "Date")]
+    pub date: Option<String>,
+    /// The name of the film.
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// The release year of the film.
+    #[serde(rename = "Year")]
+    pub year: Option<u16>,
+    /// The Letterboxd URI of the film, or of the log entry in diary and reviews.
+    ///
+    /// Only film URIs are used to match rows to films.
+    #[serde(rename = "Letterboxd URI")]
+    pub letterboxd_uri: Option<String>,
+    /// The rating between 0.5 and 5.0.
+    #[serde(rename = "Rating")]
+    pub rating: Option<f32>,
+    /// `Yes` if the film was watched before.
+    #[serde(rename = "Rewatch")]
+    pub rewatch: Option<String>,
+    /// The review text in LBML.
+    #[serde(rename = "Review")]
+    pub review: Option<String>,
+    /// The tags separated by `, `.
+    #[serde(rename = "Tags")]
+    pub tags: Option<String>,
+    /// The diary date, i.e. YYYY-MM-DD.
+    #[serde(rename = "Watched Date")]
+    pub watched_date: Option<String>,
+}
+
+impl Row {
+    /// Returns true if the row is marked as rewatch.
+    pub fn is_rewatch(&self) -> bool {
+        self.rewatch.as_deref() == Some("Yes")
+    }
+
+    /// Returns the tags of the row.
+    pub fn tags(&self) -> Vec<String> {
+        self.tags
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns the row without the URI of its log entry, so that it is matched to a film by name
+    /// and release year.
+    fn without_log_entry_uri(&self) -> Row {
+        Row {
+            letterboxd_uri: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// The rows of the CSV files of a Letterboxd export.
+#[derive(Debug, Clone, Default)]
+pub struct ImportSet {
+    /// Rows of `diary.csv`.
+    pub diary: Vec<Row>,
+    /// Rows of `reviews.csv`.
+    pub reviews: Vec<Row>,
+    /// Rows of `ratings.csv`.
+    pub ratings: Vec<Row>,
+    /// Rows of `watched.csv`.
+    pub watched: Vec<Row>,
+    /// Rows of `watchlist.csv`.
+    pub watchlist: Vec<Row>,
+}
+
+impl ImportSet {
+    /// Reads the CSV files from an extracted export directory.
+    ///
+    /// Missing files are treated as empty.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+        let read = |name: &str| -> Result<Vec<Row>> {
+            match fs::File::open(dir.join(name)) {
+                Ok(file) => Self::read_rows(file),
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+                Err(e) => Err(e.into()),
+            }
+        };
+        Ok(Self {
+            diary: read("diary.csv")?,
+            reviews: read("reviews.csv")?,
+            ratings: read("ratings.csv")?,
+            watched: read("watched.csv")?,
+            watchlist: read("watchlist.csv")?,
+        })
+    }
+
+    /// Reads the CSV files from an export ZIP archive.
+    ///
+    /// Missing files are treated as empty.
+    pub fn from_zip<R: io::Read + io::Seek>(reader: R) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+        let mut read = |name: &str| -> Result<Vec<Row>> {
+            match archive.by_name(name) {
+                Ok(file) => Self::read_rows(file),
+                Err(zip::result::ZipError::FileNotFound) => Ok(Vec::new()),
+                Err(e) => Err(e.into()),
+            }
+        };
+        Ok(Self {
+            diary: read("diary.csv")?,
+            reviews: read("reviews.csv")?,
+            ratings: read("ratings.csv")?,
+            watched: read("watched.csv")?,
+            watchlist: read("watchlist.csv")?,
+        })
+    }
+
+    /// Reads the rows of a single CSV file.
+    pub fn read_rows<R: io::Read>(reader: R) -> Result<Vec<Row>> {
+        let mut rdr = csv::Reader::from_reader(reader);
+        let rows = rdr.deserialize().collect::<std::result::Result<_, _>>()?;
+        Ok(rows)
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &Row> {
+        self.diary
+            .iter()
+            .chain(&self.reviews)
+            .chain(&self.ratings)
+            .chain(&self.watched)
+            .chain(&self.watchlist)
+    }
+}
+
+/// Matches export rows to film LIDs.
+///
+/// Search results are cached by film name, so that each film is searched only once.
+#[derive(Debug, Default)]
+pub struct ImportResolver {
+    candidates: HashMap<String, Vec<FilmSummary>>,
+}
+
+impl ImportResolver {
+    /// Creates a new resolver with an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the LID of the film of a row.
+    ///
+    /// Returns `None` if no film matches the row.
+    pub async fn resolve(&mut self, client: &Client, row: &Row) -> Result<Option<String>> {
+        if !self.candidates.contains_key(&row.name) {
+            let req = SearchRequest {
+                include: Some(vec![SearchResultType::FilmSearchItem]),
+                ..SearchRequest::new(row.name.clone())
+            };
+            let resp = client.search(&req).await?;
+            let films = resp
+                .items
+                .into_iter()
+                .filter_map(|item| match item {
+                    AbstractSearchItem::FilmSearchItem { film, .. } => Some(film),
+                    _ => None,
+                })
+                .collect();
+            self.candidates.insert(row.name.clone(), films);
+        }
+        Ok(self.get(row))
+    }
+
+    /// Returns the LID of the film of a row from the cache.
+    pub fn get(&self, row: &Row) -> Option<String> {
+        let candidates = self.candidates.get(&row.name)?;
+        let by_uri = row
+            .letterboxd_uri
+            .as_deref()
+            .and_then(|uri| candidates.iter().find(|film| is_film_uri(film, uri)));
+        by_uri
+            .or_else(|| {
+                candidates.iter().find(|film| {
+                    film.name.eq_ignore_ascii_case(&row.name)
+                        && (row.year.is_none() || film.release_year == row.year)
+                })
+            })
+            .map(|film| film.id.clone())
+    }
+}
+
+fn is_film_uri(film: &FilmSummary, uri: &str) -> bool {
+    let uri = uri.trim_end_matches('/');
+    if uri == format!("https://boxd.it/{}", film.id) {
+        return true;
+    }
+    film.links.iter().flatten().any(|link| match link {
+        Link::Letterboxd { url, .. } => url.trim_end_matches('/') == uri,
+        _ => false,
+    })
+}
+
+/// A planned update of the relationship between the target member and a film.
+#[derive(Debug, Clone)]
+pub struct RelationshipUpdate {
+    /// The LID of the film.
+    pub film_id: String,
+    /// The name of the film.
+    pub film_name: String,
+    /// The request to send.
+    pub request: FilmRelationshipUpdateRequest,
+}
+
+/// A planned creation of a log entry.
+#[derive(Debug, Clone)]
+pub struct LogEntryCreation {
+    /// The name of the film.
+    pub film_name: String,
+    /// The request to send.
+    pub request: LogEntryCreationRequest,
+}
+
+/// The changes needed to make a target account match an import.
+///
+/// Only additions are planned: data of the target account which is not part of the import is
+/// left untouched.
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    /// Relationship updates for watched, rated and watchlisted films.
+    pub relationship_updates: Vec<RelationshipUpdate>,
+    /// Diary entries and reviews to create.
+    pub log_entries: Vec<LogEntryCreation>,
+    /// Rows which could not be matched to a film.
+    pub unmatched: Vec<Row>,
+}
+
+#[derive(Debug, Default)]
+struct Desired {
+    name: String,
+    watched: bool,
+    rating: Option<f32>,
+    in_watchlist: bool,
+}
+
+impl Plan {
+    /// Resolves all rows of an import and plans the changes for the target account.
+    pub async fn build(client: &Client, import: &ImportSet, target: &Export) -> Result<Self> {
+        let mut resolver = ImportResolver::new();
+        for row in import.rows() {
+            resolver.resolve(client, row).await?;
+        }
+        Ok(Self::new(import, |row| resolver.get(row), target))
+    }
+
+    /// Plans the changes for the target account with the given film resolution.
+    pub fn new<F>(import: &ImportSet, resolve: F, target: &Export) -> Self
+    where
+        F: Fn(&Row) -> Option<String>,
+    {
+        let mut plan = Plan::default();
+        let mut unmatched: Vec<Row> = Vec::new();
+        let diary_rows: Vec<Row> = import
+            .diary
+            .iter()
+            .map(Row::without_log_entry_uri)
+            .collect();
+        let review_rows: Vec<Row> = import
+            .reviews
+            .iter()
+            .map(Row::without_log_entry_uri)
+            .collect();
+        let mut resolve = |row: &Row| {
+            let film_id = resolve(row);
+            if film_id.is_none()
+                && !unmatched
+                    .iter()
+                    .any(|r| r.name == row.name && r.year == row.year)
+            {
+                unmatched.push(row.clone());
+            }
+            film_id
+        };
+
+        // desired relationships
+        let mut desired: BTreeMap<String, Desired> = BTreeMap::new();
+        let sources = [
+            (&import.watched, true, false),
+            (&diary_rows, true, false),
+            (&import.ratings, true, true),
+            (&import.watchlist, false, false),
+        ];
+        for (rows, watched, rated) in sources.iter() {
+            for row in rows.iter() {
+                if let Some(film_id) = resolve(row) {
+                    let entry = desired.entry(film_id).or_default();
+                    entry.name = row.name.clone();
+                    entry.watched |= *watched;
+                    entry.in_watchlist |= !*watched;
+                    if *rated {
+                        entry.rating = row.rating;
+                    }
+                }
+            }
+        }
+
+        // current state of target
+        let mut watched = HashMap::new();
+        for film in &target.watched {
+            let rating = film
+                .relationships
+                .iter()
+                .find(|r| r.member.id == target.member)
+                .and_then(|r| r.relationship.rating);
+            watched.insert(film.id.as_str(), rating);
+        }
+        let watchlist: HashSet<&str> = target.watchlist.iter().map(|f| f.id.as_str()).collect();
+        let diary: HashSet<(&str, &str)> = target
+            .log_entries
+            .iter()
+            .filter_map(|e| {
+                let diary_details = e.diary_details.as_ref()?;
+                Some((e.film.id.as_str(), diary_details.diary_date.as_str()))
+            })
+            .collect();
+        let reviewed: HashSet<&str> = target
+            .log_entries
+            .iter()
+            .filter(|e| e.review.is_some())
+            .map(|e| e.film.id.as_str())
+            .collect();
+
+        for (film_id, desired) in desired {
+            let current = watched.get(film_id.as_str());
+            let mut request = FilmRelationshipUpdateRequest::default();
+            if desired.watched && current.is_none() {
+                request.watched = Some(true);
+            }
+            if desired.rating.is_some() && current.copied().flatten() != desired.rating {
+                request.rating = desired.rating;
+            }
+            if desired.in_watchlist && !desired.watched && !watchlist.contains(film_id.as_str()) {
+                request.in_watchlist = Some(true);
+            }
+            if request.watched.is_some()
+                || request.rating.is_some()
+                || request.in_watchlist.is_some()
+            {
+                plan.relationship_updates.push(RelationshipUpdate {
+                    film_id,
+                    film_name: desired.name,
+                    request,
+                });
+            }
+        }
+
+        // diary entries, with reviews written on the same date
+        let mut unused_reviews: Vec<&Row> = review_rows.iter().collect();
+        for row in &diary_rows {
+            let (film_id, diary_date) = match (resolve(row), row.watched_date.as_ref()) {
+                (Some(film_id), Some(diary_date)) => (film_id, diary_date),
+                _ => continue,
+            };
+            if diary.contains(&(film_id.as_str(), diary_date.as_str())) {
+                continue;
+            }
+            // each review is attached to at most one diary entry
+            let review = unused_reviews.iter().position(|r| {
+                r.name == row.name && r.year == row.year && r.watched_date == row.watched_date
+            });
+            let mut request = LogEntryCreationRequest {
+                diary_details: Some(LogEntryCreationRequestDiaryDetails {
+                    diary_date: diary_date.clone(),
+                    rewatch: row.is_rewatch(),
+                }),
+                tags: row.tags(),
+                rating: row.rating,
+                ..LogEntryCreationRequest::new(film_id)
+            };
+            if let Some(index) = review {
+                let review = unused_reviews.remove(index);
+                request.review = review.review.as_deref().map(review_request);
+            }
+            plan.log_entries.push(LogEntryCreation {
+                film_name: row.name.clone(),
+                request,
+            });
+        }
+
+        // reviews without diary entry, including dated reviews whose diary row is missing or
+        // already in the target
+        for row in unused_reviews {
+            let film_id = match resolve(row) {
+                Some(film_id) => film_id,
+                None => continue,
+            };
+            if reviewed.contains(film_id.as_str()) {
+                continue;
+            }
+            plan.log_entries.push(LogEntryCreation {
+                film_name: row.name.clone(),
+                request: LogEntryCreationRequest {
+                    review: row.review.as_deref().map(review_request),
+                    tags: row.tags(),
+                    rating: row.rating,
+                    ..LogEntryCreationRequest::new(film_id)
+                },
+            });
+        }
+
+        plan.unmatched = unmatched;
+        plan
+    }
+
+    /// Returns true if there is nothing to change.
+    pub fn is_empty(&self) -> bool {
+        self.relationship_updates.is_empty() && self.log_entries.is_empty()
+    }
+
+    /// Sends all planned requests.
+    ///
+    /// The client must be authenticated as the target member.
+    pub async fn apply(&self, client: &Client) -> Result<()> {
+        for update in &self.relationship_updates {
+            client
+                .update_film_relationship(&update.film_id, &update.request)
+                .await?;
+        }
+        for creation in &self.log_entries {
+            client.create_log_entry(&creation.request).await?;
+        }
+        Ok(())
+    }
+}
+
+fn review_request(text: &str) -> LogEntryCreationRequestReview {
+    LogEntryCreationRequestReview {
+        text: text.to_string(),
+        contains_spoilers: false,
+        share: Vec::new(),
+    }
+}
+
+/// Formats the plan as dry-run report.
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Relationship updates: {}",
+            self.relationship_updates.len()
+        )?;
+        for update in &self.relationship_updates {
+            let mut changes = Vec::new();
+            if update.request.watched == Some(true) {
+                changes.push(String::from("watched"));
+            }
+            if let Some(rating) = update.request.rating {
+                changes.push(format!("rating {}", rating));
+            }
+            if update.request.in_watchlist == Some(true) {
+                changes.push(String::from("watchlist"));
+            }
+            writeln!(
+                f,
+                "  {} ({}): {}",
+                update.film_name,
+                update.film_id,
+                changes.join(", ")
+            )?;
+        }
+
+        writeln!(f, "Log entries: {}", self.log_entries.len())?;
+        for creation in &self.log_entries {
+            let request = &creation.request;
+            write!(f, "  {} ({})", creation.film_name, request.film_id)?;
+            if let Some(ref diary_details) = request.diary_details {
+                write!(f, " on {}", diary_details.diary_date)?;
+                if diary_details.rewatch {
+                    f.write_str(" (rewatch)")?;
+                }
+            }
+            if let Some(rating) = request.rating {
+                write!(f, ", rating {}", rating)?;
+            }
+            if request.review.is_some() {
+                f.write_str(", with review")?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "Unmatched rows: {}", self.unmatched.len())?;
+        for row in &self.unmatched {
+            match row.year {
+                Some(year) => writeln!(f, "  {} ({})", row.name, year)?,
+                None => writeln!(f, "  {}", row.name)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1232 @@
+//! Parsing and rendering of LBML, the markup of reviews, notes, descriptions and bios.
+//!
+//! LBML is text with a small subset of HTML: `<br>`, `<strong>`, `<em>`, `<b>`, `<i>`,
+//! `<a href="">` and `<blockquote>`. Fields like `Review::lbml`, `List::description_lbml`,
+//! `ListEntry::notes_lbml` and `Member::bio_lbml` contain LBML.
+//!
+//! [`parse`](fn.parse.html) is lenient and sanitizes its input: tags outside of the allow-list
+//! are removed while their text is kept (except for `<script>` and `<style>`, which are removed
+//! completely), links with other schemes than `http`, `https` and `mailto` are replaced by their
+//! text, and unclosed tags are closed. The resulting [`Document`](struct.Document.html) renders
+//! to safe HTML, CommonMark and plain text.
+//!
+//! For writing, [`from_markdown`](fn.from_markdown.html) converts Markdown to LBML and
+//! [`validate`](fn.validate.html) checks LBML strictly against the allow-list and the limit of
+//! [`MAX_LENGTH`](constant.MAX_LENGTH.html) characters, reporting the line and column of
+//! violations instead of having them rejected or stripped by the API.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let doc = letterboxd::lbml::parse(
+//!     "A <b>must</b> see.<br><a href=\"https://boxd.it/2a9q\">Fight Club</a><script>x</script>",
+//! );
+//! assert_eq!(
+//!     doc.to_html(),
+//!     "A <b>must</b> see.<br><a href=\"https://boxd.it/2a9q\" rel=\"nofollow\">Fight Club</a>"
+//! );
+//! assert_eq!(
+//!     doc.to_markdown(),
+//!     "A **must** see.\\\n[Fight Club](https://boxd.it/2a9q)"
+//! );
+//! assert_eq!(doc.to_text(), "A must see.\nFight Club (https://boxd.it/2a9q)");
+//! ```
+//!
+//! ```rust
+//! use letterboxd::lbml;
+//!
+//! let review = lbml::from_markdown("A **must** see.\n\n> The first rule...")?;
+//! assert_eq!(review, "A <strong>must</strong> see.\n\n<blockquote>The first rule...</blockquote>");
+//!
+//! let err = lbml::validate("A <u>must</u> see.").unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "invalid LBML: tag <u> is not allowed at line 1, column 3"
+//! );
+//! # Ok::<_, letterboxd::Error>(())
+//! ```
+
+use crate::error::{Error, Result};
+
+use std::fmt::{self, Write};
+
+/// Maximum length in characters of LBML fields like `LogEntryCreationRequestReview::text`.
+pub const MAX_LENGTH: usize = 100_000;
+
+/// Tags allowed in LBML.
+const ALLOWED_TAGS: [&str; 7] = ["br", "strong", "em", "b", "i", "a", "blockquote"];
+/// Tags whose content is removed together with the tag.
+const REMOVED_WITH_CONTENT: [&str; 2] = ["script", "style"];
+/// Allowed schemes of link targets.
+const LINK_SCHEMES: [&str; 3] = ["http:", "https:", "mailto:"];
+
+/// A node of an LBML document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    /// Text, which may contain line breaks.
+    Text(String),
+    /// A line break (`<br>`).
+    LineBreak,
+    /// Strong importance (`<strong>`).
+    Strong(Vec<Node>),
+    /// Emphasis (`<em>`).
+    Emphasis(Vec<Node>),
+    /// Bold text (`<b>`).
+    Bold(Vec<Node>),
+    /// Italic text (`<i>`).
+    Italic(Vec<Node>),
+    /// A link (`<a href="">`).
+    Link {
+        /// The target of the link.
+        href: String,
+        /// The content of the link.
+        children: Vec<Node>,
+    },
+    /// A quotation (`<blockquote>`).
+    Blockquote(Vec<Node>),
+}
+
+impl Node {
+    /// Returns the child nodes, if the node is an element.
+    pub fn children(&self) -> &[Node] {
+        match self {
+            Node::Text(_) | Node::LineBreak => &[],
+            Node::Strong(children)
+            | Node::Emphasis(children)
+            | Node::Bold(children)
+            | Node::Italic(children)
+            | Node::Link { children, .. }
+            | Node::Blockquote(children) => children,
+        }
+    }
+}
+
+/// A parsed and sanitized LBML document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Document {
+    /// The top-level nodes.
+    pub nodes: Vec<Node>,
+}
+
+/// A violation of the LBML rules found by [`validate`](fn.validate.html) or
+/// [`from_markdown`](fn.from_markdown.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The line of the violation, starting at 1.
+    pub line: usize,
+    /// The column in characters of the violation, starting at 1.
+    pub column: usize,
+    /// The description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+/// Parses and sanitizes LBML.
+pub fn parse(lbml: &str) -> Document {
+    Parser::new(lbml).parse()
+}
+
+/// Removes everything outside of the LBML allow-list, see [`parse`](fn.parse.html).
+pub fn sanitize(lbml: &str) -> String {
+    parse(lbml).to_lbml()
+}
+
+impl Document {
+    /// Renders the document as LBML.
+    pub fn to_lbml(&self) -> String {
+        let mut out = String::new();
+        render_html(&self.nodes, false, &mut out);
+        out
+    }
+
+    /// Renders the document as HTML.
+    ///
+    /// Text is escaped, line breaks in text become `<br>`, and links get `rel="nofollow"`.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        render_html(&self.nodes, true, &mut out);
+        out
+    }
+
+    /// Renders the document as CommonMark.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        render_markdown(&self.nodes, &mut out);
+        out.trim_end().to_string()
+    }
+
+    /// Renders the document as plain text.
+    ///
+    /// Links are followed by their target in parentheses, unless their text is the target.
+    /// Quotations are prefixed with `> `.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        render_text(&self.nodes, &mut out);
+        out.trim_end().to_string()
+    }
+
+    /// Returns the text of the document without any markup.
+    pub fn plain_text(&self) -> String {
+        fn collect(nodes: &[Node], out: &mut String) {
+            for node in nodes {
+                match node {
+                    Node::Text(text) => out.push_str(text),
+                    Node::LineBreak => out.push('\n'),
+                    node => collect(node.children(), out),
+                }
+            }
+        }
+        let mut out = String::new();
+        collect(&self.nodes, &mut out);
+        out
+    }
+}
+
+// parsing
+
+/// An allowed element which is not closed yet.
+struct Open {
+    name: &'static str,
+    href: Option<String>,
+    children: Vec<Node>,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// The root followed by the open elements.
+    stack: Vec<Open>,
+}
+
+/// A tag in the input.
+struct Tag<'a> {
+    name: String,
+    closing: bool,
+    attributes: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            stack: vec![Open {
+                name: "",
+                href: None,
+                children: Vec::new(),
+            }],
+        }
+    }
+
+    fn parse(mut self) -> Document {
+        let mut text = String::new();
+        while self.pos < self.input.len() {
+            let rest = &self.input[self.pos..];
+            let next_tag = rest.find('<').unwrap_or(rest.len());
+            text.push_str(&rest[..next_tag]);
+            self.pos += next_tag;
+            if self.pos == self.input.len() {
+                break;
+            }
+            match self.tag() {
+                Some((tag, len)) => {
+                    self.push_text(&mut text);
+                    self.pos += len;
+                    self.handle(tag);
+                }
+                None => {
+                    // not a tag, e.g. `a < b`
+                    text.push('<');
+                    self.pos += 1;
+                }
+            }
+        }
+        self.push_text(&mut text);
+        while self.stack.len() > 1 {
+            self.close_top();
+        }
+        let root = self.stack.pop().expect("missing root");
+        Document {
+            nodes: root.children,
+        }
+    }
+
+    /// Parses the tag at the current position, returning it with its length.
+    fn tag(&self) -> Option<(Tag<'a>, usize)> {
+        let rest = &self.input[self.pos..];
+        let bytes = rest.as_bytes();
+        let mut i = 1;
+        let closing = bytes.get(i) == Some(&b'/');
+        if closing {
+            i += 1;
+        }
+        let name_start = i;
+        // tag names start with a letter, so that e.g. `<3` is text
+        if !bytes.get(i).is_some_and(|b| b.is_ascii_alphabetic()) {
+            return None;
+        }
+        while bytes.get(i).is_some_and(|b| b.is_ascii_alphanumeric()) {
+            i += 1;
+        }
+        if !bytes
+            .get(i)
+            .is_some_and(|&b| b == b'>' || b == b'/' || b.is_ascii_whitespace())
+        {
+            return None;
+        }
+        let name = rest[name_start..i].to_ascii_lowercase();
+
+        // find the end of the tag, skipping quoted attribute values
+        let attributes_start = i;
+        let mut quote = None;
+        while let Some(&b) = bytes.get(i) {
+            match (quote, b) {
+                (None, b'"') | (None, b'\'') => quote = Some(b),
+                (Some(q), b) if q == b => quote = None,
+                (None, b'>') => {
+                    let attributes = rest[attributes_start..i].trim_end_matches('/');
+                    let tag = Tag {
+                        name,
+                        closing,
+                        attributes,
+                    };
+                    return Some((tag, i + 1));
+                }
+                _ => (),
+            }
+            i += 1;
+        }
+        None
+    }
+
+    fn handle(&mut self, tag: Tag) {
+        if REMOVED_WITH_CONTENT.contains(&tag.name.as_str()) {
+            if !tag.closing {
+                self.skip_content(&tag.name);
+            }
+            return;
+        }
+        let name = match tag.name.as_str() {
+            "br" => {
+                if !tag.closing {
+                    self.push(Node::LineBreak);
+                }
+                return;
+            }
+            "strong" => "strong",
+            "em" => "em",
+            "b" => "b",
+            "i" => "i",
+            "a" => "a",
+            "blockquote" => "blockquote",
+            _ => return,
+        };
+        if tag.closing {
+            if let Some(index) = self.stack.iter().rposition(|open| open.name == name) {
+                while self.stack.len() > index {
+                    self.close_top();
+                }
+            }
+        } else {
+            let href = if name == "a" {
+                Some(attribute(tag.attributes, "href").unwrap_or_default())
+            } else {
+                None
+            };
+            self.stack.push(Open {
+                name,
+                href,
+                children: Vec::new(),
+            });
+        }
+    }
+
+    /// Skips the input until after the closing tag with the given name.
+    fn skip_content(&mut self, name: &str) {
+        let rest = self.input[self.pos..].to_ascii_lowercase();
+        let closing = format!("</{}", name);
+        self.pos = match rest.find(&closing) {
+            Some(index) => {
+                let end = rest[index..]
+                    .find('>')
+                    .map_or(rest.len(), |end| index + end + 1);
+                self.pos + end
+            }
+            None => self.input.len(),
+        };
+    }
+
+    fn push(&mut self, node: Node) {
+        let top = self.stack.last_mut().expect("missing root");
+        match (top.children.last_mut(), node) {
+            (Some(Node::Text(last)), Node::Text(text)) => last.push_str(&text),
+            (_, node) => top.children.push(node),
+        }
+    }
+
+    fn push_text(&mut self, text: &mut String) {
+        if !text.is_empty() {
+            let decoded = decode_entities(text);
+            text.clear();
+            self.push(Node::Text(decoded));
+        }
+    }
+
+    fn close_top(&mut self) {
+        let open = self.stack.pop().expect("missing element");
+        let node = match open.name {
+            "strong" => Node::Strong(open.children),
+            "em" => Node::Emphasis(open.children),
+            "b" => Node::Bold(open.children),
+            "i" => Node::Italic(open.children),
+            "blockquote" => Node::Blockquote(open.children),
+            _ => {
+                let href = open.href.unwrap_or_default();
+                if is_safe_href(&href) {
+                    Node::Link {
+                        href,
+                        children: open.children,
+                    }
+                } else {
+                    // keep the text of unsafe links
+                    for child in open.children {
+                        self.push(child);
+                    }
+                    return;
+                }
+            }
+        };
+        self.push(node);
+    }
+}
+
+/// Returns the decoded value of an attribute.
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    parse_attributes(attributes)
+        .into_iter()
+        .find(|(attribute_name, _)| attribute_name.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+}
+
+/// Returns the names and decoded values of the attributes of a tag.
+fn parse_attributes(attributes: &str) -> Vec<(&str, String)> {
+    let bytes = attributes.as_bytes();
+    let mut parsed = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let name_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'=' {
+            i += 1;
+        }
+        let attribute_name = &attributes[name_start..i];
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = "";
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match bytes.get(i) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => {
+                    let end = attributes[i + 1..]
+                        .find(quote as char)
+                        .map_or(bytes.len(), |end| i + 1 + end);
+                    value = &attributes[i + 1..end];
+                    i = end + 1;
+                }
+                _ => {
+                    let start = i;
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+                        i += 1;
+                    }
+                    value = &attributes[start..i];
+                }
+            }
+        }
+        if !attribute_name.is_empty() {
+            parsed.push((attribute_name, decode_entities(value)));
+        }
+        if i == name_start {
+            i += 1;
+        }
+    }
+    parsed
+}
+
+/// Returns whether a link target is relative or has an allowed scheme.
+fn is_safe_href(href: &str) -> bool {
+    let href: String = href
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if href.is_empty() {
+        return false;
+    }
+    match href.find(':') {
+        Some(colon) if !href[..colon].contains(['/', '?', '#']) => {
+            LINK_SCHEMES.iter().any(|scheme| href.starts_with(scheme))
+        }
+        _ => true,
+    }
+}
+
+/// Decodes the character references of HTML text.
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find(';') {
+            Some(end) if end <= 10 => end,
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(std::char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// rendering
+
+/// Escapes text content.
+pub(crate) fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Escapes an attribute value enclosed in double quotes.
+pub(crate) fn escape_attribute(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("&quot;"),
+            c => escape_html(c.encode_utf8(&mut [0; 4]), out),
+        }
+    }
+}
+
+/// Renders nodes as HTML, or as LBML, which keeps line breaks of text as they are.
+fn render_html(nodes: &[Node], html: bool, out: &mut String) {
+    for node in nodes {
+        let tag = match node {
+            Node::Text(text) => {
+                if html {
+                    let mut lines = text.split('\n');
+                    escape_html(lines.next().unwrap_or(""), out);
+                    for line in lines {
+                        out.push_str("<br>\n");
+                        escape_html(line, out);
+                    }
+                } else {
+                    escape_html(text, out);
+                }
+                continue;
+            }
+            Node::LineBreak => {
+                out.push_str("<br>");
+                continue;
+            }
+            Node::Link { href, children } => {
+                out.push_str("<a href=\"");
+                escape_attribute(href, out);
+                out.push_str(if html { "\" rel=\"nofollow\">" } else { "\">" });
+                render_html(children, html, out);
+                out.push_str("</a>");
+                continue;
+            }
+            Node::Strong(_) => "strong",
+            Node::Emphasis(_) => "em",
+            Node::Bold(_) => "b",
+            Node::Italic(_) => "i",
+            Node::Blockquote(_) => "blockquote",
+        };
+        let _ = write!(out, "<{}>", tag);
+        render_html(node.children(), html, out);
+        let _ = write!(out, "</{}>", tag);
+    }
+}
+
+fn escape_markdown(text: &str, out: &mut String) {
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+/// Prefixes all lines of a block with `prefix`.
+fn quote_block(block: &str, prefix: &str, out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    for line in block.trim_end().split('\n') {
+        out.push_str(prefix.trim_end_matches(|_| line.is_empty()));
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+fn render_markdown(nodes: &[Node], out: &mut String) {
+    for node in nodes {
+        let delimiter = match node {
+            Node::Text(text) => {
+                escape_markdown(text, out);
+                continue;
+            }
+            Node::LineBreak => {
+                out.push_str("\\\n");
+                continue;
+            }
+            Node::Link { href, children } => {
+                out.push('[');
+                render_markdown(children, out);
+                out.push_str("](");
+                for c in href.chars() {
+                    match c {
+                        '(' | ')' | ' ' => {
+                            let _ = write!(out, "%{:02X}", c as u32);
+                        }
+                        c => out.push(c),
+                    }
+                }
+                out.push(')');
+                continue;
+            }
+            Node::Blockquote(children) => {
+                let mut block = String::new();
+                render_markdown(children, &mut block);
+                quote_block(&block, "> ", out);
+                continue;
+            }
+            Node::Strong(_) | Node::Bold(_) => "**",
+            Node::Emphasis(_) | Node::Italic(_) => "*",
+        };
+        out.push_str(delimiter);
+        render_markdown(node.children(), out);
+        out.push_str(delimiter);
+    }
+}
+
+fn render_text(nodes: &[Node], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::LineBreak => out.push('\n'),
+            Node::Link { href, children } => {
+                let start = out.len();
+                render_text(children, out);
+                if out[start..].trim() != href {
+                    let _ = write!(out, " ({})", href);
+                }
+            }
+            Node::Blockquote(children) => {
+                let mut block = String::new();
+                render_text(children, &mut block);
+                quote_block(&block, "> ", out);
+            }
+            node => render_text(node.children(), out),
+        }
+    }
+}
+
+// validation
+
+/// Validates LBML before it is submitted, e.g. as `LogEntryCreationRequestReview::text`.
+///
+/// Fails with [`Kind::InvalidLbml`](../enum.Kind.html#variant.InvalidLbml) at the position of
+/// the first violation if the LBML is longer than [`MAX_LENGTH`](constant.MAX_LENGTH.html),
+/// contains tags outside of the allow-list or attributes other than `href` of `<a>`, links to
+/// a target with another scheme than `http`, `https` and `mailto`, or has unbalanced tags.
+pub fn validate(lbml: &str) -> Result<()> {
+    if let Some((offset, _)) = lbml.char_indices().nth(MAX_LENGTH) {
+        let message = format!(
+            "text has {} characters, the maximum is {}",
+            lbml.chars().count(),
+            MAX_LENGTH
+        );
+        return Err(invalid(lbml, offset, message));
+    }
+
+    let mut parser = Parser::new(lbml);
+    // names and offsets of the open elements
+    let mut open: Vec<(String, usize)> = Vec::new();
+    while let Some(index) = lbml[parser.pos..].find('<') {
+        let offset = parser.pos + index;
+        parser.pos = offset;
+        let (tag, len) = match parser.tag() {
+            Some(tag) => tag,
+            None => {
+                parser.pos += 1;
+                continue;
+            }
+        };
+        parser.pos += len;
+
+        let name = tag.name.as_str();
+        if !ALLOWED_TAGS.contains(&name) {
+            return Err(invalid(
+                lbml,
+                offset,
+                format!("tag <{}> is not allowed", name),
+            ));
+        }
+        if tag.closing {
+            if name == "br" {
+                return Err(invalid(
+                    lbml,
+                    offset,
+                    "tag </br> is not allowed".to_string(),
+                ));
+            }
+            match open.pop() {
+                Some((open_name, _)) if open_name == name => (),
+                Some((open_name, open_offset)) => {
+                    let (line, column) = line_column(lbml, open_offset);
+                    let message = format!(
+                        "closing tag </{}> does not match <{}> at line {}, column {}",
+                        name, open_name, line, column
+                    );
+                    return Err(invalid(lbml, offset, message));
+                }
+                None => {
+                    let message = format!("closing tag </{}> has no opening tag", name);
+                    return Err(invalid(lbml, offset, message));
+                }
+            }
+            continue;
+        }
+
+        let mut href = None;
+        for (attribute_name, value) in parse_attributes(tag.attributes) {
+            if name == "a" && attribute_name.eq_ignore_ascii_case("href") {
+                href = Some(value);
+            } else {
+                let message = format!("attribute {} of <{}> is not allowed", attribute_name, name);
+                return Err(invalid(lbml, offset, message));
+            }
+        }
+        if name == "a" {
+            match href {
+                Some(ref href) if is_safe_href(href) => (),
+                Some(href) => {
+                    let message = format!("link target {:?} is not allowed", href);
+                    return Err(invalid(lbml, offset, message));
+                }
+                None => {
+                    let message = "tag <a> requires an href attribute".to_string();
+                    return Err(invalid(lbml, offset, message));
+                }
+            }
+        }
+        if name != "br" {
+            open.push((tag.name, offset));
+        }
+    }
+    match open.pop() {
+        Some((name, offset)) => Err(invalid(
+            lbml,
+            offset,
+            format!("tag <{}> is not closed", name),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Returns the 1-based line and column in characters of a byte offset.
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+fn invalid(text: &str, offset: usize, message: String) -> Error {
+    let (line, column) = line_column(text, offset);
+    Error::invalid_lbml(ValidationError {
+        line,
+        column,
+        message,
+    })
+}
+
+// markdown
+
+/// Converts Markdown to LBML, e.g. for `CommentCreationRequest::comment`.
+///
+/// Paragraphs, line breaks, `**strong**`, `*emphasis*`, `[links](https://boxd.it/2a9q)`,
+/// `<https://boxd.it/2a9q>` autolinks and `>` quotations are converted to their LBML
+/// counterparts. Headings become strong text, and code is kept as text, as is HTML, which is
+/// escaped. Links with a disallowed target fail at their position in the Markdown, and the
+/// result is checked with [`validate`](fn.validate.html), whose errors, like an exceeded length,
+/// report their position in the resulting LBML instead.
+pub fn from_markdown(markdown: &str) -> Result<String> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for text in markdown.split('\n') {
+        lines.push(Line {
+            offset,
+            text: text.strip_suffix('\r').unwrap_or(text),
+        });
+        offset += text.len() + 1;
+    }
+    let nodes = Markdown { source: markdown }.blocks(&lines)?;
+    let lbml = Document { nodes }.to_lbml();
+    validate(&lbml)?;
+    Ok(lbml)
+}
+
+/// A line of Markdown with the byte offset of its text in the source.
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    offset: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Returns the line reduced to a slice of its text.
+    fn slice(self, text: &'a str) -> Line<'a> {
+        Line {
+            offset: self.offset + (text.as_ptr() as usize - self.text.as_ptr() as usize),
+            text,
+        }
+    }
+}
+
+struct Markdown<'a> {
+    source: &'a str,
+}
+
+impl Markdown<'_> {
+    /// Converts lines to blocks separated by blank lines.
+    fn blocks(&self, lines: &[Line]) -> Result<Vec<Node>> {
+        let mut blocks = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let trimmed = lines[i].text.trim_start();
+            let block = if trimmed.is_empty() {
+                i += 1;
+                continue;
+            } else if let Some(fence) = fence(trimmed) {
+                let mut code = Vec::new();
+                i += 1;
+                while i < lines.len() && !lines[i].text.trim_start().starts_with(fence) {
+                    code.push(lines[i].text);
+                    i += 1;
+                }
+                i += 1;
+                vec![Node::Text(code.join("\n"))]
+            } else if trimmed.starts_with('>') {
+                let mut quoted = Vec::new();
+                while let Some(line) = lines.get(i) {
+                    match line.text.trim_start().strip_prefix('>') {
+                        Some(rest) => {
+                            quoted.push(line.slice(rest.strip_prefix(' ').unwrap_or(rest)));
+                            i += 1;
+                        }
+                        None => break,
+                    }
+                }
+                let nodes = self.blocks(&quoted)?;
+                if is_blank(&nodes) {
+                    continue;
+                }
+                vec![Node::Blockquote(nodes)]
+            } else if let Some(content) = heading(trimmed) {
+                let line = lines[i].slice(content);
+                i += 1;
+                let nodes = self.paragraph(&[line])?;
+                if is_blank(&nodes) {
+                    continue;
+                }
+                vec![Node::Strong(nodes)]
+            } else {
+                let start = i;
+                i += 1;
+                while i < lines.len() && !interrupts_paragraph(lines[i].text.trim_start()) {
+                    i += 1;
+                }
+                self.paragraph(&lines[start..i])?
+            };
+            if !is_blank(&block) {
+                blocks.push(block);
+            }
+        }
+
+        let mut nodes = Vec::new();
+        for (index, block) in blocks.into_iter().enumerate() {
+            if index > 0 {
+                nodes.push(Node::Text("\n\n".to_string()));
+            }
+            nodes.extend(block);
+        }
+        Ok(nodes)
+    }
+
+    /// Converts the inline content of the lines of a paragraph.
+    fn paragraph(&self, lines: &[Line]) -> Result<Vec<Node>> {
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            if index > 0 {
+                text.push('\n');
+            }
+            segments.push((text.len(), line.offset));
+            text.push_str(line.text.trim_end());
+        }
+        let inline = Inline {
+            source: self.source,
+            text: &text,
+            segments: &segments,
+        };
+        inline.parse(0, text.len())
+    }
+}
+
+/// Returns the fence of a fenced code block.
+fn fence(line: &str) -> Option<&'static str> {
+    ["```", "~~~"]
+        .iter()
+        .find(|fence| line.starts_with(*fence))
+        .copied()
+}
+
+/// Returns the content of an ATX heading like `## Title`.
+fn heading(line: &str) -> Option<&str> {
+    let level = line.bytes().take_while(|&b| b == b'#').count();
+    let rest = &line[level..];
+    if level == 0 || level > 6 || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let content = rest.trim();
+    let without_closing = content.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        Some(without_closing.trim_end())
+    } else {
+        Some(content)
+    }
+}
+
+/// Returns true if the nodes of a block render to nothing.
+fn is_blank(nodes: &[Node]) -> bool {
+    nodes.is_empty() || nodes == [Node::Text(String::new())]
+}
+
+fn interrupts_paragraph(line: &str) -> bool {
+    line.is_empty() || line.starts_with('>') || fence(line).is_some() || heading(line).is_some()
+}
+
+/// The inline content of a paragraph.
+struct Inline<'a> {
+    source: &'a str,
+    text: &'a str,
+    /// Starts of the lines in the text with their offsets in the source.
+    segments: &'a [(usize, usize)],
+}
+
+impl Inline<'_> {
+    fn parse(&self, start: usize, end: usize) -> Result<Vec<Node>> {
+        let text = self.text;
+        let bytes = text.as_bytes();
+        let mut nodes = Vec::new();
+        let mut plain = String::new();
+        let mut i = start;
+        while i < end {
+            match bytes[i] {
+                b'\\' if i + 1 < end && bytes[i + 1].is_ascii_punctuation() => {
+                    plain.push(bytes[i + 1] as char);
+                    i += 2;
+                }
+                b'`' => {
+                    let n = run(bytes, i, end);
+                    match find_run(bytes, i + n, end, b'`', n) {
+                        Some(close) => {
+                            let code = &text[i + n..close];
+                            let code =
+                                match code.strip_prefix(' ').and_then(|c| c.strip_suffix(' ')) {
+                                    Some(stripped) if !stripped.trim().is_empty() => stripped,
+                                    _ => code,
+                                };
+                            plain.push_str(code);
+                            i = close + n;
+                        }
+                        None => {
+                            plain.push_str(&text[i..i + n]);
+                            i += n;
+                        }
+                    }
+                }
+                delimiter @ b'*' | delimiter @ b'_' => {
+                    let n = run(bytes, i, end);
+                    let opens = n <= 3
+                        && i + n < end
+                        && !bytes[i + n].is_ascii_whitespace()
+                        && (delimiter == b'*' || i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+                    match self
+                        .closing_delimiter(i + n, end, delimiter, n)
+                        .filter(|_| opens)
+                    {
+                        Some(close) => {
+                            let children = self.parse(i + n, close)?;
+                            flush(&mut plain, &mut nodes);
+                            nodes.push(match n {
+                                1 => Node::Emphasis(children),
+                                2 => Node::Strong(children),
+                                _ => Node::Strong(vec![Node::Emphasis(children)]),
+                            });
+                            i = close + n;
+                        }
+                        None => {
+                            plain.push_str(&text[i..i + n]);
+                            i += n;
+                        }
+                    }
+                }
+                b'[' => match self.link(i, end) {
+                    Some((label_end, href, href_start, link_end)) => {
+                        if !is_safe_href(&href) {
+                            return Err(self.invalid_href(href_start, &href));
+                        }
+                        let children = self.parse(i + 1, label_end)?;
+                        flush(&mut plain, &mut nodes);
+                        nodes.push(Node::Link { href, children });
+                        i = link_end;
+                    }
+                    None => {
+                        plain.push('[');
+                        i += 1;
+                    }
+                },
+                b'<' => match autolink(&text[i + 1..end]) {
+                    Some((href, len)) => {
+                        if !is_safe_href(&href) {
+                            return Err(self.invalid_href(i + 1, &href));
+                        }
+                        flush(&mut plain, &mut nodes);
+                        let label = text[i + 1..i + 1 + len].to_string();
+                        nodes.push(Node::Link {
+                            href,
+                            children: vec![Node::Text(label)],
+                        });
+                        i += len + 2;
+                    }
+                    None => {
+                        plain.push('<');
+                        i += 1;
+                    }
+                },
+                _ => {
+                    let c = text[i..].chars().next().expect("empty text");
+                    plain.push(c);
+                    i += c.len_utf8();
+                }
+            }
+        }
+        flush(&mut plain, &mut nodes);
+        Ok(nodes)
+    }
+
+    /// Returns the start of the delimiter run closing emphasis with `n` delimiters.
+    fn closing_delimiter(
+        &self,
+        start: usize,
+        end: usize,
+        delimiter: u8,
+        n: usize,
+    ) -> Option<usize> {
+        let bytes = self.text.as_bytes();
+        let mut j = start;
+        while j < end {
+            if bytes[j] == b'\\' {
+                j += 2;
+            } else if bytes[j] == delimiter {
+                let m = run(bytes, j, end);
+                let closes = m == n
+                    && j > start
+                    && !bytes[j - 1].is_ascii_whitespace()
+                    && (delimiter == b'*' || j + m >= end || !bytes[j + m].is_ascii_alphanumeric());
+                if closes {
+                    return Some(j);
+                }
+                j += m;
+            } else {
+                j += 1;
+            }
+        }
+        None
+    }
+
+    /// Parses a link like `[label](href "title")` at `start`.
+    ///
+    /// Returns the end of the label, the target and its start, and the end of the link.
+    fn link(&self, start: usize, end: usize) -> Option<(usize, String, usize, usize)> {
+        let bytes = self.text.as_bytes();
+        let label_end = matching(bytes, start, end, b'[', b']')?;
+        if bytes.get(label_end + 1) != Some(&b'(') {
+            return None;
+        }
+        let link_end = matching(bytes, label_end + 1, end, b'(', b')')?;
+        let destination = &self.text[label_end + 2..link_end];
+        let trimmed = destination.trim_start();
+        let href_start = label_end + 2 + destination.len() - trimmed.len();
+        let href = match trimmed.strip_prefix('<') {
+            Some(rest) => &rest[..rest.find('>')?],
+            None => trimmed.split_whitespace().next().unwrap_or(""),
+        };
+        let mut unescaped = String::with_capacity(href.len());
+        let mut chars = href.chars().peekable();
+        while let Some(c) = chars.next() {
+            match chars.peek() {
+                Some(next) if c == '\\' && next.is_ascii_punctuation() => {}
+                _ => unescaped.push(c),
+            }
+        }
+        Some((label_end, unescaped, href_start, link_end + 1))
+    }
+
+    fn invalid_href(&self, index: usize, href: &str) -> Error {
+        let segment =
+            self.segments[self.segments.partition_point(|&(start, _)| start <= index) - 1];
+        let offset = segment.1 + index - segment.0;
+        invalid(
+            self.source,
+            offset,
+            format!("link target {:?} is not allowed", href),
+        )
+    }
+}
+
+fn flush(plain: &mut String, nodes: &mut Vec<Node>) {
+    if !plain.is_empty() {
+        nodes.push(Node::Text(std::mem::take(plain)));
+    }
+}
+
+/// Returns the length of the run of the same byte at `start`.
+fn run(bytes: &[u8], start: usize, end: usize) -> usize {
+    bytes[start..end]
+        .iter()
+        .take_while(|&&b| b == bytes[start])
+        .count()
+}
+
+/// Returns the start of the next run of exactly `n` of a byte.
+fn find_run(bytes: &[u8], start: usize, end: usize, byte: u8, n: usize) -> Option<usize> {
+    let mut j = start;
+    while j < end {
+        if bytes[j] == byte {
+            let m = run(bytes, j, end);
+            if m == n {
+                return Some(j);
+            }
+            j += m;
+        } else {
+            j += 1;
+        }
+    }
+    None
+}
+
+/// Returns the index of the bracket closing the one at `start`.
+fn matching(bytes: &[u8], start: usize, end: usize, open: u8, close: u8) -> Option<usize> {
+    let mut depth = 0;
+    let mut j = start;
+    while j < end {
+        match bytes[j] {
+            b'\\' => j += 1,
+            b if b == open => depth += 1,
+            b if b == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(j);
+                }
+            }
+            _ => (),
+        }
+        j += 1;
+    }
+    None
+}
+
+/// Parses the content of an autolink like `<https://boxd.it>` after the `<`, returning the
+/// target and the length of the content.
+fn autolink(text: &str) -> Option<(String, usize)> {
+    let len = text.find('>')?;
+    let content = &text[..len];
+    if content.is_empty() || content.contains(|c: char| c.is_whitespace() || c == '<') {
+        return None;
+    }
+    match content.find(':') {
+        Some(colon) => {
+            let scheme = &content[..colon];
+            let valid_scheme = (2..=32).contains(&scheme.len())
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-'));
+            if valid_scheme {
+                Some((content.to_string(), len))
+            } else {
+                None
+            }
+        }
+        None if content.contains('@') && !content.starts_with('@') => {
+            Some((format!("mailto:{}", content), len))
+        }
+        None => None,
+    }
+}
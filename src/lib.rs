@@ -73,10 +73,35 @@
 //! println!("{:?}", resp);
 //! ```
 
+pub mod assets;
+pub mod availability;
+pub mod builder;
+pub mod cache;
 mod client;
+pub mod compare;
+pub mod country;
+pub mod crossref;
+mod date;
 mod defs;
+pub mod endpoint;
 mod error;
+pub mod export;
+pub mod feed;
+mod format;
+pub mod ics;
+pub mod image;
+pub mod import;
+pub mod lbml;
+pub mod list_sync;
+pub mod middleware;
+#[cfg(feature = "mirror")]
+pub mod mirror;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod signing;
+pub mod stats;
+pub mod token_store;
 
 pub use client::{ApiKeyPair, Client};
 pub use defs::*;
-pub use error::{Error, Result};
+pub use error::{Error, Kind, Result};
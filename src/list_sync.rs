@@ -0,0 +1,245 @@
+//! Declarative synchronization of a list with a desired state.
+//!
+//! A [`ListSync`](struct.ListSync.html) holds the desired, ordered entries of a list. It fetches
+//! the current entries of the list and computes a minimal `ListUpdateRequest`: films which are
+//! not desired are removed, new films are inserted, notes and spoiler flags are updated only if
+//! they differ, and only the entries which are out of order are moved.
+//!
+//! The rank of a moved or inserted entry is its final 1-based position in the desired order, so
+//! the ranks of a request are unique. After the removals, the ranked entries take their ranks and
+//! the other entries keep their relative order around them, which yields the desired order.
+
+use crate::client::Client;
+use crate::defs::{
+    List, ListEntriesRequest, ListEntry, ListUpdateEntry, ListUpdateMessage, ListUpdateRequest,
+};
+use crate::error::Result;
+
+use std::collections::{HashMap, HashSet};
+
+/// Page size used when fetching the entries of the list.
+const PER_PAGE: usize = 100;
+
+/// A desired entry of a list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesiredEntry {
+    /// The LID of the film.
+    pub film: String,
+    /// The notes for the entry in LBML.
+    pub notes: Option<String>,
+    /// Set to true if the notes contain plot spoilers for the film.
+    pub contains_spoilers: bool,
+}
+
+impl DesiredEntry {
+    /// Creates a new desired entry without notes.
+    pub fn new(film: String) -> Self {
+        Self {
+            film,
+            notes: None,
+            contains_spoilers: false,
+        }
+    }
+}
+
+/// Result of applying a list synchronization.
+#[derive(Debug, Clone)]
+pub struct ListSyncReport {
+    /// The update request which was sent, or `None` if the list was already in sync.
+    pub request: Option<ListUpdateRequest>,
+    /// The list after the update.
+    pub list: List,
+    /// Error messages returned by Letterboxd for the update.
+    pub warnings: Vec<ListUpdateMessage>,
+}
+
+/// Synchronizes a list with desired entries.
+#[derive(Debug, Clone)]
+pub struct ListSync {
+    list_id: String,
+    entries: Vec<DesiredEntry>,
+}
+
+impl ListSync {
+    /// Creates a new synchronization of the list with the given LID.
+    ///
+    /// The order of the desired entries is the order of the list. If a film occurs more than
+    /// once, only its first occurrence is used.
+    pub fn new(list_id: String, entries: Vec<DesiredEntry>) -> Self {
+        let mut films = HashSet::new();
+        let entries = entries
+            .into_iter()
+            .filter(|entry| films.insert(entry.film.clone()))
+            .collect();
+        Self { list_id, entries }
+    }
+
+    /// Returns the LID of the synchronized list.
+    pub fn list_id(&self) -> &str {
+        &self.list_id
+    }
+
+    /// Returns the desired entries.
+    pub fn entries(&self) -> &[DesiredEntry] {
+        &self.entries
+    }
+
+    /// Fetches the list and its entries and computes the update request.
+    ///
+    /// Returns the current list together with the request, or `None` if the list is already in
+    /// sync.
+    pub async fn plan(&self, client: &Client) -> Result<(List, Option<ListUpdateRequest>)> {
+        let list = client.list(&self.list_id).await?;
+
+        let mut current = Vec::new();
+        let mut req = ListEntriesRequest {
+            per_page: Some(PER_PAGE),
+            ..Default::default()
+        };
+        loop {
+            let resp = client.list_entries(&self.list_id, &req).await?;
+            current.extend(resp.items);
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let request = self.diff(&list.name, &current);
+        Ok((list, request))
+    }
+
+    /// Computes the update request from the current entries of the list.
+    ///
+    /// Returns `None` if the list is already in sync.
+    pub fn diff(&self, name: &str, current: &[ListEntry]) -> Option<ListUpdateRequest> {
+        let desired_index: HashMap<&str, usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (entry.film.as_str(), index))
+            .collect();
+
+        let films_to_remove: Vec<String> = current
+            .iter()
+            .filter(|entry| !desired_index.contains_key(entry.film.id.as_str()))
+            .map(|entry| entry.film.id.clone())
+            .collect();
+
+        // positions in the desired order of the entries which are kept, in current order
+        let kept: Vec<usize> = current
+            .iter()
+            .filter_map(|entry| desired_index.get(entry.film.id.as_str()).copied())
+            .collect();
+        let in_order: HashSet<usize> = longest_increasing_subsequence(&kept)
+            .into_iter()
+            .map(|i| kept[i])
+            .collect();
+
+        // entries which are not in order are placed at their final position
+        let ranks: HashMap<usize, usize> = (0..self.entries.len())
+            .filter(|index| !in_order.contains(index))
+            .map(|index| (index, index + 1))
+            .collect();
+
+        let current_by_film: HashMap<&str, &ListEntry> = current
+            .iter()
+            .map(|entry| (entry.film.id.as_str(), entry))
+            .collect();
+        let mut moved = Vec::new();
+        let mut updated = Vec::new();
+        for (index, desired) in self.entries.iter().enumerate() {
+            let mut entry = ListUpdateEntry::new(desired.film.clone());
+            entry.rank = ranks.get(&index).copied();
+            match current_by_film.get(desired.film.as_str()) {
+                Some(current) => {
+                    let current_notes = current.notes_lbml.as_deref().unwrap_or("");
+                    let desired_notes = desired.notes.as_deref().unwrap_or("");
+                    let current_spoilers = current.contains_spoilers.unwrap_or(false);
+                    if current_notes != desired_notes
+                        || current_spoilers != desired.contains_spoilers
+                    {
+                        entry.notes = Some(desired_notes.to_string());
+                        entry.contains_spoilers = Some(desired.contains_spoilers);
+                    }
+                }
+                None => {
+                    entry.notes = desired.notes.clone();
+                    entry.contains_spoilers = Some(desired.contains_spoilers);
+                }
+            }
+            if entry.rank.is_some() {
+                moved.push(entry);
+            } else if entry.notes.is_some() || entry.contains_spoilers.is_some() {
+                updated.push(entry);
+            }
+        }
+
+        if films_to_remove.is_empty() && moved.is_empty() && updated.is_empty() {
+            return None;
+        }
+        moved.extend(updated);
+        Some(ListUpdateRequest {
+            films_to_remove,
+            entries: moved,
+            ..ListUpdateRequest::new(name.to_string())
+        })
+    }
+
+    /// Synchronizes the list.
+    ///
+    /// The client must be authenticated as the owner of the list.
+    pub async fn apply(&self, client: &Client) -> Result<ListSyncReport> {
+        let (list, request) = self.plan(client).await?;
+        let request = match request {
+            Some(request) => request,
+            None => {
+                return Ok(ListSyncReport {
+                    request: None,
+                    list,
+                    warnings: Vec::new(),
+                })
+            }
+        };
+        let resp = client.update_list(&self.list_id, &request).await?;
+        let warnings = resp
+            .messages
+            .into_iter()
+            .filter(|message| matches!(message, ListUpdateMessage::Error { .. }))
+            .collect();
+        Ok(ListSyncReport {
+            request: Some(request),
+            list: resp.data,
+            warnings,
+        })
+    }
+}
+
+/// Returns the indices of a longest strictly increasing subsequence.
+fn longest_increasing_subsequence(values: &[usize]) -> Vec<usize> {
+    // tails[k] is the index of the smallest tail of an increasing subsequence of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut predecessors = vec![None; values.len()];
+    for (index, &value) in values.iter().enumerate() {
+        let k = tails
+            .binary_search_by(|&tail| values[tail].cmp(&value))
+            .unwrap_or_else(|k| k);
+        if k > 0 {
+            predecessors[index] = Some(tails[k - 1]);
+        }
+        if k == tails.len() {
+            tails.push(index);
+        } else {
+            tails[k] = index;
+        }
+    }
+
+    let mut subsequence = Vec::with_capacity(tails.len());
+    let mut next = tails.last().copied();
+    while let Some(index) = next {
+        subsequence.push(index);
+        next = predecessors[index];
+    }
+    subsequence.reverse();
+    subsequence
+}
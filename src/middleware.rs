@@ -0,0 +1,202 @@
+//! Hooks into the requests of a client.
+//!
+//! Middlewares are added to a client with
+//! [`Client::add_middleware`](../struct.Client.html#method.add_middleware) and called in the
+//! order in which they were added:
+//!
+//! 1. `before_sign` with the request before the signing parameters are added to its url,
+//! 2. `handle` around the signing and sending of the request, which continues with the
+//!    following middlewares by running [`Next`](struct.Next.html),
+//! 3. `after_sign` with the signed request, right before it is sent,
+//! 4. `on_response` with the response, in reverse order.
+//!
+//! A middleware can short-circuit a request by returning a response from `before_sign` or
+//! `after_sign`. The request is then not sent, and the remaining hooks of this and the following
+//! middlewares are not called. Only the `on_response` hooks of the preceding middlewares are
+//! called with the returned response.
+//!
+//! Since `handle` is asynchronous, it can delay a request, e.g. for rate limiting, or run the
+//! rest of the chain several times, e.g. to retry failed requests. Each run signs the request
+//! anew and calls the `after_sign` and `on_response` hooks again.
+//!
+//! The [response cache](../cache/struct.ResponseCache.html) of a client is a middleware which
+//! always comes first.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use letterboxd::middleware::{header, BoxFuture, Middleware, Next, Request, Response};
+//!
+//! /// Adds a header to all requests.
+//! #[derive(Debug)]
+//! struct Tag;
+//!
+//! impl Middleware for Tag {
+//!     fn after_sign(&self, request: &mut Request) -> Option<letterboxd::middleware::Response> {
+//!         let value = header::HeaderValue::from_static("reporting");
+//!         request.headers.insert("x-client", value);
+//!         None
+//!     }
+//! }
+//!
+//! /// Retries requests which failed with a server error once.
+//! #[derive(Debug)]
+//! struct Retry;
+//!
+//! impl Middleware for Retry {
+//!     fn handle<'a>(
+//!         &'a self,
+//!         request: Request,
+//!         next: Next<'a>,
+//!     ) -> BoxFuture<'a, letterboxd::Result<Response>> {
+//!         Box::pin(async move {
+//!             let response = next.run(request.clone()).await?;
+//!             if response.status.is_server_error() {
+//!                 return next.run(request).await;
+//!             }
+//!             Ok(response)
+//!         })
+//!     }
+//! }
+//!
+//! let api_key_pair = letterboxd::ApiKeyPair::new("key".into(), "secret".into());
+//! let mut client = letterboxd::Client::new(api_key_pair);
+//! client.add_middleware(Tag);
+//! client.add_middleware(Retry);
+//! ```
+
+pub use futures::future::BoxFuture;
+pub use hyper::{header, HeaderMap, Method, StatusCode};
+pub use url::Url;
+
+use crate::error::Result;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// A request of a client.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// The HTTP method.
+    pub method: Method,
+    /// The path of the endpoint relative to the API base url, e.g. `film/2a9q`.
+    pub endpoint_path: String,
+    /// The url of the request. After signing, it includes the signing parameters.
+    ///
+    /// Changes of the url after signing invalidate the signature, unless the request is
+    /// forwarded to the API with the original url.
+    pub url: Url,
+    /// The headers of the request.
+    pub headers: HeaderMap,
+    /// The body of the request.
+    pub body: Vec<u8>,
+}
+
+/// A response to a request.
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The body of the response.
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Creates a new response with status `200 OK` and no headers.
+    pub fn ok(body: Vec<u8>) -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body,
+        }
+    }
+}
+
+/// Hooks called for each request of a client.
+///
+/// All hooks do nothing by default.
+pub trait Middleware: Send + Sync {
+    /// Called before the request is signed.
+    ///
+    /// Returning a response short-circuits the request.
+    fn before_sign(&self, _request: &mut Request) -> Option<Response> {
+        None
+    }
+
+    /// Called after the request is signed, right before it is sent.
+    ///
+    /// Returning a response short-circuits the request.
+    fn after_sign(&self, _request: &mut Request) -> Option<Response> {
+        None
+    }
+
+    /// Called around the signing and sending of the request.
+    ///
+    /// The request is continued by running `next`, which may be done several times or not at
+    /// all. By default, the request is continued once.
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        next.run(request)
+    }
+
+    /// Called with the response to a request.
+    fn on_response(&self, _request: &Request, _response: &mut Response) {}
+}
+
+/// Signs and sends a request after the `handle` hooks of all middlewares.
+pub(crate) type SendFn<'a> = dyn Fn(Request) -> BoxFuture<'a, Result<Response>> + Sync + 'a;
+
+/// The rest of the middleware chain of a request, passed to `Middleware::handle`.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    middlewares: &'a [&'a dyn Middleware],
+    send: &'a SendFn<'a>,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(middlewares: &'a [&'a dyn Middleware], send: &'a SendFn<'a>) -> Self {
+        Self { middlewares, send }
+    }
+
+    /// Runs the `handle` hooks of the following middlewares, then signs and sends the request.
+    pub fn run(&self, request: Request) -> BoxFuture<'a, Result<Response>> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => middleware.handle(
+                request,
+                Next {
+                    middlewares,
+                    send: self.send,
+                },
+            ),
+            None => (self.send)(request),
+        }
+    }
+}
+
+impl fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Next")
+            .field("middlewares", &self.middlewares.len())
+            .finish()
+    }
+}
+
+/// A shared middleware, e.g. for inspecting its state after requests.
+impl<M: Middleware + ?Sized> Middleware for Arc<M> {
+    fn before_sign(&self, request: &mut Request) -> Option<Response> {
+        self.as_ref().before_sign(request)
+    }
+
+    fn after_sign(&self, request: &mut Request) -> Option<Response> {
+        self.as_ref().after_sign(request)
+    }
+
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        self.as_ref().handle(request, next)
+    }
+
+    fn on_response(&self, request: &Request, response: &mut Response) {
+        self.as_ref().on_response(request, response)
+    }
+}
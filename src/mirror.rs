@@ -0,0 +1,555 @@
+//! Local SQLite mirror of member data with incremental synchronization.
+//!
+//! A [`Mirror`](struct.Mirror.html) persists the log entries, lists, list entries, watched films
+//! and watchlist of a member into SQLite, so that they can be queried with SQL joins without
+//! fetching them from the API again. The tables follow the types in the API definitions:
+//!
+//! * `members`: `MemberSummary` of owners and members with film relationships,
+//! * `films`: `FilmSummary` of all films referenced by the other tables,
+//! * `film_relationships`: `FilmRelationship` of a member to a film,
+//! * `log_entries` and `log_entry_tags`: `LogEntry` and its tags,
+//! * `lists` and `list_entries`: `ListSummary` and the `ListEntry` values of a list,
+//! * `sync_state`: the per-endpoint high-water mark of a member.
+//!
+//! Each row additionally contains the complete entity as JSON in the `data` column.
+//!
+//! A synchronization is incremental:
+//!
+//! * Log entries are only stored if they were updated since the last synchronization
+//!   (high-water mark of `whenUpdated`). The API cannot sort them by update, so all log entries
+//!   are still fetched, and stored log entries which were not fetched are deleted.
+//! * The entries of a list are only fetched if the list is new or its summary changed.
+//! * Watched films and the watchlist are always fetched completely, since the API does not
+//!   provide any change marker for them.
+//!
+//! The savings are therefore in the writes to the mirror rather than in the requests: every
+//! synchronization pages through all log entries, lists, watched films and the watchlist.
+
+use crate::client::Client;
+use crate::defs::{
+    FilmRelationshipType, FilmSummary, FilmsRequest, ListEntriesRequest, ListEntry,
+    ListMemberRelationship, ListSummary, ListsRequest, LogEntriesRequest, LogEntry,
+    LogEntryRelationshipType, MemberSummary, WatchlistRequest,
+};
+use crate::error::Result;
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// The SQLite bindings used by the mirror, e.g. for querying its connection.
+pub use rusqlite;
+
+/// Page size used when fetching data for the mirror.
+const PER_PAGE: usize = 100;
+
+/// Name of the endpoint whose high-water mark is the `whenUpdated` of the log entries.
+pub const LOG_ENTRIES: &str = "log-entries";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS members (
+    id TEXT PRIMARY KEY,
+    username TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS films (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    original_name TEXT,
+    release_year INTEGER,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS film_relationships (
+    member_id TEXT NOT NULL,
+    film_id TEXT NOT NULL,
+    watched INTEGER NOT NULL DEFAULT 0,
+    liked INTEGER NOT NULL DEFAULT 0,
+    favorited INTEGER NOT NULL DEFAULT 0,
+    in_watchlist INTEGER NOT NULL DEFAULT 0,
+    rating REAL,
+    PRIMARY KEY (member_id, film_id)
+);
+CREATE TABLE IF NOT EXISTS log_entries (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    owner_id TEXT NOT NULL,
+    film_id TEXT NOT NULL,
+    diary_date TEXT,
+    rewatch INTEGER,
+    review_lbml TEXT,
+    review_contains_spoilers INTEGER,
+    rating REAL,
+    liked INTEGER NOT NULL,
+    when_created TEXT NOT NULL,
+    when_updated TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS log_entries_owner_id ON log_entries (owner_id);
+CREATE INDEX IF NOT EXISTS log_entries_film_id ON log_entries (film_id);
+CREATE TABLE IF NOT EXISTS log_entry_tags (
+    log_entry_id TEXT NOT NULL,
+    code TEXT NOT NULL,
+    display_tag TEXT NOT NULL,
+    PRIMARY KEY (log_entry_id, code)
+);
+CREATE TABLE IF NOT EXISTS lists (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    owner_id TEXT NOT NULL,
+    film_count INTEGER NOT NULL,
+    published INTEGER NOT NULL,
+    ranked INTEGER NOT NULL,
+    description_lbml TEXT,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS list_entries (
+    list_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    rank INTEGER,
+    film_id TEXT NOT NULL,
+    notes_lbml TEXT,
+    contains_spoilers INTEGER,
+    PRIMARY KEY (list_id, position)
+);
+CREATE INDEX IF NOT EXISTS list_entries_film_id ON list_entries (film_id);
+CREATE TABLE IF NOT EXISTS sync_state (
+    member_id TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    cursor TEXT NOT NULL,
+    PRIMARY KEY (member_id, endpoint)
+);
+";
+
+/// Changes of a member's data fetched from the API, which are applied to a mirror.
+#[derive(Debug, Clone, Default)]
+pub struct MirrorUpdate {
+    /// The LID of the member.
+    pub member: String,
+    /// Log entries created or updated since the last synchronization.
+    pub log_entries: Vec<LogEntry>,
+    /// The LIDs of all log entries of the member, if known. Stored log entries which are
+    /// missing are deleted.
+    pub log_entry_ids: Option<Vec<String>>,
+    /// All lists owned by the member. Stored lists which are missing are deleted.
+    pub lists: Vec<ListSummary>,
+    /// All entries of the lists which are new or changed, by LID of the list.
+    pub list_entries: HashMap<String, Vec<ListEntry>>,
+    /// All films watched by the member.
+    pub watched: Vec<FilmSummary>,
+    /// All films in the member's watchlist.
+    pub watchlist: Vec<FilmSummary>,
+}
+
+/// Number of changes made by a synchronization.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Number of created or updated log entries.
+    pub log_entries: usize,
+    /// Number of deleted log entries.
+    pub removed_log_entries: usize,
+    /// Number of lists whose entries were replaced.
+    pub lists: usize,
+    /// Number of deleted lists.
+    pub removed_lists: usize,
+    /// Number of films watched by the member.
+    pub watched: usize,
+    /// Number of films in the member's watchlist.
+    pub watchlist: usize,
+}
+
+/// SQLite database mirroring the data of members.
+#[derive(Debug)]
+pub struct Mirror {
+    conn: Connection,
+}
+
+impl Mirror {
+    /// Opens or creates the mirror database at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Creates a mirror in memory.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Creates a mirror from a connection, creating the tables if they do not exist.
+    pub fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Returns the underlying connection, e.g. for running queries.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Returns the high-water mark of an endpoint for a member, if the member was synchronized.
+    pub fn cursor(&self, member: &str, endpoint: &str) -> Result<Option<String>> {
+        let cursor = self
+            .conn
+            .query_row(
+                "SELECT cursor FROM sync_state WHERE member_id = ?1 AND endpoint = ?2",
+                params![member, endpoint],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(cursor)
+    }
+
+    /// Forgets the high-water marks of a member, such that the next synchronization is full.
+    pub fn reset(&mut self, member: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM sync_state WHERE member_id = ?1",
+            params![member],
+        )?;
+        Ok(())
+    }
+
+    /// Fetches the changes of the member's data and applies them to the mirror.
+    ///
+    /// Private data (e.g. unpublished lists) is only included if the client is authenticated
+    /// as the member.
+    pub async fn sync(&mut self, client: &Client, member: &str) -> Result<SyncReport> {
+        let update = self.fetch(client, member).await?;
+        self.apply(&update)
+    }
+
+    /// Fetches the changes of the member's data since the last synchronization.
+    pub async fn fetch(&self, client: &Client, member: &str) -> Result<MirrorUpdate> {
+        let high_water_mark = self.cursor(member, LOG_ENTRIES)?;
+        let mut log_entries = Vec::new();
+        let mut req = LogEntriesRequest {
+            per_page: Some(PER_PAGE),
+            member: Some(member.to_string()),
+            member_relationship: Some(LogEntryRelationshipType::Owner),
+            ..Default::default()
+        };
+        let mut log_entry_ids = Vec::new();
+        loop {
+            let resp = client.log_entries(&req).await?;
+            // log entries are ordered by creation, and edits of older entries do not reorder
+            // them, so all pages are fetched and filtered by the mark
+            log_entry_ids.extend(resp.items.iter().map(|entry| entry.id.clone()));
+            log_entries.extend(resp.items.into_iter().filter(|entry| {
+                high_water_mark
+                    .as_ref()
+                    .is_none_or(|mark| entry.when_updated > *mark)
+            }));
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let mut lists = Vec::new();
+        let mut req = ListsRequest {
+            per_page: Some(PER_PAGE),
+            member: Some(member.to_string()),
+            member_relationship: Some(ListMemberRelationship::Owner),
+            ..Default::default()
+        };
+        loop {
+            let resp = client.lists(&req).await?;
+            lists.extend(resp.items);
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+        let mut list_entries = HashMap::new();
+        for id in self.changed_lists(&lists)? {
+            let entries = fetch_list_entries(client, &id).await?;
+            list_entries.insert(id, entries);
+        }
+
+        let mut watched = Vec::new();
+        let mut req = FilmsRequest {
+            per_page: Some(PER_PAGE),
+            member: Some(member.to_string()),
+            member_relationship: Some(FilmRelationshipType::Watched),
+            ..Default::default()
+        };
+        loop {
+            let resp = client.films(&req).await?;
+            watched.extend(resp.items);
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let mut watchlist = Vec::new();
+        let mut req = WatchlistRequest {
+            per_page: Some(PER_PAGE),
+            ..Default::default()
+        };
+        loop {
+            let resp = client.member_watchlist(member, &req).await?;
+            watchlist.extend(resp.items);
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(MirrorUpdate {
+            member: member.to_string(),
+            log_entries,
+            log_entry_ids: Some(log_entry_ids),
+            lists,
+            list_entries,
+            watched,
+            watchlist,
+        })
+    }
+
+    /// Returns the LIDs of the lists which are not stored or whose stored summary differs.
+    pub fn changed_lists(&self, lists: &[ListSummary]) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT data FROM lists WHERE id = ?1")?;
+        let mut changed = Vec::new();
+        for list in lists {
+            let data = serde_json::to_string(list)?;
+            let stored: Option<String> = stmt
+                .query_row(params![list.id], |row| row.get(0))
+                .optional()?;
+            if stored.as_ref() != Some(&data) {
+                changed.push(list.id.clone());
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Applies fetched changes in a single transaction and advances the high-water marks.
+    pub fn apply(&mut self, update: &MirrorUpdate) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        let tx = self.conn.transaction()?;
+
+        for entry in &update.log_entries {
+            insert_log_entry(&tx, entry)?;
+        }
+        report.log_entries = update.log_entries.len();
+        if let Some(ids) = &update.log_entry_ids {
+            let ids: HashSet<&str> = ids.iter().map(String::as_str).collect();
+            for id in stored_ids(&tx, "log_entries", &update.member)? {
+                if !ids.contains(id.as_str()) {
+                    tx.execute("DELETE FROM log_entries WHERE id = ?1", params![id])?;
+                    tx.execute(
+                        "DELETE FROM log_entry_tags WHERE log_entry_id = ?1",
+                        params![id],
+                    )?;
+                    report.removed_log_entries += 1;
+                }
+            }
+        }
+
+        let ids: HashSet<&str> = update.lists.iter().map(|list| list.id.as_str()).collect();
+        for id in stored_ids(&tx, "lists", &update.member)? {
+            if !ids.contains(id.as_str()) {
+                tx.execute("DELETE FROM lists WHERE id = ?1", params![id])?;
+                tx.execute("DELETE FROM list_entries WHERE list_id = ?1", params![id])?;
+                report.removed_lists += 1;
+            }
+        }
+        for list in &update.lists {
+            insert_list(&tx, list)?;
+        }
+        for (id, entries) in &update.list_entries {
+            tx.execute("DELETE FROM list_entries WHERE list_id = ?1", params![id])?;
+            for (position, entry) in entries.iter().enumerate() {
+                insert_film(&tx, &entry.film)?;
+                tx.execute(
+                    "INSERT INTO list_entries
+                     (list_id, position, rank, film_id, notes_lbml, contains_spoilers)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        id,
+                        position as i64,
+                        entry.rank.map(|rank| rank as i64),
+                        entry.film.id,
+                        entry.notes_lbml,
+                        entry.contains_spoilers,
+                    ],
+                )?;
+            }
+            report.lists += 1;
+        }
+
+        for (films, column) in &[
+            (&update.watched, "watched"),
+            (&update.watchlist, "in_watchlist"),
+        ] {
+            tx.execute(
+                &format!(
+                    "UPDATE film_relationships SET {} = 0 WHERE member_id = ?1",
+                    column
+                ),
+                params![update.member],
+            )?;
+            for film in films.iter() {
+                insert_film(&tx, film)?;
+                tx.execute(
+                    &format!(
+                        "INSERT INTO film_relationships (member_id, film_id, {0}) VALUES (?1, ?2, 1)
+                         ON CONFLICT (member_id, film_id) DO UPDATE SET {0} = 1",
+                        column
+                    ),
+                    params![update.member, film.id],
+                )?;
+            }
+        }
+        report.watched = update.watched.len();
+        report.watchlist = update.watchlist.len();
+
+        let mark = update
+            .log_entries
+            .iter()
+            .map(|entry| &entry.when_updated)
+            .max();
+        if let Some(mark) = mark {
+            tx.execute(
+                "INSERT INTO sync_state (member_id, endpoint, cursor) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (member_id, endpoint) DO UPDATE SET cursor = max(cursor, ?3)",
+                params![update.member, LOG_ENTRIES, mark],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(report)
+    }
+}
+
+async fn fetch_list_entries(client: &Client, id: &str) -> Result<Vec<ListEntry>> {
+    let mut entries = Vec::new();
+    let mut req = ListEntriesRequest {
+        per_page: Some(PER_PAGE),
+        ..Default::default()
+    };
+    loop {
+        let resp = client.list_entries(id, &req).await?;
+        entries.extend(resp.items);
+        match resp.next {
+            Some(next) => req.cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(entries)
+}
+
+/// Returns the LIDs of the rows of a table owned by a member.
+fn stored_ids(tx: &Transaction, table: &str, member: &str) -> Result<Vec<String>> {
+    let mut stmt = tx.prepare(&format!("SELECT id FROM {} WHERE owner_id = ?1", table))?;
+    let ids = stmt
+        .query_map(params![member], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(ids)
+}
+
+fn insert_member(tx: &Transaction, member: &MemberSummary) -> Result<()> {
+    tx.execute(
+        "INSERT OR REPLACE INTO members (id, username, display_name, data)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            member.id,
+            member.username,
+            member.display_name,
+            serde_json::to_string(member)?,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Stores a film together with the film relationships it includes.
+fn insert_film(tx: &Transaction, film: &FilmSummary) -> Result<()> {
+    tx.execute(
+        "INSERT OR REPLACE INTO films (id, name, original_name, release_year, data)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            film.id,
+            film.name,
+            film.original_name,
+            film.release_year,
+            serde_json::to_string(film)?,
+        ],
+    )?;
+    for relationship in &film.relationships {
+        insert_member(tx, &relationship.member)?;
+        let r = &relationship.relationship;
+        tx.execute(
+            "INSERT OR REPLACE INTO film_relationships
+             (member_id, film_id, watched, liked, favorited, in_watchlist, rating)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                relationship.member.id,
+                film.id,
+                r.watched,
+                r.liked,
+                r.favorited,
+                r.in_watchlist,
+                r.rating.map(f64::from),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_log_entry(tx: &Transaction, entry: &LogEntry) -> Result<()> {
+    insert_member(tx, &entry.owner)?;
+    insert_film(tx, &entry.film)?;
+    tx.execute(
+        "INSERT OR REPLACE INTO log_entries
+         (id, name, owner_id, film_id, diary_date, rewatch, review_lbml,
+          review_contains_spoilers, rating, liked, when_created, when_updated, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            entry.id,
+            entry.name,
+            entry.owner.id,
+            entry.film.id,
+            entry.diary_details.as_ref().map(|d| &d.diary_date),
+            entry.diary_details.as_ref().map(|d| d.rewatch),
+            entry.review.as_ref().map(|r| &r.lbml),
+            entry.review.as_ref().map(|r| r.contains_spoilers),
+            entry.rating.map(f64::from),
+            entry.like,
+            entry.when_created,
+            entry.when_updated,
+            serde_json::to_string(entry)?,
+        ],
+    )?;
+    tx.execute(
+        "DELETE FROM log_entry_tags WHERE log_entry_id = ?1",
+        params![entry.id],
+    )?;
+    for tag in &entry.tags2 {
+        tx.execute(
+            "INSERT OR REPLACE INTO log_entry_tags (log_entry_id, code, display_tag)
+             VALUES (?1, ?2, ?3)",
+            params![entry.id, tag.code, tag.display_tag],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_list(tx: &Transaction, list: &ListSummary) -> Result<()> {
+    insert_member(tx, &list.owner)?;
+    tx.execute(
+        "INSERT OR REPLACE INTO lists
+         (id, name, owner_id, film_count, published, ranked, description_lbml, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            list.id,
+            list.name,
+            list.owner.id,
+            list.film_count as i64,
+            list.published,
+            list.ranked,
+            list.description_lbml,
+            serde_json::to_string(list)?,
+        ],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,1367 @@
+//! In-process mock of the Letterboxd API for tests.
+//!
+//! A [`MockServer`](struct.MockServer.html) serves a [`Dataset`](struct.Dataset.html) over HTTP
+//! on an ephemeral local port. It verifies the signature of each request like the API does,
+//! and implements the endpoints wrapped by the [`Client`](../struct.Client.html) with cursor
+//! pagination and mutation semantics:
+//!
+//! * `auth/token` with the `password` and `refresh_token` grants,
+//! * `films`, `films/genres`, `films/film-services`, `film/{id}` (also by `imdb:` and `tmdb:`
+//!   identifiers), `film/{id}/availability`, `film/{id}/me`,
+//! * `lists`, `list/{id}`, `list/{id}/entries`,
+//! * `log-entries`, `member/{id}/watchlist`, `members/pronouns` and `search`.
+//!
+//! Other endpoints respond with `404 Not Found`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use letterboxd::mock::{Dataset, MockServer};
+//! use tokio::runtime::Runtime;
+//!
+//! let api_key_pair = letterboxd::ApiKeyPair::new("key".into(), "secret".into());
+//! let server = MockServer::start(api_key_pair, Dataset::seeded()).unwrap();
+//!
+//! let res = async {
+//!     let mut client = server.client();
+//!     client.login("alice", "password").await?;
+//!     let req = letterboxd::FilmsRequest::default();
+//!     client.films(&req).await
+//! };
+//!
+//! let mut rt = Runtime::new().unwrap();
+//! let films = rt.block_on(res).unwrap();
+//! assert_eq!(films.items.len(), 5);
+//! ```
+
+use crate::client::{ApiKeyPair, Client};
+use crate::date;
+use crate::defs::{Country, FilmAvailabilityService};
+use crate::error::Result;
+use crate::signing::Signer;
+
+use futures::channel::oneshot;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Value};
+use url::Url;
+
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default number of items per page.
+const PER_PAGE: usize = 20;
+/// Maximum number of items per page.
+const MAX_PER_PAGE: usize = 100;
+/// Number of seconds until an access token expires.
+const EXPIRES_IN: u64 = 3600;
+
+/// A member of the dataset.
+#[derive(Debug, Clone)]
+struct Member {
+    id: String,
+    username: String,
+    password: String,
+}
+
+/// A film of the dataset.
+#[derive(Debug, Clone)]
+struct Film {
+    id: String,
+    name: String,
+    release_year: u16,
+    imdb_id: Option<String>,
+    tmdb_id: Option<String>,
+}
+
+/// A list of the dataset.
+#[derive(Debug, Clone)]
+struct List {
+    id: String,
+    owner: String,
+    name: String,
+    description: Option<String>,
+    published: bool,
+    ranked: bool,
+    entries: Vec<ListEntry>,
+    when_created: String,
+}
+
+/// An entry of a list of the dataset.
+#[derive(Debug, Clone)]
+struct ListEntry {
+    film: String,
+    notes: Option<String>,
+    contains_spoilers: bool,
+}
+
+/// A log entry of the dataset.
+#[derive(Debug, Clone)]
+struct LogEntry {
+    id: String,
+    owner: String,
+    film: String,
+    diary_date: Option<String>,
+    rewatch: bool,
+    review: Option<String>,
+    contains_spoilers: bool,
+    rating: Option<f32>,
+    like: bool,
+    tags: Vec<String>,
+    when_created: String,
+    when_updated: String,
+}
+
+/// The availability of a film on a service in a country.
+#[derive(Debug, Clone)]
+struct Availability {
+    film: String,
+    service: FilmAvailabilityService,
+    country: Country,
+}
+
+/// The relationship of a member with a film.
+#[derive(Debug, Clone, Default)]
+struct Relationship {
+    watched: bool,
+    liked: bool,
+    in_watchlist: bool,
+    rating: Option<f32>,
+}
+
+/// Data served by a mock server.
+#[derive(Debug, Clone, Default)]
+pub struct Dataset {
+    members: Vec<Member>,
+    films: Vec<Film>,
+    lists: Vec<List>,
+    log_entries: Vec<LogEntry>,
+    relationships: HashMap<(String, String), Relationship>,
+    availability: Vec<Availability>,
+    next_id: usize,
+}
+
+impl Dataset {
+    /// Creates an empty dataset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a small dataset with two members, five films, a list and a few log entries.
+    ///
+    /// The members `alice` (LID `m1`) and `bob` (LID `m2`) have the password `password`.
+    pub fn seeded() -> Self {
+        let mut dataset = Self::new();
+        dataset
+            .add_member("m1", "alice", "password")
+            .add_member("m2", "bob", "password")
+            .add_film("2a9q", "Fight Club", 1999)
+            .add_film("bPI", "Melancholia", 2011)
+            .add_film("hTha", "Parasite", 2019)
+            .add_film("1Kxk", "Stalker", 1979)
+            .add_film("hEQi", "Portrait of a Lady on Fire", 2019)
+            .set_external_ids("2a9q", "tt0137523", "550")
+            .set_external_ids("bPI", "tt1527186", "62215")
+            .set_external_ids("hTha", "tt6751668", "496243")
+            .set_external_ids("1Kxk", "tt0079944", "1398")
+            .set_external_ids("hEQi", "tt8613070", "531428")
+            .add_list("l1", "m1", "Favorites", &["hTha", "2a9q", "bPI"])
+            .add_log_entry("e1", "m1", "2a9q", Some("2020-01-01"), Some(4.5))
+            .add_log_entry("e2", "m1", "bPI", Some("2020-02-01"), Some(5.0))
+            .add_log_entry("e3", "m2", "hTha", Some("2020-02-03"), Some(4.0))
+            .add_to_watchlist("m1", "1Kxk")
+            .add_to_watchlist("m1", "hEQi");
+        dataset
+    }
+
+    /// Adds a member which can authenticate with the given username and password.
+    pub fn add_member(&mut self, id: &str, username: &str, password: &str) -> &mut Self {
+        self.members.push(Member {
+            id: id.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+        self
+    }
+
+    /// Adds a film.
+    pub fn add_film(&mut self, id: &str, name: &str, release_year: u16) -> &mut Self {
+        self.films.push(Film {
+            id: id.to_string(),
+            name: name.to_string(),
+            release_year,
+            imdb_id: None,
+            tmdb_id: None,
+        });
+        self
+    }
+
+    /// Sets the IMDb and TMDB IDs of a film, which are linked from the film and by which it
+    /// can be requested.
+    pub fn set_external_ids(&mut self, film: &str, imdb_id: &str, tmdb_id: &str) -> &mut Self {
+        if let Some(film) = self.films.iter_mut().find(|f| f.id == film) {
+            film.imdb_id = Some(imdb_id.to_string());
+            film.tmdb_id = Some(tmdb_id.to_string());
+        }
+        self
+    }
+
+    /// Makes a film available on a service in a country.
+    ///
+    /// The services of the dataset are listed by `films/film-services`, with the name of the
+    /// variant as name and in lowercase as LID, e.g. `netflix`.
+    pub fn add_availability(
+        &mut self,
+        film: &str,
+        service: FilmAvailabilityService,
+        country: Country,
+    ) -> &mut Self {
+        self.availability.push(Availability {
+            film: film.to_string(),
+            service,
+            country,
+        });
+        self
+    }
+
+    /// Adds a published and ranked list of films owned by a member.
+    pub fn add_list(&mut self, id: &str, owner: &str, name: &str, films: &[&str]) -> &mut Self {
+        self.lists.push(List {
+            id: id.to_string(),
+            owner: owner.to_string(),
+            name: name.to_string(),
+            description: None,
+            published: true,
+            ranked: true,
+            entries: films
+                .iter()
+                .map(|film| ListEntry {
+                    film: film.to_string(),
+                    notes: None,
+                    contains_spoilers: false,
+                })
+                .collect(),
+            when_created: now(),
+        });
+        self
+    }
+
+    /// Adds a log entry of a film, and marks the film as watched and rated by the member.
+    ///
+    /// Log entries are ordered by creation, i.e. the last added entry is the most recent one.
+    pub fn add_log_entry(
+        &mut self,
+        id: &str,
+        owner: &str,
+        film: &str,
+        diary_date: Option<&str>,
+        rating: Option<f32>,
+    ) -> &mut Self {
+        let when_created = timestamp(1_577_836_800 + 60 * self.log_entries.len() as u64);
+        self.log_entries.push(LogEntry {
+            id: id.to_string(),
+            owner: owner.to_string(),
+            film: film.to_string(),
+            diary_date: diary_date.map(String::from),
+            rewatch: false,
+            review: None,
+            contains_spoilers: false,
+            rating,
+            like: false,
+            tags: Vec::new(),
+            when_created: when_created.clone(),
+            when_updated: when_created,
+        });
+        let relationship = self.relationship_mut(owner, film);
+        relationship.watched = true;
+        if rating.is_some() {
+            relationship.rating = rating;
+        }
+        self
+    }
+
+    /// Changes the rating of a log entry, which is then updated now.
+    pub fn rate_log_entry(&mut self, id: &str, rating: Option<f32>) -> &mut Self {
+        if let Some(entry) = self.log_entries.iter_mut().find(|entry| entry.id == id) {
+            entry.rating = rating;
+            entry.when_updated = now();
+        }
+        self
+    }
+
+    /// Removes a log entry.
+    pub fn remove_log_entry(&mut self, id: &str) -> &mut Self {
+        self.log_entries.retain(|entry| entry.id != id);
+        self
+    }
+
+    /// Adds a film to the watchlist of a member.
+    pub fn add_to_watchlist(&mut self, member: &str, film: &str) -> &mut Self {
+        self.relationship_mut(member, film).in_watchlist = true;
+        self
+    }
+
+    fn member(&self, id: &str) -> Option<&Member> {
+        self.members.iter().find(|member| member.id == id)
+    }
+
+    fn film(&self, id: &str) -> Option<&Film> {
+        self.films.iter().find(|film| film.id == id)
+    }
+
+    /// Returns a film by LID or by an `imdb:` or `tmdb:` identifier.
+    fn film_by_identifier(&self, identifier: &str) -> Option<&Film> {
+        match identifier.split_once(':') {
+            Some(("imdb", id)) => self
+                .films
+                .iter()
+                .find(|film| film.imdb_id.as_deref() == Some(id)),
+            Some(("tmdb", id)) => self
+                .films
+                .iter()
+                .find(|film| film.tmdb_id.as_deref() == Some(id)),
+            _ => self.film(identifier),
+        }
+    }
+
+    fn relationship(&self, member: &str, film: &str) -> Relationship {
+        self.relationships
+            .get(&(member.to_string(), film.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn relationship_mut(&mut self, member: &str, film: &str) -> &mut Relationship {
+        self.relationships
+            .entry((member.to_string(), film.to_string()))
+            .or_default()
+    }
+
+    fn generate_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("mock{}", self.next_id)
+    }
+
+    // JSON representation of the entities
+
+    fn member_json(&self, id: &str) -> Value {
+        let username = self.member(id).map_or(id, |member| &member.username);
+        json!({
+            "id": id,
+            "username": username,
+            "displayName": username,
+            "shortName": username,
+            "pronoun": pronoun_json(),
+            "avatar": { "sizes": [] },
+            "memberStatus": "Member"
+        })
+    }
+
+    fn relationship_json(&self, member: &str, film: &str) -> Value {
+        let relationship = self.relationship(member, film);
+        let entries = || {
+            self.log_entries
+                .iter()
+                .rev()
+                .filter(move |entry| entry.owner == member && entry.film == film)
+        };
+        let reviews: Vec<&str> = entries()
+            .filter(|entry| entry.review.is_some())
+            .map(|entry| entry.id.as_str())
+            .collect();
+        let diary_entries: Vec<&str> = entries()
+            .filter(|entry| entry.diary_date.is_some())
+            .map(|entry| entry.id.as_str())
+            .collect();
+        json!({
+            "watched": relationship.watched,
+            "liked": relationship.liked,
+            "favorited": false,
+            "inWatchlist": relationship.in_watchlist,
+            "rating": relationship.rating,
+            "reviews": reviews,
+            "diaryEntries": diary_entries
+        })
+    }
+
+    fn film_links_json(film: &Film) -> Value {
+        let mut links = vec![json!({
+            "type": "letterboxd",
+            "id": film.id,
+            "url": format!("https://letterboxd.com/film/{}/", film.id)
+        })];
+        if let Some(id) = film.tmdb_id.as_ref() {
+            links.push(json!({
+                "type": "tmdb",
+                "id": id,
+                "url": format!("https://www.themoviedb.org/movie/{}/", id)
+            }));
+        }
+        if let Some(id) = film.imdb_id.as_ref() {
+            links.push(json!({
+                "type": "imdb",
+                "id": id,
+                "url": format!("http://www.imdb.com/title/{}/maindetails", id)
+            }));
+        }
+        Value::Array(links)
+    }
+
+    /// Summary of a film including the relationships of the given members.
+    fn film_summary_json(&self, film: &Film, members: &[&str]) -> Value {
+        let relationships: Vec<Value> = members
+            .iter()
+            .map(|member| {
+                json!({
+                    "member": self.member_json(member),
+                    "relationship": self.relationship_json(member, &film.id)
+                })
+            })
+            .collect();
+        json!({
+            "id": film.id,
+            "name": film.name,
+            "releaseYear": film.release_year,
+            "directors": [],
+            "relationships": relationships,
+            "links": Self::film_links_json(film)
+        })
+    }
+
+    fn film_json(&self, film: &Film) -> Value {
+        json!({
+            "id": film.id,
+            "name": film.name,
+            "alternativeNames": [],
+            "releaseYear": film.release_year,
+            "tagline": "",
+            "description": "",
+            "runTime": 0,
+            "poster": { "sizes": [] },
+            "backdrop": { "sizes": [] },
+            "backdropFocalPoint": 0.0,
+            "trailer": { "id": "", "url": "" },
+            "genres": [],
+            "contributions": [],
+            "links": Self::film_links_json(film)
+        })
+    }
+
+    fn list_entries_json(&self, list: &List, viewer: &[&str]) -> Vec<Value> {
+        list.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let film = self.film(&entry.film)?;
+                let mut json = json!({
+                    "notesLbml": entry.notes,
+                    "notes": entry.notes,
+                    "containsSpoilers": entry.contains_spoilers,
+                    "film": self.film_summary_json(film, viewer)
+                });
+                if list.ranked {
+                    json["rank"] = json!(index + 1);
+                }
+                Some(json)
+            })
+            .collect()
+    }
+
+    fn list_summary_json(&self, list: &List) -> Value {
+        let preview_entries: Vec<Value> = self
+            .list_entries_json(list, &[])
+            .into_iter()
+            .take(4)
+            .collect();
+        json!({
+            "id": list.id,
+            "name": list.name,
+            "filmCount": list.entries.len(),
+            "published": list.published,
+            "ranked": list.ranked,
+            "descriptionLbml": list.description,
+            "description": list.description,
+            "owner": self.member_json(&list.owner),
+            "previewEntries": preview_entries
+        })
+    }
+
+    fn list_json(&self, list: &List) -> Value {
+        let mut json = self.list_summary_json(list);
+        json["hasEntriesWithNotes"] = json!(list.entries.iter().any(|e| e.notes.is_some()));
+        json["tags2"] = json!([]);
+        json["whenCreated"] = json!(list.when_created);
+        json["whenPublished"] = json!(Some(&list.when_created).filter(|_| list.published));
+        json["links"] = json!([{
+            "type": "letterboxd",
+            "id": list.id,
+            "url": format!("https://boxd.it/{}", list.id)
+        }]);
+        json
+    }
+
+    fn log_entry_json(&self, entry: &LogEntry) -> Value {
+        let film = self.film(&entry.film);
+        let tags: Vec<Value> = entry
+            .tags
+            .iter()
+            .map(|tag| json!({ "code": tag, "displayTag": tag }))
+            .collect();
+        json!({
+            "id": entry.id,
+            "name": film.map_or("", |film| &film.name),
+            "owner": self.member_json(&entry.owner),
+            "film": film.map(|film| self.film_summary_json(film, &[&entry.owner])),
+            "diaryDetails": entry.diary_date.as_ref().map(|date| json!({
+                "diaryDate": date,
+                "rewatch": entry.rewatch
+            })),
+            "review": entry.review.as_ref().map(|review| json!({
+                "lbml": review,
+                "containsSpoilers": entry.contains_spoilers,
+                "whenReviewed": entry.when_created,
+                "text": review
+            })),
+            "tags2": tags,
+            "whenCreated": entry.when_created,
+            "whenUpdated": entry.when_updated,
+            "rating": entry.rating,
+            "like": entry.like,
+            "commentable": true,
+            "links": [{
+                "type": "letterboxd",
+                "id": entry.id,
+                "url": format!("https://boxd.it/{}", entry.id)
+            }]
+        })
+    }
+}
+
+/// State of a running mock server.
+#[derive(Debug)]
+struct State {
+    api_key_pair: ApiKeyPair,
+    dataset: Dataset,
+    /// Member LIDs by access token.
+    access_tokens: HashMap<String, String>,
+    /// Member LIDs by refresh token.
+    refresh_tokens: HashMap<String, String>,
+    /// Nonces of all requests, to reject replayed requests.
+    nonces: HashSet<String>,
+}
+
+/// A parsed request to the mock server.
+struct MockRequest {
+    method: Method,
+    segments: Vec<String>,
+    query: Vec<(String, String)>,
+    body: Vec<u8>,
+    /// The LID of the authenticated member.
+    member: Option<String>,
+}
+
+impl MockRequest {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn json(&self) -> std::result::Result<Value, (StatusCode, Value)> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| error(StatusCode::BAD_REQUEST, &e.to_string()))
+    }
+
+    fn viewer(&self) -> Vec<&str> {
+        self.member.iter().map(String::as_str).collect()
+    }
+}
+
+type Reply = std::result::Result<(StatusCode, Value), (StatusCode, Value)>;
+
+/// Local HTTP server mocking the API.
+///
+/// The server is stopped when it is dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts a server on an ephemeral port of the loopback interface.
+    ///
+    /// Requests must be signed with the given API key pair.
+    pub fn start(api_key_pair: ApiKeyPair, dataset: Dataset) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State {
+            api_key_pair,
+            dataset,
+            access_tokens: HashMap::new(),
+            refresh_tokens: HashMap::new(),
+            nonces: HashSet::new(),
+        }));
+
+        let mut rt = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()?;
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let server_state = state.clone();
+        let thread = thread::spawn(move || {
+            rt.block_on(async move {
+                let make_service = make_service_fn(move |_| {
+                    let state = server_state.clone();
+                    async move {
+                        Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), addr, req)))
+                    }
+                });
+                let server = match hyper::Server::from_tcp(listener) {
+                    Ok(server) => server,
+                    Err(_) => return,
+                };
+                let _ = server
+                    .serve(make_service)
+                    .with_graceful_shutdown(async {
+                        let _ = shutdown_rx.await;
+                    })
+                    .await;
+            })
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+
+    /// Returns the address of the server.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the base url of the mocked API.
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/api/v0/", self.addr)).expect("invalid url")
+    }
+
+    /// Creates a new client for the server without authentication.
+    pub fn client(&self) -> Client {
+        let api_key_pair = self
+            .state
+            .lock()
+            .expect("poisoned lock")
+            .api_key_pair
+            .clone();
+        let mut client = Client::new(api_key_pair);
+        client.set_api_base_url(self.url());
+        client
+    }
+
+    /// Returns a snapshot of the current data of the server.
+    pub fn dataset(&self) -> Dataset {
+        self.state.lock().expect("poisoned lock").dataset.clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    addr: SocketAddr,
+    req: Request<Body>,
+) -> std::result::Result<Response<Body>, hyper::Error> {
+    let (parts, body) = req.into_parts();
+    let body = hyper::body::to_bytes(body).await?.to_vec();
+
+    let mut state = state.lock().expect("poisoned lock");
+    let reply = state.verify(addr, &parts, &body).and_then(|member| {
+        let path = parts.uri.path();
+        let segments = match path.strip_prefix("/api/v0/") {
+            Some(path) => path
+                .split('/')
+                .map(|segment| percent_decode_str(segment).decode_utf8_lossy().into_owned())
+                .collect(),
+            None => return Err(error(StatusCode::NOT_FOUND, "unknown endpoint")),
+        };
+        let query = url::form_urlencoded::parse(parts.uri.query().unwrap_or("").as_bytes())
+            .into_owned()
+            .collect();
+        let request = MockRequest {
+            method: parts.method.clone(),
+            segments,
+            query,
+            body,
+            member,
+        };
+        state.route(&request)
+    });
+
+    let (status, json) = reply.unwrap_or_else(|e| e);
+    let body = match status {
+        StatusCode::NO_CONTENT => Body::empty(),
+        _ => Body::from(json.to_string()),
+    };
+    Ok(Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .expect("invalid response"))
+}
+
+impl State {
+    /// Verifies the signature of a request and returns the LID of the authenticated member.
+    fn verify(
+        &mut self,
+        addr: SocketAddr,
+        parts: &hyper::http::request::Parts,
+        body: &[u8],
+    ) -> std::result::Result<Option<String>, (StatusCode, Value)> {
+        let unauthorized = |message| error(StatusCode::UNAUTHORIZED, message);
+        let path_and_query = parts
+            .uri
+            .path_and_query()
+            .map_or("", |path_and_query| path_and_query.as_str());
+        let url = Url::parse(&format!("http://{}{}", addr, path_and_query))
+            .map_err(|_| unauthorized("invalid url"))?;
+        Signer::new()
+            .verify(&parts.method, &url, body, &self.api_key_pair)
+            .map_err(|e| error(StatusCode::UNAUTHORIZED, &e.to_string()))?;
+        let nonce = url
+            .query_pairs()
+            .find(|(key, _)| key == "nonce")
+            .map(|(_, nonce)| nonce.into_owned())
+            .unwrap_or_default();
+        if !self.nonces.insert(nonce) {
+            return Err(unauthorized("reused nonce"));
+        }
+
+        match parts.headers.get(header::AUTHORIZATION) {
+            Some(value) => {
+                let token = value
+                    .to_str()
+                    .ok()
+                    .and_then(|value| value.strip_prefix("Bearer "))
+                    .ok_or_else(|| unauthorized("invalid authorization"))?;
+                match self.access_tokens.get(token) {
+                    Some(member) => Ok(Some(member.clone())),
+                    None => Err(unauthorized("invalid access token")),
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn route(&mut self, req: &MockRequest) -> Reply {
+        let segments: Vec<&str> = req.segments.iter().map(String::as_str).collect();
+        match (&req.method, segments.as_slice()) {
+            (&Method::POST, ["auth", "token"]) => self.token(req),
+            (&Method::GET, ["films"]) => self.films(req),
+            (&Method::GET, ["films", "genres"]) => ok(json!({
+                "items": [
+                    { "id": "8G", "name": "Horror" },
+                    { "id": "9k", "name": "Drama" }
+                ]
+            })),
+            (&Method::GET, ["films", "film-services"]) => {
+                let mut names: Vec<String> = self
+                    .dataset
+                    .availability
+                    .iter()
+                    .map(|availability| service_name(availability.service))
+                    .collect();
+                names.sort();
+                names.dedup();
+                let services: Vec<Value> = names
+                    .iter()
+                    .map(|name| json!({ "id": name.to_lowercase(), "name": name }))
+                    .collect();
+                ok(json!({ "items": services }))
+            }
+            (&Method::GET, ["film", id]) => {
+                let film = self.dataset.film_by_identifier(id).ok_or_else(not_found)?;
+                ok(self.dataset.film_json(film))
+            }
+            (&Method::GET, ["film", id, "availability"]) => {
+                self.dataset.film(id).ok_or_else(not_found)?;
+                let items: Vec<Value> = self
+                    .dataset
+                    .availability
+                    .iter()
+                    .filter(|availability| availability.film == *id)
+                    .map(|availability| {
+                        let name = service_name(availability.service);
+                        json!({
+                            "service": availability.service,
+                            "displayName": name,
+                            "country": availability.country,
+                            "id": id,
+                            "url": format!("https://{}.example.com/{}", name.to_lowercase(), id)
+                        })
+                    })
+                    .collect();
+                ok(json!({ "items": items }))
+            }
+            (&Method::GET, ["film", id, "me"]) => {
+                let member = authenticated(req)?;
+                self.dataset.film(id).ok_or_else(not_found)?;
+                ok(self.dataset.relationship_json(member, id))
+            }
+            (&Method::PATCH, ["film", id, "me"]) => self.update_relationship(req, id),
+            (&Method::GET, ["lists"]) => self.lists(req),
+            (&Method::POST, ["lists"]) => self.create_list(req),
+            (&Method::GET, ["list", id]) => {
+                let list = self.visible_list(req, id)?;
+                ok(self.dataset.list_json(list))
+            }
+            (&Method::PATCH, ["list", id]) => self.update_list(req, id),
+            (&Method::DELETE, ["list", id]) => {
+                self.owned_list(req, id)?;
+                self.dataset.lists.retain(|list| list.id != *id);
+                Ok((StatusCode::NO_CONTENT, Value::Null))
+            }
+            (&Method::GET, ["list", id, "entries"]) => {
+                let list = self.visible_list(req, id)?;
+                page(req, self.dataset.list_entries_json(list, &req.viewer()))
+            }
+            (&Method::GET, ["log-entries"]) => self.log_entries(req),
+            (&Method::POST, ["log-entries"]) => self.create_log_entry(req),
+            (&Method::GET, ["member", id, "watchlist"]) => {
+                self.dataset.member(id).ok_or_else(not_found)?;
+                let films = self
+                    .dataset
+                    .films
+                    .iter()
+                    .filter(|film| self.dataset.relationship(id, &film.id).in_watchlist)
+                    .map(|film| self.dataset.film_summary_json(film, &req.viewer()))
+                    .collect();
+                page(req, films)
+            }
+            (&Method::GET, ["members", "pronouns"]) => ok(json!({ "items": [pronoun_json()] })),
+            (&Method::GET, ["search"]) => self.search(req),
+            _ => Err(not_found()),
+        }
+    }
+
+    fn token(&mut self, req: &MockRequest) -> Reply {
+        let params: HashMap<String, String> = url::form_urlencoded::parse(&req.body)
+            .into_owned()
+            .collect();
+        let param = |name: &str| params.get(name).map(String::as_str);
+        let invalid_grant = || (StatusCode::BAD_REQUEST, json!({ "error": "invalid_grant" }));
+        let member = match param("grant_type") {
+            Some("password") => self
+                .dataset
+                .members
+                .iter()
+                .find(|member| {
+                    Some(member.username.as_str()) == param("username")
+                        && Some(member.password.as_str()) == param("password")
+                })
+                .map(|member| member.id.clone()),
+            Some("refresh_token") => param("refresh_token")
+                .and_then(|token| self.refresh_tokens.get(token))
+                .cloned(),
+            _ => None,
+        }
+        .ok_or_else(invalid_grant)?;
+
+        let access_token = uuid::Uuid::new_v4().to_string();
+        let refresh_token = uuid::Uuid::new_v4().to_string();
+        self.access_tokens
+            .insert(access_token.clone(), member.clone());
+        self.refresh_tokens.insert(refresh_token.clone(), member);
+        ok(json!({
+            "access_token": access_token,
+            "token_type": "bearer",
+            "refresh_token": refresh_token,
+            "expires_in": EXPIRES_IN
+        }))
+    }
+
+    fn films(&self, req: &MockRequest) -> Reply {
+        let member = req.param("member");
+        let relationship = req.param("memberRelationship");
+        let service = req.param("service");
+        let mut viewer = req.viewer();
+        if let Some(member) = member {
+            if !viewer.contains(&member) {
+                viewer.push(member);
+            }
+        }
+        let films = self
+            .dataset
+            .films
+            .iter()
+            .filter(|film| match member {
+                Some(member) => {
+                    let r = self.dataset.relationship(member, &film.id);
+                    // the relationship defaults to `Watched`
+                    match relationship.unwrap_or("Watched") {
+                        "Watched" => r.watched,
+                        "NotWatched" => !r.watched,
+                        "Liked" => r.liked,
+                        "NotLiked" => !r.liked,
+                        "InWatchlist" => r.in_watchlist,
+                        "NotInWatchlist" => !r.in_watchlist,
+                        _ => false,
+                    }
+                }
+                None => true,
+            })
+            .filter(|film| match service {
+                Some(service) => self.dataset.availability.iter().any(|availability| {
+                    availability.film == film.id
+                        && service_name(availability.service).to_lowercase() == service
+                }),
+                None => true,
+            })
+            .map(|film| self.dataset.film_summary_json(film, &viewer))
+            .collect();
+        page(req, films)
+    }
+
+    fn update_relationship(&mut self, req: &MockRequest, id: &str) -> Reply {
+        let member = authenticated(req)?.to_string();
+        self.dataset.film(id).ok_or_else(not_found)?;
+        let update = req.json()?;
+        let relationship = self.dataset.relationship_mut(&member, id);
+        if let Some(watched) = update["watched"].as_bool() {
+            relationship.watched = watched;
+            if !watched {
+                relationship.rating = None;
+            }
+        }
+        if let Some(liked) = update["liked"].as_bool() {
+            relationship.liked = liked;
+        }
+        if let Some(in_watchlist) = update["inWatchlist"].as_bool() {
+            relationship.in_watchlist = in_watchlist;
+        }
+        let mut messages = Vec::new();
+        if let Some(rating) = update["rating"].as_f64() {
+            if valid_rating(rating) {
+                relationship.rating = Some(rating as f32);
+                relationship.watched = true;
+            } else {
+                messages.push(json!({
+                    "type": "Error",
+                    "code": "InvalidRatingValue",
+                    "title": "Invalid rating value"
+                }));
+            }
+        }
+        ok(json!({
+            "data": self.dataset.relationship_json(&member, id),
+            "messages": messages
+        }))
+    }
+
+    fn visible_list(
+        &self,
+        req: &MockRequest,
+        id: &str,
+    ) -> std::result::Result<&List, (StatusCode, Value)> {
+        self.dataset
+            .lists
+            .iter()
+            .find(|list| {
+                list.id == id && (list.published || req.member.as_ref() == Some(&list.owner))
+            })
+            .ok_or_else(not_found)
+    }
+
+    fn owned_list(
+        &self,
+        req: &MockRequest,
+        id: &str,
+    ) -> std::result::Result<usize, (StatusCode, Value)> {
+        let member = authenticated(req)?;
+        let index = self
+            .dataset
+            .lists
+            .iter()
+            .position(|list| list.id == id)
+            .ok_or_else(not_found)?;
+        if self.dataset.lists[index].owner != member {
+            return Err(error(StatusCode::FORBIDDEN, "not the owner of the list"));
+        }
+        Ok(index)
+    }
+
+    fn lists(&self, req: &MockRequest) -> Reply {
+        let member = req.param("member");
+        let lists = self
+            .dataset
+            .lists
+            .iter()
+            .filter(|list| list.published || req.member.as_ref() == Some(&list.owner))
+            .filter(|list| member.is_none_or(|member| list.owner == member))
+            .rev()
+            .map(|list| self.dataset.list_summary_json(list))
+            .collect();
+        page(req, lists)
+    }
+
+    /// Parses list entries of a creation or update request.
+    fn list_entries(
+        &self,
+        entries: &Value,
+    ) -> std::result::Result<Vec<(ListEntry, Option<usize>)>, Value> {
+        let mut parsed: Vec<(ListEntry, Option<usize>)> = Vec::new();
+        for entry in entries.as_array().into_iter().flatten() {
+            let film = entry["film"].as_str().unwrap_or_default();
+            if self.dataset.film(film).is_none() {
+                return Err(json!({
+                    "type": "Error",
+                    "code": "UnknownFilmCode",
+                    "title": format!("Unknown film code {}", film)
+                }));
+            }
+            let list_entry = ListEntry {
+                film: film.to_string(),
+                notes: entry["notes"].as_str().map(String::from),
+                contains_spoilers: entry["containsSpoilers"].as_bool().unwrap_or(false),
+            };
+            let rank = entry["rank"].as_u64().map(|rank| rank as usize);
+            if let Some(rank) =
+                rank.filter(|&rank| parsed.iter().any(|&(_, other)| other == Some(rank)))
+            {
+                return Err(json!({
+                    "type": "Error",
+                    "code": "DuplicateRank",
+                    "title": format!("Duplicate rank {}", rank)
+                }));
+            }
+            parsed.push((list_entry, rank));
+        }
+        Ok(parsed)
+    }
+
+    fn create_list(&mut self, req: &MockRequest) -> Reply {
+        let member = authenticated(req)?.to_string();
+        let request = req.json()?;
+        let name = request["name"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if name.is_empty() {
+            return Err(message_error(json!({
+                "type": "Error",
+                "code": "ListNameIsBlank",
+                "title": "The list name is blank"
+            })));
+        }
+        let mut entries = self
+            .list_entries(&request["entries"])
+            .map_err(message_error)?;
+        // entries with a rank come first in the order of their ranks
+        entries.sort_by_key(|(_, rank)| rank.unwrap_or(usize::MAX));
+
+        let id = self.dataset.generate_id();
+        self.dataset.lists.push(List {
+            id: id.clone(),
+            owner: member,
+            name,
+            description: request["description"].as_str().map(String::from),
+            published: request["published"].as_bool().unwrap_or(false),
+            ranked: request["ranked"].as_bool().unwrap_or(false),
+            entries: entries.into_iter().map(|(entry, _)| entry).collect(),
+            when_created: now(),
+        });
+        let list = self.dataset.lists.last().expect("list was added");
+        Ok((
+            StatusCode::CREATED,
+            json!({ "data": self.dataset.list_json(list), "messages": [] }),
+        ))
+    }
+
+    fn update_list(&mut self, req: &MockRequest, id: &str) -> Reply {
+        let index = self.owned_list(req, id)?;
+        let request = req.json()?;
+        let entries = self
+            .list_entries(&request["entries"])
+            .map_err(message_error)?;
+
+        let list = &mut self.dataset.lists[index];
+        if let Some(name) = request["name"].as_str() {
+            list.name = name.to_string();
+        }
+        if let Some(published) = request["published"].as_bool() {
+            list.published = published;
+        }
+        if let Some(ranked) = request["ranked"].as_bool() {
+            list.ranked = ranked;
+        }
+        if let Some(description) = request["description"].as_str() {
+            list.description = Some(description.to_string());
+        }
+        let films_to_remove: Vec<&str> = request["filmsToRemove"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        list.entries
+            .retain(|entry| !films_to_remove.contains(&entry.film.as_str()));
+
+        // the ranked entries take their ranks, the others keep their relative order and new
+        // entries without a rank are appended
+        let mut ranked = Vec::new();
+        for (update, rank) in entries {
+            let position = list.entries.iter().position(|e| e.film == update.film);
+            let mut entry = match position {
+                Some(position) if rank.is_some() => list.entries.remove(position),
+                Some(position) => {
+                    let entry = &mut list.entries[position];
+                    entry.notes = update.notes.or_else(|| entry.notes.take());
+                    entry.contains_spoilers = update.contains_spoilers;
+                    continue;
+                }
+                None => update.clone(),
+            };
+            if update.notes.is_some() {
+                entry.notes = update.notes;
+                entry.contains_spoilers = update.contains_spoilers;
+            }
+            match rank {
+                Some(rank) => ranked.push((rank, entry)),
+                None => list.entries.push(entry),
+            }
+        }
+        ranked.sort_by_key(|&(rank, _)| rank);
+        for (rank, entry) in ranked {
+            let index = rank.saturating_sub(1).min(list.entries.len());
+            list.entries.insert(index, entry);
+        }
+
+        let list = &self.dataset.lists[index];
+        ok(json!({ "data": self.dataset.list_json(list), "messages": [] }))
+    }
+
+    fn log_entries(&self, req: &MockRequest) -> Reply {
+        let member = req.param("member");
+        let film = req.param("film");
+        let entries = self
+            .dataset
+            .log_entries
+            .iter()
+            .rev()
+            .filter(|entry| member.is_none_or(|member| entry.owner == member))
+            .filter(|entry| film.is_none_or(|film| entry.film == film))
+            .map(|entry| self.dataset.log_entry_json(entry))
+            .collect();
+        page(req, entries)
+    }
+
+    fn create_log_entry(&mut self, req: &MockRequest) -> Reply {
+        let member = authenticated(req)?.to_string();
+        let request = req.json()?;
+        let film = request["filmId"].as_str().unwrap_or_default().to_string();
+        self.dataset.film(&film).ok_or_else(not_found)?;
+        let rating = request["rating"].as_f64();
+        if let Some(rating) = rating {
+            if !valid_rating(rating) {
+                return Err(error(StatusCode::BAD_REQUEST, "invalid rating value"));
+            }
+        }
+
+        let id = self.dataset.generate_id();
+        let when_created = now();
+        let diary_details = &request["diaryDetails"];
+        let entry = LogEntry {
+            id,
+            owner: member.clone(),
+            film: film.clone(),
+            diary_date: diary_details["diaryDate"].as_str().map(String::from),
+            rewatch: diary_details["rewatch"].as_bool().unwrap_or(false),
+            review: request["review"]["text"].as_str().map(String::from),
+            contains_spoilers: request["review"]["containsSpoilers"]
+                .as_bool()
+                .unwrap_or(false),
+            rating: rating.map(|rating| rating as f32),
+            like: request["like"].as_bool().unwrap_or(false),
+            tags: request["tags"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect(),
+            when_created: when_created.clone(),
+            when_updated: when_created,
+        };
+
+        let relationship = self.dataset.relationship_mut(&member, &film);
+        if entry.diary_date.is_some() || entry.rating.is_some() {
+            relationship.watched = true;
+        }
+        if entry.rating.is_some() {
+            relationship.rating = entry.rating;
+        }
+        if entry.like {
+            relationship.liked = true;
+        }
+        let json = self.dataset.log_entry_json(&entry);
+        self.dataset.log_entries.push(entry);
+        Ok((StatusCode::CREATED, json))
+    }
+
+    fn search(&self, req: &MockRequest) -> Reply {
+        let input = req.param("input").unwrap_or_default().to_lowercase();
+        let matches = |name: &str| !input.is_empty() && name.to_lowercase().contains(&input);
+        let films = self
+            .dataset
+            .films
+            .iter()
+            .filter(|film| matches(&film.name))
+            .map(|film| {
+                json!({
+                    "type": "FilmSearchItem",
+                    "score": 1.0,
+                    "film": self.dataset.film_summary_json(film, &req.viewer())
+                })
+            });
+        let lists = self
+            .dataset
+            .lists
+            .iter()
+            .filter(|list| list.published && matches(&list.name))
+            .map(|list| {
+                json!({
+                    "type": "ListSearchItem",
+                    "score": 1.0,
+                    "list": self.dataset.list_summary_json(list)
+                })
+            });
+        let members = self
+            .dataset
+            .members
+            .iter()
+            .filter(|member| matches(&member.username))
+            .map(|member| {
+                json!({
+                    "type": "MemberSearchItem",
+                    "score": 1.0,
+                    "member": self.dataset.member_json(&member.id)
+                })
+            });
+        page(req, films.chain(lists).chain(members).collect())
+    }
+}
+
+/// Returns a page of items according to the `cursor` and `perPage` parameters.
+fn page(req: &MockRequest, items: Vec<Value>) -> Reply {
+    let start = match req.param("cursor") {
+        Some(cursor) => cursor
+            .strip_prefix("start=")
+            .and_then(|start| start.parse::<usize>().ok())
+            .ok_or_else(|| error(StatusCode::BAD_REQUEST, "invalid cursor"))?,
+        None => 0,
+    };
+    let per_page = req
+        .param("perPage")
+        .and_then(|per_page| per_page.parse().ok())
+        .unwrap_or(PER_PAGE)
+        .clamp(1, MAX_PER_PAGE);
+    let end = start.saturating_add(per_page).min(items.len());
+    let next = Some(format!("start={}", end)).filter(|_| end < items.len());
+    let items: Vec<Value> = items.into_iter().skip(start).take(per_page).collect();
+    ok(json!({ "next": next, "items": items }))
+}
+
+fn authenticated(req: &MockRequest) -> std::result::Result<&str, (StatusCode, Value)> {
+    req.member
+        .as_deref()
+        .ok_or_else(|| error(StatusCode::UNAUTHORIZED, "authentication required"))
+}
+
+fn valid_rating(rating: f64) -> bool {
+    (0.5..=5.0).contains(&rating) && (rating * 2.0).fract() == 0.0
+}
+
+fn ok(json: Value) -> Reply {
+    Ok((StatusCode::OK, json))
+}
+
+fn error(status: StatusCode, message: &str) -> (StatusCode, Value) {
+    (
+        status,
+        json!({ "code": status.as_u16(), "message": message }),
+    )
+}
+
+fn not_found() -> (StatusCode, Value) {
+    error(StatusCode::NOT_FOUND, "not found")
+}
+
+fn message_error(message: Value) -> (StatusCode, Value) {
+    (StatusCode::BAD_REQUEST, json!({ "messages": [message] }))
+}
+
+fn pronoun_json() -> Value {
+    json!({
+        "id": "they",
+        "label": "They / their",
+        "subjectPronoun": "they",
+        "objectPronoun": "them",
+        "possessiveAdjective": "their",
+        "possessivePronoun": "theirs",
+        "reflexive": "themselves"
+    })
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("SystemTime::duration_since failed")
+        .as_secs()
+}
+
+fn now() -> String {
+    timestamp(now_secs())
+}
+
+/// Formats seconds since the Unix epoch in ISO 8601 format, i.e. `YYYY-MM-DDThh:mm:ssZ`.
+fn timestamp(secs: u64) -> String {
+    let (year, month, day) = date::from_day_number((secs / 86_400) as i64);
+    let time = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Returns the name of the variant of a service, e.g. `AmazonPrime`.
+fn service_name(service: FilmAvailabilityService) -> String {
+    serde_json::to_value(service)
+        .ok()
+        .and_then(|value| value.as_str().map(String::from))
+        .unwrap_or_default()
+}
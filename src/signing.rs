@@ -0,0 +1,238 @@
+//! Signing of API requests.
+//!
+//! Each request is signed by appending the query parameters `apikey`, `nonce` and `timestamp` to
+//! its url, followed by the parameter `signature`. The signature is the lowercase hex encoded
+//! HMAC-SHA256 of
+//!
+//! ```text
+//! METHOD \0 URL \0 BODY
+//! ```
+//!
+//! keyed with the API secret, where `URL` is the url including all parameters except the
+//! signature. See http://api-docs.letterboxd.com/#signing.
+//!
+//! Besides signing requests on behalf of clients, e.g. in a proxy, a [`Signer`](struct.Signer.html)
+//! verifies the signatures of incoming requests. The nonce and clock of a signer can be replaced
+//! for deterministic tests.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use letterboxd::signing::{self, Signer};
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! let key_pair = letterboxd::ApiKeyPair::new("key".into(), "secret".into());
+//! let url = "https://api.letterboxd.com/api/v0/film/2a9q".parse().unwrap();
+//!
+//! let signed = signing::sign("GET", url, b"", &key_pair);
+//! assert!(signing::verify("GET", &signed, b"", &key_pair).is_ok());
+//!
+//! let signer = Signer::new()
+//!     .with_nonce(|| "nonce".to_string())
+//!     .with_clock(|| UNIX_EPOCH + Duration::from_secs(1_577_836_800));
+//! let url = "https://api.letterboxd.com/api/v0/film/2a9q".parse().unwrap();
+//! let signed = signer.sign("GET", url, b"", &key_pair);
+//! assert_eq!(
+//!     signed.query(),
+//!     Some(
+//!         "apikey=key&nonce=nonce&timestamp=1577836800&signature=\
+//!          6b6f38634905dfecbcb5bab442570d115d58048c7993bed464f2f055d69c9482"
+//!     )
+//! );
+//! ```
+
+use crate::client::ApiKeyPair;
+use crate::error::{Error, Result};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use url::Url;
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Query parameters added by signing, in the order in which they are added.
+pub const SIGNING_PARAMS: [&str; 4] = ["apikey", "nonce", "timestamp", "signature"];
+
+/// Default maximum age of a signed request accepted by [`Signer::verify`].
+///
+/// [`Signer::verify`]: struct.Signer.html#method.verify
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// Signs a request with a random nonce and the current time.
+///
+/// Returns the url with the signing parameters appended.
+pub fn sign<M: AsRef<str>>(method: M, url: Url, body: &[u8], key_pair: &ApiKeyPair) -> Url {
+    Signer::new().sign(method, url, body, key_pair)
+}
+
+/// Verifies the signature of a request against the current time.
+///
+/// See [`Signer::verify`](struct.Signer.html#method.verify).
+pub fn verify<M: AsRef<str>>(
+    method: M,
+    url: &Url,
+    body: &[u8],
+    key_pair: &ApiKeyPair,
+) -> Result<()> {
+    Signer::new().verify(method, url, body, key_pair)
+}
+
+/// Computes the signature of a request from its method, url and body.
+///
+/// The url must not contain the `signature` parameter.
+pub fn signature<M: AsRef<str>>(method: M, url: &Url, body: &[u8], api_secret: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(api_secret.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(method.as_ref().as_bytes());
+    mac.update(b"\0");
+    mac.update(url.as_str().as_bytes());
+    mac.update(b"\0");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Signs and verifies requests.
+///
+/// By default, nonces are random UUIDs and timestamps are taken from the system clock.
+pub struct Signer {
+    nonce: Box<dyn Fn() -> String + Send + Sync>,
+    clock: Box<dyn Fn() -> SystemTime + Send + Sync>,
+    max_age: Duration,
+}
+
+impl Signer {
+    /// Creates a new signer with random nonces and the system clock.
+    pub fn new() -> Self {
+        Self {
+            nonce: Box::new(|| uuid::Uuid::new_v4().to_string()),
+            clock: Box::new(SystemTime::now),
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+
+    /// Replaces the generator of nonces.
+    pub fn with_nonce<F>(mut self, nonce: F) -> Self
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        self.nonce = Box::new(nonce);
+        self
+    }
+
+    /// Replaces the clock providing timestamps.
+    pub fn with_clock<F>(mut self, clock: F) -> Self
+    where
+        F: Fn() -> SystemTime + Send + Sync + 'static,
+    {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Sets the maximum age of requests accepted by `verify`, which is also the allowed skew of
+    /// timestamps in the future.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn now(&self) -> u64 {
+        (self.clock)()
+            .duration_since(UNIX_EPOCH)
+            .expect("SystemTime::duration_since failed")
+            .as_secs()
+    }
+
+    /// Signs a request.
+    ///
+    /// Returns the url with the signing parameters appended.
+    pub fn sign<M: AsRef<str>>(
+        &self,
+        method: M,
+        mut url: Url,
+        body: &[u8],
+        key_pair: &ApiKeyPair,
+    ) -> Url {
+        url.query_pairs_mut()
+            .append_pair("apikey", &key_pair.api_key)
+            .append_pair("nonce", &(self.nonce)())
+            .append_pair("timestamp", &self.now().to_string());
+        let signature = signature(method, &url, body, &key_pair.api_secret);
+        url.query_pairs_mut().append_pair("signature", &signature);
+        url
+    }
+
+    /// Verifies the signature of a request.
+    ///
+    /// The `signature` must be the last parameter of the url, the `apikey` must match the key
+    /// pair, and the `timestamp` must not differ from the current time by more than the maximum
+    /// age, in either direction to allow for clock skew. Rejecting reused nonces is left to the
+    /// caller, since it requires keeping state.
+    pub fn verify<M: AsRef<str>>(
+        &self,
+        method: M,
+        url: &Url,
+        body: &[u8],
+        key_pair: &ApiKeyPair,
+    ) -> Result<()> {
+        let query = url.query().unwrap_or("");
+        let (unsigned_query, signature_param) = match query.rfind('&') {
+            Some(index) => (&query[..index], &query[index + 1..]),
+            None => ("", query),
+        };
+        let actual = signature_param
+            .strip_prefix("signature=")
+            .ok_or_else(|| Error::invalid_signature("missing signature"))?;
+
+        let mut unsigned = url.clone();
+        unsigned.set_query(Some(unsigned_query));
+        let param = |name: &str| {
+            unsigned
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        if param("apikey").as_ref() != Some(&key_pair.api_key) {
+            return Err(Error::invalid_signature("invalid api key"));
+        }
+        if param("nonce").is_none() {
+            return Err(Error::invalid_signature("missing nonce"));
+        }
+        let timestamp: u64 = param("timestamp")
+            .and_then(|timestamp| timestamp.parse().ok())
+            .ok_or_else(|| Error::invalid_signature("invalid timestamp"))?;
+        let now = self.now();
+        if now.saturating_sub(timestamp) > self.max_age.as_secs() {
+            return Err(Error::invalid_signature("expired timestamp"));
+        }
+        // the clocks may be skewed by at most the maximum age
+        if timestamp.saturating_sub(now) > self.max_age.as_secs() {
+            return Err(Error::invalid_signature("future timestamp"));
+        }
+
+        let expected = signature(method, &unsigned, body, &key_pair.api_secret);
+        if !constant_time_eq(actual.as_bytes(), expected.as_bytes()) {
+            return Err(Error::invalid_signature("signature mismatch"));
+        }
+        Ok(())
+    }
+}
+
+impl Default for Signer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Signer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Signer")
+            .field("max_age", &self.max_age)
+            .finish()
+    }
+}
+
+/// Compares two byte strings in time independent of their contents.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,296 @@
+//! Year-in-review statistics computed from diary entries.
+//!
+//! `MemberStatistics::years_in_review` is only available for paying members. A
+//! [`YearInReview`](struct.YearInReview.html) is computed locally from the diary entries of a
+//! member in a year, together with the details of the logged films, and is therefore available
+//! for any account.
+//!
+//! Genres, directors and actors are counted once per film, while the runtime includes every
+//! viewing, i.e. rewatches as well.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::stats::YearInReview;
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let client = letterboxd::Client::new(api_key_pair);
+//!
+//!     let review = YearInReview::fetch(&client, "2bbs", 2019).await?;
+//!     println!(
+//!         "{} films in {} entries, {} hours",
+//!         review.films,
+//!         review.entries,
+//!         review.run_time / 60
+//!     );
+//!     for genre in &review.top_genres {
+//!         println!("{}: {}", genre.name, genre.count);
+//!     }
+//!     Ok::<_, letterboxd::Error>(())
+//! };
+//! ```
+
+use crate::client::Client;
+use crate::date;
+use crate::defs::{
+    ContributionType, Film, LogEntriesRequest, LogEntry, LogEntryRelationshipType, LogEntryStatus,
+    RatingsHistogramBar,
+};
+use crate::error::Result;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+use std::collections::{BTreeSet, HashMap};
+
+/// Page size used when fetching the diary entries.
+const PER_PAGE: usize = 100;
+/// Number of concurrent requests when fetching the films.
+const CONCURRENCY: usize = 8;
+/// Number of genres, directors and actors in the top lists.
+const TOP: usize = 10;
+/// Number of streaks in the list of longest streaks.
+const STREAKS: usize = 3;
+
+/// A genre or contributor with the number of films.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tally {
+    /// The LID of the genre or contributor.
+    pub id: String,
+    /// The name of the genre or contributor.
+    pub name: String,
+    /// The number of films.
+    pub count: usize,
+}
+
+/// Consecutive days with at least one diary entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Streak {
+    /// The first day in ISO 8601 format, i.e. YYYY-MM-DD.
+    pub start: String,
+    /// The last day in ISO 8601 format.
+    pub end: String,
+    /// The number of days.
+    pub days: usize,
+}
+
+/// Statistics of the diary entries of a member in a year.
+#[derive(Debug, Clone)]
+pub struct YearInReview {
+    /// The year.
+    pub year: u16,
+    /// The number of diary entries.
+    pub entries: usize,
+    /// The number of distinct films.
+    pub films: usize,
+    /// The number of diary entries by month, starting with January.
+    pub entries_per_month: [usize; 12],
+    /// The number of diary entries marked as rewatch.
+    pub rewatches: usize,
+    /// The distribution of the ratings of the diary entries, one bar per increment from 0.5
+    /// to 5.0.
+    pub ratings: Vec<RatingsHistogramBar>,
+    /// The most watched genres, at most 10.
+    pub top_genres: Vec<Tally>,
+    /// The most watched directors, at most 10.
+    pub top_directors: Vec<Tally>,
+    /// The most watched actors, at most 10.
+    pub top_actors: Vec<Tally>,
+    /// The total runtime of the diary entries in minutes.
+    pub run_time: u32,
+    /// The longest streaks of consecutive days, longest first, at most 3.
+    pub longest_streaks: Vec<Streak>,
+}
+
+impl YearInReview {
+    /// Fetches the diary entries of a member in a year and the details of their films, and
+    /// computes the statistics.
+    pub async fn fetch(client: &Client, member: &str, year: u16) -> Result<Self> {
+        let mut entries = Vec::new();
+        let mut req = LogEntriesRequest {
+            per_page: Some(PER_PAGE),
+            member: Some(member.to_string()),
+            member_relationship: Some(LogEntryRelationshipType::Owner),
+            year: Some(year),
+            where_logentry_status: vec![LogEntryStatus::HasDiaryDate],
+            ..Default::default()
+        };
+        loop {
+            let resp = client.log_entries(&req).await?;
+            entries.extend(resp.items);
+            match resp.next {
+                Some(next) => req.cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let ids: BTreeSet<&str> = entries
+            .iter()
+            .filter(|entry| in_year(entry, year))
+            .map(|entry| entry.film.id.as_str())
+            .collect();
+        let films: Vec<Film> = stream::iter(ids)
+            .map(|id| client.film(id))
+            .buffered(CONCURRENCY)
+            .try_collect()
+            .await?;
+        Ok(Self::new(year, &entries, &films))
+    }
+
+    /// Computes the statistics of the diary entries in a year.
+    ///
+    /// Entries without a diary date or with a date in another year are ignored. Films which are
+    /// not included in `films` are counted, but do not contribute to genres, contributors and
+    /// runtime.
+    pub fn new(year: u16, entries: &[LogEntry], films: &[Film]) -> Self {
+        let films_by_id: HashMap<&str, &Film> =
+            films.iter().map(|film| (film.id.as_str(), film)).collect();
+        let entries: Vec<&LogEntry> = entries.iter().filter(|e| in_year(e, year)).collect();
+
+        let mut entries_per_month = [0; 12];
+        let mut rewatches = 0;
+        let mut rating_counts = [0; 10];
+        let mut run_time = 0;
+        let mut days = BTreeSet::new();
+        let mut film_ids = BTreeSet::new();
+        for entry in &entries {
+            let diary_details = entry.diary_details.as_ref().expect("missing diary details");
+            let (_, month, day) = date::parse(&diary_details.diary_date).expect("invalid date");
+            entries_per_month[month as usize - 1] += 1;
+            days.insert(date::day_number(year as i64, month, day));
+            if diary_details.rewatch {
+                rewatches += 1;
+            }
+            if let Some(rating) = entry.rating {
+                let index = (rating * 2.0).round() as usize;
+                if (1..=10).contains(&index) {
+                    rating_counts[index - 1] += 1;
+                }
+            }
+            if let Some(film) = films_by_id.get(entry.film.id.as_str()) {
+                run_time += film.run_time as u32;
+            }
+            film_ids.insert(entry.film.id.as_str());
+        }
+
+        let max_count = rating_counts.iter().copied().max().unwrap_or(0);
+        let ratings = rating_counts
+            .iter()
+            .enumerate()
+            .map(|(index, &count)| RatingsHistogramBar {
+                rating: (index + 1) as f32 / 2.0,
+                normalized_weight: if max_count > 0 {
+                    count as f32 / max_count as f32
+                } else {
+                    0.0
+                },
+                count,
+            })
+            .collect();
+
+        let watched: Vec<&Film> = film_ids
+            .iter()
+            .filter_map(|id| films_by_id.get(id).copied())
+            .collect();
+        let top_genres = top(watched.iter().flat_map(|film| {
+            film.genres
+                .iter()
+                .map(|genre| (genre.id.as_str(), genre.name.as_str()))
+        }));
+        let contributors = |contribution_type: ContributionType| {
+            top(watched.iter().flat_map(move |film| {
+                film.contributions
+                    .iter()
+                    .filter(move |c| c.contribution_type.as_ref() == Some(&contribution_type))
+                    .flat_map(|c| c.contributors.iter())
+                    .map(|contributor| (contributor.id.as_str(), contributor.name.as_str()))
+            }))
+        };
+
+        Self {
+            year,
+            entries: entries.len(),
+            films: film_ids.len(),
+            entries_per_month,
+            rewatches,
+            ratings,
+            top_genres,
+            top_directors: contributors(ContributionType::Director),
+            top_actors: contributors(ContributionType::Actor),
+            run_time,
+            longest_streaks: longest_streaks(&days),
+        }
+    }
+
+    /// Returns the share of diary entries which are rewatches, between 0.0 and 1.0.
+    pub fn rewatch_ratio(&self) -> Option<f64> {
+        if self.entries > 0 {
+            Some(self.rewatches as f64 / self.entries as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the average rating of the rated diary entries.
+    pub fn average_rating(&self) -> Option<f32> {
+        let count: usize = self.ratings.iter().map(|bar| bar.count).sum();
+        let sum: f32 = self
+            .ratings
+            .iter()
+            .map(|bar| bar.rating * bar.count as f32)
+            .sum();
+        if count > 0 {
+            Some(sum / count as f32)
+        } else {
+            None
+        }
+    }
+}
+
+fn in_year(entry: &LogEntry, year: u16) -> bool {
+    entry
+        .diary_details
+        .as_ref()
+        .and_then(|details| date::parse(&details.diary_date))
+        .is_some_and(|(entry_year, _, _)| entry_year == year)
+}
+
+/// Returns the most frequent items by id and name, ordered by count and name.
+fn top<'a, I: Iterator<Item = (&'a str, &'a str)>>(items: I) -> Vec<Tally> {
+    let mut counts: HashMap<&str, (&str, usize)> = HashMap::new();
+    for (id, name) in items {
+        counts.entry(id).or_insert((name, 0)).1 += 1;
+    }
+    let mut tallies: Vec<Tally> = counts
+        .into_iter()
+        .map(|(id, (name, count))| Tally {
+            id: id.to_string(),
+            name: name.to_string(),
+            count,
+        })
+        .collect();
+    tallies.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    tallies.truncate(TOP);
+    tallies
+}
+
+fn longest_streaks(days: &BTreeSet<i64>) -> Vec<Streak> {
+    let mut streaks: Vec<(i64, i64)> = Vec::new();
+    for &day in days {
+        match streaks.last_mut() {
+            Some((_, end)) if *end + 1 == day => *end = day,
+            _ => streaks.push((day, day)),
+        }
+    }
+    // longest first, earlier first on ties
+    streaks.sort_by(|a, b| (b.1 - b.0).cmp(&(a.1 - a.0)).then(a.0.cmp(&b.0)));
+    streaks
+        .into_iter()
+        .take(STREAKS)
+        .map(|(start, end)| Streak {
+            start: date::format(start),
+            end: date::format(end),
+            days: (end - start + 1) as usize,
+        })
+        .collect()
+}
@@ -0,0 +1,192 @@
+//! Persistent storage of access tokens.
+//!
+//! A client created with [`Client::from_store`](../struct.Client.html#method.from_store) loads
+//! its token from a [`TokenStore`](trait.TokenStore.html), and saves tokens obtained by
+//! [`Client::login`](../struct.Client.html#method.login) or
+//! [`Client::refresh`](../struct.Client.html#method.refresh) back to it.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use letterboxd::token_store::FileTokenStore;
+//!
+//! let res = async {
+//!     let api_key_pair = letterboxd::ApiKeyPair::from_env().unwrap();
+//!     let store = FileTokenStore::new(FileTokenStore::default_path());
+//!     let mut client = letterboxd::Client::from_store(api_key_pair, store)?;
+//!     if !client.is_authenticated() {
+//!         // the token is saved, so the next client is authenticated
+//!         client.login("username", "password").await?;
+//!     }
+//!     Ok::<_, letterboxd::Error>(())
+//! };
+//! ```
+
+use crate::defs::AccessToken;
+use crate::error::Result;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Storage of an access token.
+pub trait TokenStore: Send + Sync {
+    /// Loads the stored token, if any.
+    fn load(&self) -> Result<Option<AccessToken>>;
+    /// Saves the token, replacing a stored token.
+    fn save(&self, token: &AccessToken) -> Result<()>;
+    /// Removes the stored token.
+    fn clear(&self) -> Result<()>;
+}
+
+/// Token store saving the token as JSON in a file.
+///
+/// On Unix, the file is only readable and writable by the current user (mode 0600).
+#[derive(Debug, Clone)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    /// Creates a new store saving the token in the given file.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the default path of the token file.
+    ///
+    /// This is `$XDG_CACHE_HOME/letterboxd/token.json` resp. `~/.cache/letterboxd/token.json`.
+    pub fn default_path() -> PathBuf {
+        let cache_dir = env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .unwrap_or_else(env::temp_dir);
+        cache_dir.join("letterboxd").join("token.json")
+    }
+
+    /// Returns the path of the token file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self) -> Result<Option<AccessToken>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, token: &AccessToken) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // the mode is only applied to new files
+            if self.path.exists() {
+                fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        let file = options.open(&self.path)?;
+        serde_json::to_writer(file, token)?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Read-only token store loading the token from an environment variable.
+///
+/// The variable contains either the token as JSON, or only the access token. Saving and
+/// clearing the token does nothing.
+#[derive(Debug, Clone)]
+pub struct EnvTokenStore {
+    var: String,
+}
+
+impl EnvTokenStore {
+    /// Default environment variable name containing the token.
+    pub const TOKEN_ENVVAR: &'static str = "LETTERBOXD_TOKEN";
+
+    /// Creates a new store loading the token from the given environment variable.
+    pub fn new<S: Into<String>>(var: S) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl Default for EnvTokenStore {
+    fn default() -> Self {
+        Self::new(Self::TOKEN_ENVVAR)
+    }
+}
+
+impl TokenStore for EnvTokenStore {
+    fn load(&self) -> Result<Option<AccessToken>> {
+        let value = match env::var(&self.var) {
+            Ok(value) => value,
+            Err(_) => return Ok(None),
+        };
+        if value.trim_start().starts_with('{') {
+            return Ok(Some(serde_json::from_str(&value)?));
+        }
+        Ok(Some(AccessToken {
+            access_token: value,
+            token_type: "bearer".to_string(),
+            ..Default::default()
+        }))
+    }
+
+    fn save(&self, _token: &AccessToken) -> Result<()> {
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Token store keeping the token in memory.
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl MemoryTokenStore {
+    /// Creates a new store containing the given token.
+    pub fn new(token: Option<AccessToken>) -> Self {
+        Self {
+            token: Mutex::new(token),
+        }
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn load(&self) -> Result<Option<AccessToken>> {
+        Ok(self.token.lock().expect("poisoned lock").clone())
+    }
+
+    fn save(&self, token: &AccessToken) -> Result<()> {
+        *self.token.lock().expect("poisoned lock") = Some(token.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        *self.token.lock().expect("poisoned lock") = None;
+        Ok(())
+    }
+}
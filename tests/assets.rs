@@ -0,0 +1,108 @@
+use letterboxd::assets::AssetCache;
+use letterboxd::{Image, ImageSize};
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const POSTER: &[u8] = b"\x89PNG poster";
+
+/// Serves the same image for all paths except `/missing.jpg` with the ETag `"v1"`.
+///
+/// Returns the base url and the counts of full and not modified responses.
+fn serve() -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let full = Arc::new(AtomicUsize::new(0));
+    let not_modified = Arc::new(AtomicUsize::new(0));
+    let (full_count, not_modified_count) = (full.clone(), not_modified.clone());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut if_none_match = None;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if line.to_ascii_lowercase().starts_with("if-none-match:") {
+                    if_none_match = Some(line[14..].trim().to_string());
+                }
+            }
+
+            let response: Vec<u8> = if request_line.contains("/missing.jpg") {
+                b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec()
+            } else if if_none_match.as_deref() == Some("\"v1\"") {
+                not_modified_count.fetch_add(1, Ordering::SeqCst);
+                b"HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_vec()
+            } else {
+                full_count.fetch_add(1, Ordering::SeqCst);
+                let mut response = format!(
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    POSTER.len()
+                )
+                .into_bytes();
+                response.extend_from_slice(POSTER);
+                response
+            };
+            stream.write_all(&response).unwrap();
+        }
+    });
+    (format!("http://{}", addr), full, not_modified)
+}
+
+fn size(base: &str, name: &str, width: usize) -> ImageSize {
+    ImageSize {
+        width,
+        height: width * 3 / 2,
+        url: format!("{}/{}", base, name),
+    }
+}
+
+#[test]
+fn download_and_revalidate() -> letterboxd::Result<()> {
+    let (base, full, not_modified) = serve();
+    let dir = std::env::temp_dir().join(format!("letterboxd-assets-{}", std::process::id()));
+    let cache = AssetCache::new(&dir)?.with_concurrency(2);
+
+    let poster = Image {
+        sizes: vec![
+            size(&base, "poster-230.jpg", 230),
+            size(&base, "poster-460.jpg?k=1", 460),
+        ],
+    };
+    assert!(cache.get_image(&poster, 230, 1.0).is_none());
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let paths = rt.block_on(cache.fetch_all(&poster.sizes));
+    let paths: Vec<_> = paths.into_iter().collect::<letterboxd::Result<_>>()?;
+    assert_eq!(full.load(Ordering::SeqCst), 2);
+    // the same content is stored once
+    assert_eq!(paths[0], paths[1]);
+    assert_eq!(paths[0].extension().unwrap(), "jpg");
+    assert_eq!(std::fs::read(&paths[0])?, POSTER);
+    assert_eq!(std::fs::read_dir(dir.join("objects"))?.count(), 1);
+
+    // cached images are revalidated
+    let path = rt.block_on(cache.fetch_image(&poster, 300, 1.0))?;
+    assert_eq!(path.as_ref(), Some(&paths[1]));
+    assert_eq!(full.load(Ordering::SeqCst), 2);
+    assert_eq!(not_modified.load(Ordering::SeqCst), 1);
+
+    // and available offline
+    assert_eq!(cache.get_image(&poster, 230, 1.0), Some(paths[0].clone()));
+
+    let err = rt
+        .block_on(cache.fetch(&size(&base, "missing.jpg", 70)))
+        .unwrap_err();
+    assert!(err.to_string().contains("404 Not Found"), "{}", err);
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use letterboxd::availability::{Offer, Snapshot};
+use letterboxd::{Country, FilmSummary};
+
+fn film(id: &str, name: &str, release_year: u16) -> FilmSummary {
+    serde_json::from_value(common::film(id, name, release_year)).unwrap()
+}
+
+#[cfg(feature = "mock")]
+fn offered_services(snapshot: &Snapshot) -> Vec<(&str, Vec<&str>)> {
+    snapshot
+        .films
+        .iter()
+        .map(|film| {
+            let services = film.offers.iter().map(|o| o.service.as_str()).collect();
+            (film.id.as_str(), services)
+        })
+        .collect()
+}
+
+#[test]
+fn newly_available() {
+    let stalker = film("1Kxk", "Stalker", 1979);
+    let parasite = film("hTha", "Parasite", 2019);
+
+    let mut previous = Snapshot::new(Some(Country::DEU));
+    previous.add(&stalker, "Netflix", Some("https://netflix.com/1"));
+    let mut snapshot = Snapshot::new(Some(Country::DEU));
+    snapshot.add(&stalker, "Netflix", Some("https://netflix.com/1"));
+    snapshot.add(&stalker, "MUBI", None);
+    snapshot.add(&stalker, "MUBI", None);
+    snapshot.add(&parasite, "Amazon Prime", None);
+
+    let report = snapshot.newly_available(&previous);
+    assert_eq!(report.country, Some(Country::DEU));
+    assert_eq!(report.films.len(), 2);
+    assert_eq!(
+        report.films[0].offers,
+        [Offer {
+            service: "MUBI".to_string(),
+            url: None
+        }]
+    );
+    assert_eq!(
+        report.films[1].link.as_deref(),
+        Some("https://letterboxd.com/film/hTha/")
+    );
+    assert_eq!(
+        report.to_string(),
+        "Stalker (1979): MUBI\nParasite (2019): Amazon Prime\n"
+    );
+
+    assert!(snapshot.newly_available(&snapshot).is_empty());
+    // offers which are no longer available are not reported
+    assert!(previous.newly_available(&snapshot).is_empty());
+
+    // offers of another country are not comparable
+    let mut other = Snapshot::new(Some(Country::USA));
+    other.add(&parasite, "Netflix", None);
+    assert!(snapshot.newly_available(&other).is_empty());
+    // all offers are new compared to an empty snapshot, e.g. of a missing file
+    assert_eq!(
+        snapshot.newly_available(&Snapshot::default()).films.len(),
+        2
+    );
+}
+
+#[test]
+fn save_and_load() -> letterboxd::Result<()> {
+    let path = std::env::temp_dir().join(format!(
+        "letterboxd-availability-{}.json",
+        std::process::id()
+    ));
+    assert_eq!(Snapshot::load(&path)?, Snapshot::default());
+
+    let mut snapshot = Snapshot::new(Some(Country::GBR));
+    snapshot.add(&film("2a9q", "Fight Club", 1999), "Netflix", None);
+    snapshot.save(&path)?;
+    let loaded = Snapshot::load(&path)?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(loaded, snapshot);
+    Ok(())
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn fetch() -> letterboxd::Result<()> {
+    use letterboxd::mock::{Dataset, MockServer};
+    use letterboxd::{ApiKeyPair, FilmAvailabilityService};
+
+    let mut dataset = Dataset::seeded();
+    dataset
+        .add_availability("1Kxk", FilmAvailabilityService::Netflix, Country::DEU)
+        .add_availability("1Kxk", FilmAvailabilityService::AmazonPrime, Country::USA)
+        .add_availability("hEQi", FilmAvailabilityService::AmazonPrime, Country::DEU)
+        // not in the watchlist
+        .add_availability("hTha", FilmAvailabilityService::Netflix, Country::DEU);
+    let api_key_pair = ApiKeyPair::new("key".to_string(), "secret".to_string());
+    let server = MockServer::start(api_key_pair, dataset)?;
+    let client = server.client();
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+
+    let snapshot = rt.block_on(Snapshot::fetch(&client, "m1", Country::DEU))?;
+    let films = offered_services(&snapshot);
+    assert_eq!(
+        films,
+        [("1Kxk", vec!["Netflix"]), ("hEQi", vec!["AmazonPrime"])]
+    );
+    assert_eq!(
+        snapshot.films[0].offers[0].url.as_deref(),
+        Some("https://netflix.example.com/1Kxk")
+    );
+
+    let services = rt.block_on(client.film_services())?.items;
+    let snapshot = rt.block_on(Snapshot::fetch_by_service(&client, "m1", &services))?;
+    assert_eq!(snapshot.country, None);
+    let films = offered_services(&snapshot);
+    assert_eq!(
+        films,
+        [
+            ("1Kxk", vec!["AmazonPrime", "Netflix"]),
+            ("hEQi", vec!["AmazonPrime"])
+        ]
+    );
+    Ok(())
+}
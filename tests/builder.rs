@@ -0,0 +1,122 @@
+use letterboxd::middleware::{Middleware, Request, Response};
+use letterboxd::{
+    ApiKeyPair, Client, FilmRelationshipType, FilmsRequest, IncludeFriends, Kind,
+    ListEntriesRequest, ListMemberRelationship, ListsRequest,
+};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn message(res: letterboxd::Result<impl std::fmt::Debug>) -> String {
+    match res.unwrap_err().kind() {
+        Kind::InvalidRequest(message) => message.clone(),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn films_request() {
+    let request = FilmsRequest::builder()
+        .per_page(100)
+        .decade(1990)
+        .member("11Ht")
+        .member_relationship(FilmRelationshipType::InWatchlist)
+        .include_friends(IncludeFriends::All)
+        .tag_code("noir")
+        .tagger("11Ht")
+        .include_tagger_friends(IncludeFriends::Only)
+        .build()
+        .unwrap();
+    assert_eq!(request.per_page, Some(100));
+    assert_eq!(request.tagger.as_deref(), Some("11Ht"));
+
+    assert_eq!(
+        message(FilmsRequest::builder().per_page(101).build()),
+        "per_page must be between 1 and 100, got 101"
+    );
+    assert_eq!(
+        message(FilmsRequest::builder().decade(1994).build()),
+        "decade must be the starting year of a decade, got 1994"
+    );
+    assert_eq!(
+        message(
+            FilmsRequest::builder()
+                .member_relationship(FilmRelationshipType::Watched)
+                .build()
+        ),
+        "member_relationship must be used in conjunction with member"
+    );
+    assert_eq!(
+        message(
+            FilmsRequest::builder()
+                .tag_code("noir")
+                .include_tagger_friends(IncludeFriends::All)
+                .build()
+        ),
+        "include_tagger_friends must be used in conjunction with tagger"
+    );
+}
+
+#[test]
+fn list_requests() {
+    assert!(ListEntriesRequest::builder()
+        .decade(2010)
+        .member("11Ht")
+        .build()
+        .is_ok());
+    assert_eq!(
+        message(ListEntriesRequest::builder().per_page(0).build()),
+        "per_page must be between 1 and 100, got 0"
+    );
+    assert_eq!(
+        message(ListEntriesRequest::builder().tagger("11Ht").build()),
+        "tagger must be used in conjunction with tag_code"
+    );
+
+    assert!(ListsRequest::builder()
+        .member("11Ht")
+        .tag_code("horror")
+        .build()
+        .is_ok());
+    assert_eq!(
+        message(
+            ListsRequest::builder()
+                .member("11Ht")
+                .member_relationship(ListMemberRelationship::Liked)
+                .tag_code("horror")
+                .build()
+        ),
+        "tag_code must be used in conjunction with member and member_relationship Owner"
+    );
+}
+
+#[test]
+fn client_validates_before_sending() {
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+
+    impl Middleware for Counter {
+        fn before_sign(&self, _request: &mut Request) -> Option<Response> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Some(Response::ok(b"{\"items\":[]}".to_vec()))
+        }
+    }
+
+    let counter = Arc::new(Counter::default());
+    let mut client = Client::new(ApiKeyPair::new("key".to_string(), "secret".to_string()));
+    client.add_middleware(counter.clone());
+
+    let request = FilmsRequest {
+        decade: Some(1995),
+        ..Default::default()
+    };
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    assert_eq!(
+        message(rt.block_on(client.films(&request))),
+        "decade must be the starting year of a decade, got 1995"
+    );
+    assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+
+    rt.block_on(client.films(&FilmsRequest::default())).unwrap();
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,91 @@
+use letterboxd::cache::{
+    cache_key, CacheBackend, CacheEntry, DiskCache, MemoryCache, ResponseCache,
+};
+use url::Url;
+
+use std::time::Duration;
+
+fn entry(body: &str) -> CacheEntry {
+    CacheEntry::new(
+        body.as_bytes().to_vec(),
+        Some("\"v1\"".to_string()),
+        Duration::from_secs(60),
+    )
+}
+
+#[test]
+fn key_ignores_signing_params() {
+    let signed = Url::parse(
+        "https://api.letterboxd.com/api/v0/films?perPage=1&apikey=key&nonce=abc\
+         &timestamp=1577836800&signature=0123",
+    )
+    .unwrap();
+    let resigned = Url::parse(
+        "https://api.letterboxd.com/api/v0/films?perPage=1&apikey=key&nonce=def\
+         &timestamp=1577836801&signature=4567",
+    )
+    .unwrap();
+    assert_eq!(cache_key(&signed, None), cache_key(&resigned, None));
+    assert_eq!(
+        cache_key(&signed, None),
+        "https://api.letterboxd.com/api/v0/films?perPage=1"
+    );
+    assert_ne!(cache_key(&signed, None), cache_key(&signed, Some("token")));
+
+    let other_page = Url::parse("https://api.letterboxd.com/api/v0/films?perPage=2").unwrap();
+    assert_ne!(cache_key(&signed, None), cache_key(&other_page, None));
+}
+
+#[test]
+fn endpoint_ttls() {
+    let cache = ResponseCache::reference_data(MemoryCache::new(10))
+        .with_ttl("film/*/statistics", Duration::from_secs(60));
+    let day = Some(Duration::from_secs(24 * 60 * 60));
+    assert_eq!(cache.ttl("films/genres"), day);
+    assert_eq!(cache.ttl("members/pronouns"), day);
+    assert_eq!(cache.ttl("film/2a9q"), day);
+    assert_eq!(
+        cache.ttl("film/2a9q/statistics"),
+        Some(Duration::from_secs(60))
+    );
+    assert_eq!(cache.ttl("film/2a9q/me"), None);
+    assert_eq!(cache.ttl("films"), None);
+
+    let cache = cache.with_ttl("film/*", Duration::from_secs(1));
+    assert_eq!(cache.ttl("film/2a9q"), Some(Duration::from_secs(1)));
+}
+
+#[test]
+fn memory_cache_evicts_least_recently_used() {
+    let cache = MemoryCache::new(2);
+    cache.put("a", entry("a"));
+    cache.put("b", entry("b"));
+    assert!(cache.get("a").is_some()); // "b" is now least recently used
+    cache.put("c", entry("c"));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("b").is_none());
+    assert!(cache.get("a").is_some());
+    assert!(cache.get("c").is_some());
+
+    cache.remove("a");
+    assert!(cache.get("a").is_none());
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn disk_cache_round_trip() -> letterboxd::Result<()> {
+    let dir = std::env::temp_dir().join(format!("letterboxd-cache-{}", std::process::id()));
+    let cache = DiskCache::new(&dir)?;
+    assert!(cache.get("key").is_none());
+
+    let stored = entry("{\"items\":[]}\n");
+    cache.put("key", stored.clone());
+    let loaded = cache.get("key").expect("cached entry");
+    assert_eq!(loaded, stored);
+    assert!(loaded.is_fresh());
+
+    cache.remove("key");
+    assert!(cache.get("key").is_none());
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
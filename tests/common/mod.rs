@@ -0,0 +1,126 @@
+//! Fixtures shared by the offline tests.
+
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+
+pub fn member(id: &str) -> Value {
+    json!({
+        "id": id,
+        "username": id,
+        "displayName": id,
+        "shortName": id,
+        "pronoun": {
+            "id": "they",
+            "label": "They / their",
+            "subjectPronoun": "they",
+            "objectPronoun": "them",
+            "possessiveAdjective": "their",
+            "possessivePronoun": "theirs",
+            "reflexive": "themselves"
+        },
+        "avatar": { "sizes": [] },
+        "memberStatus": "Member"
+    })
+}
+
+pub fn film(id: &str, name: &str, release_year: u16) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "releaseYear": release_year,
+        "directors": [],
+        "relationships": [],
+        "links": [
+            {
+                "type": "letterboxd",
+                "id": id,
+                "url": format!("https://letterboxd.com/film/{}/", id)
+            }
+        ]
+    })
+}
+
+/// A `Film` with details, without genres, contributions and links.
+pub fn film_details(id: &str, name: &str, release_year: u16, run_time: u16) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "alternativeNames": [],
+        "releaseYear": release_year,
+        "tagline": "",
+        "description": "",
+        "runTime": run_time,
+        "poster": { "sizes": [] },
+        "backdrop": { "sizes": [] },
+        "backdropFocalPoint": 0.0,
+        "trailer": { "id": "", "url": "" },
+        "genres": [],
+        "contributions": [],
+        "links": []
+    })
+}
+
+pub fn film_relationship(member_id: &str, rating: Option<f32>) -> Value {
+    json!({
+        "member": member(member_id),
+        "relationship": {
+            "watched": true,
+            "liked": false,
+            "favorited": false,
+            "inWatchlist": false,
+            "rating": rating,
+            "reviews": [],
+            "diaryEntries": []
+        }
+    })
+}
+
+pub fn log_entry(id: &str, film: Value, diary_date: Option<&str>, review: Option<&str>) -> Value {
+    json!({
+        "id": id,
+        "name": "log entry",
+        "owner": member("m1"),
+        "film": film,
+        "diaryDetails": diary_date.map(|date| json!({ "diaryDate": date, "rewatch": false })),
+        "review": review.map(|lbml| json!({
+            "lbml": lbml,
+            "containsSpoilers": false,
+            "whenReviewed": "2019-12-31T20:00:00Z",
+            "text": lbml
+        })),
+        "tags2": [],
+        "whenCreated": "2020-01-01T10:00:00Z",
+        "whenUpdated": "2020-01-01T10:00:00Z",
+        "rating": 4.5,
+        "like": false,
+        "commentable": true,
+        "links": []
+    })
+}
+
+pub fn list(id: &str, name: &str) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "filmCount": 0,
+        "published": true,
+        "ranked": true,
+        "hasEntriesWithNotes": false,
+        "tags2": [],
+        "whenCreated": "2020-02-02T12:00:00Z",
+        "owner": member("m1"),
+        "previewEntries": [],
+        "links": []
+    })
+}
+
+pub fn list_entry(rank: usize, film: Value, notes: Option<&str>) -> Value {
+    json!({
+        "rank": rank,
+        "notesLbml": notes,
+        "containsSpoilers": false,
+        "film": film,
+        "notes": notes
+    })
+}
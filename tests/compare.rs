@@ -0,0 +1,107 @@
+mod common;
+
+use letterboxd::compare::Comparison;
+use letterboxd::FilmSummary;
+
+/// A film watched by a member with a rating and like.
+fn watched(id: &str, member: &str, rating: Option<f32>, liked: bool) -> FilmSummary {
+    let mut film = common::film(id, id, 2000);
+    let mut relationship = common::film_relationship(member, rating);
+    relationship["relationship"]["liked"] = liked.into();
+    film["relationships"] = serde_json::json!([relationship]);
+    serde_json::from_value(film).unwrap()
+}
+
+fn ids(films: &[FilmSummary]) -> Vec<&str> {
+    films.iter().map(|film| film.id.as_str()).collect()
+}
+
+#[test]
+fn compare_members() {
+    let a = vec![
+        watched("f1", "a", Some(5.0), true),
+        watched("f2", "a", Some(4.0), false),
+        watched("f3", "a", Some(1.0), false),
+        watched("f4", "a", Some(3.0), true),
+        watched("f5", "a", Some(4.5), false),
+        watched("f6", "a", None, true),
+        watched("f7", "a", Some(2.0), true),
+    ];
+    let b = vec![
+        watched("f3", "b", Some(2.0), false),
+        watched("f1", "b", Some(4.5), true),
+        watched("f2", "b", Some(3.0), true),
+        watched("f4", "b", Some(1.0), false),
+        watched("f8", "b", None, true),
+        watched("f9", "b", Some(3.5), false),
+    ];
+    let comparison = Comparison::new("a", &a, "b", &b);
+
+    assert_eq!(comparison.shared.len(), 4);
+    assert_eq!(comparison.shared[0].film.id, "f1");
+    assert_eq!(comparison.shared[0].rating_b, Some(4.5));
+    let correlation = comparison.rating_correlation.unwrap();
+    assert!((correlation - 0.7027).abs() < 1e-4, "{}", correlation);
+    // f1 is liked by both, f2 and f4 by one of them
+    assert_eq!(comparison.like_agreement, Some(1.0 / 3.0));
+
+    let disagreements: Vec<_> = comparison
+        .disagreements(2)
+        .iter()
+        .map(|shared| (shared.film.id.as_str(), shared.rating_difference().unwrap()))
+        .collect();
+    assert_eq!(disagreements, [("f4", 2.0), ("f2", 1.0)]);
+    assert_eq!(comparison.disagreements(10).len(), 4);
+
+    assert_eq!(ids(&comparison.recommendations_for_b), ["f5", "f6"]);
+    assert_eq!(ids(&comparison.recommendations_for_a), ["f8"]);
+}
+
+#[test]
+fn no_shared_ratings() {
+    let a = vec![watched("f1", "a", Some(5.0), false)];
+    let b = vec![
+        watched("f1", "b", None, false),
+        watched("f2", "b", Some(4.0), false),
+    ];
+    let comparison = Comparison::new("a", &a, "b", &b);
+    assert_eq!(comparison.shared.len(), 1);
+    assert_eq!(comparison.rating_correlation, None);
+    assert_eq!(comparison.like_agreement, None);
+    assert!(comparison.disagreements(5).is_empty());
+    assert!(comparison.recommendations_for_a.is_empty());
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn fetch_from_mock() -> letterboxd::Result<()> {
+    use letterboxd::mock::{Dataset, MockServer};
+
+    let mut dataset = Dataset::seeded();
+    dataset
+        .add_log_entry("e4", "m2", "2a9q", None, Some(4.0))
+        .add_log_entry("e5", "m2", "bPI", None, Some(3.0))
+        .add_log_entry("e6", "m1", "hTha", None, Some(5.0));
+    let api_key_pair = letterboxd::ApiKeyPair::new("key".to_string(), "secret".to_string());
+    let server = MockServer::start(api_key_pair, dataset)?;
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+
+    let comparison = rt.block_on(Comparison::fetch(&server.client(), "m1", "m2"))?;
+    let mut shared: Vec<_> = comparison
+        .shared
+        .iter()
+        .map(|shared| (shared.film.id.as_str(), shared.rating_a, shared.rating_b))
+        .collect();
+    shared.sort_by_key(|shared| shared.0);
+    assert_eq!(
+        shared,
+        [
+            ("2a9q", Some(4.5), Some(4.0)),
+            ("bPI", Some(5.0), Some(3.0)),
+            ("hTha", Some(5.0), Some(4.0)),
+        ]
+    );
+    assert!(comparison.rating_correlation.is_some());
+    assert_eq!(comparison.disagreements(1)[0].film.id, "bPI");
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use letterboxd::{Country, FilmAvailability};
+
+use std::collections::HashSet;
+
+#[test]
+fn codes_and_names() {
+    assert_eq!(Country::CYM.alpha2(), "KY");
+    assert_eq!(Country::CYM.alpha3(), "CYM");
+    assert_eq!(Country::CYM.name(), "Cayman Islands");
+    assert_eq!(Country::from_alpha2("us"), Some(Country::USA));
+    assert_eq!(Country::from_alpha3("Nzl"), Some(Country::NZL));
+    assert_eq!(Country::from_alpha2("XX"), None);
+
+    assert_eq!("UA".parse(), Ok(Country::UKR));
+    assert_eq!("twn".parse(), Ok(Country::TWN));
+    let err = "England".parse::<Country>().unwrap_err();
+    assert_eq!(err, "England".parse::<Country>().unwrap_err());
+    assert_eq!(err.to_string(), "unknown country code: \"England\"");
+}
+
+#[test]
+fn all_countries() {
+    assert_eq!(Country::iter().count(), Country::ALL.len());
+    assert!(Country::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+    let alpha2: HashSet<_> = Country::iter().map(Country::alpha2).collect();
+    assert_eq!(alpha2.len(), Country::ALL.len());
+    for country in Country::iter() {
+        assert_eq!(country.to_string().parse(), Ok(country));
+        assert_eq!(country.alpha2().parse(), Ok(country));
+        assert_eq!(
+            serde_json::to_value(country).unwrap(),
+            serde_json::json!(country.to_string())
+        );
+    }
+}
+
+#[test]
+fn filter_availability_by_locale() {
+    let availability: Vec<FilmAvailability> = serde_json::from_value(serde_json::json!([
+        { "service": "Netflix", "displayName": "Netflix", "country": "GBR", "id": "1", "url": "" },
+        { "service": "Netflix", "displayName": "Netflix", "country": "USA", "id": "2", "url": "" },
+    ]))
+    .unwrap();
+    let country = Country::from_alpha2("gb").unwrap();
+    let ids: Vec<_> = availability
+        .iter()
+        .filter(|item| item.country == country)
+        .map(|item| item.id.as_str())
+        .collect();
+    assert_eq!(ids, ["1"]);
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use letterboxd::crossref::ExternalId;
+use letterboxd::{Film, Kind};
+
+use serde_json::json;
+
+fn film() -> Film {
+    let mut film = common::film_details("2a9q", "Fight Club", 1999, 139);
+    film["links"] = json!([
+        { "type": "letterboxd", "id": "2a9q", "url": "https://letterboxd.com/film/fight-club/" },
+        { "type": "tmdb", "id": "550", "url": "https://www.themoviedb.org/movie/550/" },
+        { "type": "imdb", "id": "tt0137523", "url": "http://www.imdb.com/title/tt0137523/maindetails" }
+    ]);
+    serde_json::from_value(film).unwrap()
+}
+
+#[test]
+fn link_accessors() {
+    let film = film();
+    assert_eq!(film.imdb_id(), Some("tt0137523"));
+    assert_eq!(film.tmdb_id(), Some("550"));
+    assert_eq!(
+        film.letterboxd_url(),
+        Some("https://letterboxd.com/film/fight-club/")
+    );
+    assert_eq!(film.links[1].url(), "https://www.themoviedb.org/movie/550/");
+
+    let film = Film {
+        links: vec![],
+        ..film
+    };
+    assert_eq!(film.imdb_id(), None);
+}
+
+#[test]
+fn external_ids() {
+    let imdb = ExternalId::imdb("TT0137523").unwrap();
+    assert_eq!(imdb, ExternalId::Imdb("tt0137523".to_string()));
+    assert_eq!(imdb.to_string(), "imdb:tt0137523");
+    assert_eq!("tmdb:550".parse::<ExternalId>().unwrap().id(), "550");
+
+    for result in [
+        ExternalId::imdb("0137523"),
+        ExternalId::tmdb("tt550"),
+        "letterboxd:2a9q".parse(),
+    ] {
+        assert!(matches!(
+            result.unwrap_err().kind(),
+            Kind::InvalidRequest(_)
+        ));
+    }
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn resolve_with_cache() -> letterboxd::Result<()> {
+    use letterboxd::crossref::FilmResolver;
+    use letterboxd::middleware::{Middleware, Request, Response};
+    use letterboxd::mock::{Dataset, MockServer};
+    use letterboxd::ApiKeyPair;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+
+    impl Middleware for Counter {
+        fn on_response(&self, _request: &Request, _response: &mut Response) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let api_key_pair = ApiKeyPair::new("key".to_string(), "secret".to_string());
+    let server = MockServer::start(api_key_pair, Dataset::seeded())?;
+    let mut client = server.client();
+    let counter = Arc::new(Counter::default());
+    client.add_middleware(counter.clone());
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+
+    let resolver = FilmResolver::new();
+    let lid = rt.block_on(resolver.resolve_imdb(&client, "tt0137523"))?;
+    assert_eq!(lid.as_deref(), Some("2a9q"));
+    // the TMDB ID of the film was cached by the same request
+    let lid = rt.block_on(resolver.resolve_tmdb(&client, "550"))?;
+    assert_eq!(lid.as_deref(), Some("2a9q"));
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+    // unknown films are cached as well
+    assert_eq!(
+        rt.block_on(resolver.resolve_imdb(&client, "tt0000001"))?,
+        None
+    );
+    assert_eq!(
+        rt.block_on(resolver.resolve_imdb(&client, "tt0000001"))?,
+        None
+    );
+    assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+    assert_eq!(resolver.len(), 3);
+
+    let path =
+        std::env::temp_dir().join(format!("letterboxd-crossref-{}.json", std::process::id()));
+    resolver.save(&path)?;
+    let loaded = FilmResolver::load(&path)?;
+    std::fs::remove_file(&path)?;
+    let lid = rt.block_on(loaded.resolve_tmdb(&client, "550"))?;
+    assert_eq!(lid.as_deref(), Some("2a9q"));
+    assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+
+    let film = rt.block_on(client.film("hTha"))?;
+    loaded.record(&film);
+    let lid = rt.block_on(loaded.resolve_imdb(&client, "tt6751668"))?;
+    assert_eq!(lid.as_deref(), Some("hTha"));
+    assert_eq!(counter.0.load(Ordering::SeqCst), 3);
+
+    // forgotten unknown films are looked up again
+    assert_eq!(loaded.forget_missing(), 1);
+    assert_eq!(loaded.forget_missing(), 0);
+    assert_eq!(
+        rt.block_on(loaded.resolve_imdb(&client, "tt0000001"))?,
+        None
+    );
+    assert_eq!(counter.0.load(Ordering::SeqCst), 4);
+    assert_eq!(loaded.len(), 5);
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use letterboxd::endpoint::{
+    CreateList, CreateLogEntry, DeleteList, Endpoint, GetFilmRelationship, UpdateList,
+};
+use letterboxd::middleware::{Method, Middleware, Request, Response};
+use letterboxd::{
+    ApiKeyPair, Client, Kind, ListCreationRequest, ListUpdateRequest, LogEntryCreationRequest,
+    LogEntryCreationRequestReview,
+};
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+const RELATIONSHIP: &str = r#"{
+    "watched": true,
+    "liked": false,
+    "favorited": false,
+    "inWatchlist": false,
+    "rating": 4.5,
+    "reviews": [],
+    "diaryEntries": ["e1"]
+}"#;
+
+/// Answers all requests with a fixed body and records the requests.
+#[derive(Debug)]
+struct Stub {
+    body: &'static str,
+    requests: Mutex<Vec<Request>>,
+}
+
+impl Stub {
+    fn new(body: &'static str) -> Arc<Self> {
+        Arc::new(Self {
+            body,
+            requests: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+impl Middleware for Stub {
+    fn after_sign(&self, request: &mut Request) -> Option<Response> {
+        self.requests.lock().unwrap().push(request.clone());
+        Some(Response::ok(self.body.as_bytes().to_vec()))
+    }
+}
+
+fn client(stub: &Arc<Stub>) -> Client {
+    let mut client = Client::new(ApiKeyPair::new("key".to_string(), "secret".to_string()));
+    client.add_middleware(stub.clone());
+    client
+}
+
+#[test]
+fn film_relationship() -> letterboxd::Result<()> {
+    let stub = Stub::new(RELATIONSHIP);
+    let client = client(&stub);
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let relationship = rt.block_on(client.film_relationship("2a9q"))?;
+    assert_eq!(relationship.rating, Some(4.5));
+    assert_eq!(relationship.diary_entries, vec!["e1"]);
+
+    let relationship = rt.block_on(client.execute(&GetFilmRelationship { id: "2a9q" }))?;
+    assert!(relationship.watched);
+
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests[0].endpoint_path, "film/2a9q/me");
+    assert_eq!(requests[0].method, Method::GET);
+    Ok(())
+}
+
+#[test]
+fn empty_response() -> letterboxd::Result<()> {
+    let stub = Stub::new("");
+    let client = client(&stub);
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(client.execute(&DeleteList { id: "l1" }))?;
+    rt.block_on(client.delete_list("l1"))?;
+
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests[0].method, Method::DELETE);
+    assert_eq!(requests[1].endpoint_path, "list/l1");
+    Ok(())
+}
+
+#[test]
+fn custom_endpoint() -> letterboxd::Result<()> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ReportRequest {
+        reason: &'static str,
+    }
+
+    #[derive(Deserialize)]
+    struct Report {
+        status: String,
+    }
+
+    struct ReportComment<'a> {
+        review: &'a str,
+        comment: &'a str,
+        request: ReportRequest,
+    }
+
+    impl Endpoint for ReportComment<'_> {
+        const METHOD: Method = Method::POST;
+        const PATH: &'static str = "review/{review}/comment/{comment}/report";
+        type Query = ();
+        type Body = ReportRequest;
+        type Response = Report;
+
+        fn path_params(&self) -> Vec<&str> {
+            vec![self.review, self.comment]
+        }
+
+        fn body(&self) -> Option<&Self::Body> {
+            Some(&self.request)
+        }
+    }
+
+    let stub = Stub::new(r#"{"status":"received"}"#);
+    let client = client(&stub);
+    let endpoint = ReportComment {
+        review: "r1",
+        comment: "c2",
+        request: ReportRequest { reason: "Spam" },
+    };
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let report = rt.block_on(client.execute(&endpoint))?;
+    assert_eq!(report.status, "received");
+
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests[0].endpoint_path, "review/r1/comment/c2/report");
+    assert_eq!(requests[0].body, br#"{"reason":"Spam"}"#);
+    assert_eq!(requests[0].headers["content-type"], "application/json");
+    Ok(())
+}
+
+#[test]
+fn path_segments_are_encoded() -> letterboxd::Result<()> {
+    let stub = Stub::new(RELATIONSHIP);
+    let client = client(&stub);
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(client.film_relationship("a/b?c=d#e"))?;
+    rt.block_on(client.film_relationship("%2e%2e"))?;
+
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests[0].endpoint_path, "film/a%2Fb%3Fc=d%23e/me");
+    assert_eq!(requests[0].url.path(), "/api/v0/film/a%2Fb%3Fc=d%23e/me");
+    assert_eq!(requests[0].url.query_pairs().next().unwrap().0, "apikey");
+    assert_eq!(requests[1].url.path(), "/api/v0/film/%252e%252e/me");
+    Ok(())
+}
+
+#[test]
+fn dot_segments_are_rejected() {
+    let stub = Stub::new(RELATIONSHIP);
+    let client = client(&stub);
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    for id in &["..", ".", ""] {
+        let err = rt.block_on(client.film_relationship(id)).unwrap_err();
+        match err.kind() {
+            Kind::InvalidPathSegment(segment) => assert_eq!(segment, id),
+            kind => panic!("unexpected error: {:?}", kind),
+        }
+    }
+    assert!(stub.requests.lock().unwrap().is_empty());
+}
+
+#[test]
+fn lbml_is_validated() {
+    let text = "I <3 this film. 5 > 4";
+    let mut create_list = ListCreationRequest::new("Favorites".to_string());
+    create_list.description = Some(text.to_string());
+    let mut update_list = ListUpdateRequest::new("Favorites".to_string());
+    update_list.description = Some(text.to_string());
+    let mut create_log_entry = LogEntryCreationRequest::new("2a9q".to_string());
+    create_log_entry.review = Some(LogEntryCreationRequestReview {
+        text: text.to_string(),
+        contains_spoilers: false,
+        share: Vec::new(),
+    });
+
+    assert!(CreateList {
+        request: &create_list
+    }
+    .validate()
+    .is_ok());
+    assert!(UpdateList {
+        id: "l1",
+        request: &update_list
+    }
+    .validate()
+    .is_ok());
+    assert!(CreateLogEntry {
+        request: &create_log_entry
+    }
+    .validate()
+    .is_ok());
+
+    create_list.description = Some("<p>No paragraphs</p>".to_string());
+    let err = CreateList {
+        request: &create_list,
+    }
+    .validate()
+    .unwrap_err();
+    assert!(matches!(err.kind(), Kind::InvalidLbml(_)));
+}
+
+#[test]
+fn path_params_must_match_placeholders() {
+    struct FilmRelationship(Vec<&'static str>);
+
+    impl Endpoint for FilmRelationship {
+        const METHOD: Method = Method::GET;
+        const PATH: &'static str = "film/{id}/me";
+        type Query = ();
+        type Body = ();
+        type Response = letterboxd::FilmRelationship;
+
+        fn path_params(&self) -> Vec<&str> {
+            self.0.clone()
+        }
+    }
+
+    let stub = Stub::new(RELATIONSHIP);
+    let client = client(&stub);
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let err = rt
+        .block_on(client.execute(&FilmRelationship(vec![])))
+        .unwrap_err();
+    match err.kind() {
+        Kind::InvalidRequest(message) => {
+            assert_eq!(message, "missing path parameter {id} of film/{id}/me")
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+    let err = rt
+        .block_on(client.execute(&FilmRelationship(vec!["2a9q", "extra"])))
+        .unwrap_err();
+    match err.kind() {
+        Kind::InvalidRequest(message) => {
+            assert_eq!(message, "too many path parameters for film/{id}/me")
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+    assert!(stub.requests.lock().unwrap().is_empty());
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use letterboxd::{export, FilmSummary, List, ListEntry, LogEntry};
+
+fn to_string(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).expect("valid utf-8")
+}
+
+#[test]
+fn diary_and_reviews() -> letterboxd::Result<()> {
+    let entries: Vec<LogEntry> = serde_json::from_value(serde_json::json!([
+        common::log_entry(
+            "e1",
+            common::film("2a9q", "Fight Club", 1999),
+            Some("2020-01-01"),
+            None
+        ),
+        common::log_entry(
+            "e2",
+            common::film("bPI", "Melancholia", 2011),
+            None,
+            Some("Bleak, <i>beautiful</i>.")
+        ),
+    ]))?;
+
+    let mut diary = Vec::new();
+    export::write_diary(&mut diary, &entries)?;
+    assert_eq!(
+        to_string(diary),
+        "Date,Name,Year,Letterboxd URI,Rating,Rewatch,Tags,Watched Date\n\
+         2020-01-01,Fight Club,1999,https://boxd.it/e1,4.5,,,2020-01-01\n"
+    );
+
+    let mut reviews = Vec::new();
+    export::write_reviews(&mut reviews, &entries)?;
+    assert_eq!(
+        to_string(reviews),
+        "Date,Name,Year,Letterboxd URI,Rating,Rewatch,Review,Tags,Watched Date\n\
+         2020-01-01,Melancholia,2011,https://boxd.it/e2,4.5,,\"Bleak, <i>beautiful</i>.\",,\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn ratings_and_watched() -> letterboxd::Result<()> {
+    let mut rated = common::film("2a9q", "Fight Club", 1999);
+    rated["relationships"] = serde_json::json!([
+        common::film_relationship("other", Some(1.0)),
+        common::film_relationship("m1", Some(4.0)),
+    ]);
+    let films: Vec<FilmSummary> = serde_json::from_value(serde_json::json!([
+        rated,
+        common::film("bPI", "Melancholia", 2011),
+    ]))?;
+
+    let mut ratings = Vec::new();
+    export::write_ratings(&mut ratings, "m1", &films)?;
+    assert_eq!(
+        to_string(ratings),
+        "Date,Name,Year,Letterboxd URI,Rating\n\
+         ,Fight Club,1999,https://letterboxd.com/film/2a9q/,4\n"
+    );
+
+    let mut watched = Vec::new();
+    export::write_watched(&mut watched, &films)?;
+    assert_eq!(
+        to_string(watched),
+        "Date,Name,Year,Letterboxd URI\n\
+         ,Fight Club,1999,https://letterboxd.com/film/2a9q/\n\
+         ,Melancholia,2011,https://letterboxd.com/film/bPI/\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn list() -> letterboxd::Result<()> {
+    let list: List = serde_json::from_value(common::list("1fKte", "My Favorite Films!"))?;
+    let entries: Vec<ListEntry> = serde_json::from_value(serde_json::json!([
+        common::list_entry(
+            1,
+            common::film("2a9q", "Fight Club", 1999),
+            Some("first rule")
+        ),
+        common::list_entry(2, common::film("bPI", "Melancholia", 2011), None),
+    ]))?;
+
+    assert_eq!(export::list_file_name(&list), "my-favorite-films-1fKte.csv");
+    let other: List = serde_json::from_value(common::list("2aLmn", "My favorite films"))?;
+    assert_eq!(
+        export::list_file_name(&other),
+        "my-favorite-films-2aLmn.csv"
+    );
+    let unnamed: List = serde_json::from_value(common::list("3bXy", "!!!"))?;
+    assert_eq!(export::list_file_name(&unnamed), "3bXy.csv");
+
+    let mut csv = Vec::new();
+    export::write_list(&mut csv, &list, &entries)?;
+    assert_eq!(
+        to_string(csv),
+        "Letterboxd list export v7\n\
+         Date,Name,Tags,URL,Description\n\
+         2020-02-02,My Favorite Films!,,https://boxd.it/1fKte,\n\
+         \n\
+         Position,Name,Year,URL,Description\n\
+         1,Fight Club,1999,https://letterboxd.com/film/2a9q/,first rule\n\
+         2,Melancholia,2011,https://letterboxd.com/film/bPI/,\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use letterboxd::feed::{Entry, Feed, Format};
+use letterboxd::{ActivityResponse, List, ListEntriesResponse, LogEntriesResponse};
+
+use serde_json::json;
+
+fn log_entries() -> LogEntriesResponse {
+    let mut film = common::film("2a9q", "Fight Club", 1999);
+    film["poster"] = json!({ "sizes": [
+        { "width": 70, "height": 105, "url": "https://a.ltrbxd.com/2a9q-70.jpg" },
+        { "width": 230, "height": 345, "url": "https://a.ltrbxd.com/2a9q-230.png?v=1" }
+    ]});
+    let mut entry = common::log_entry("e1", film, Some("2019-12-31"), Some("<b>Tyler</b> & Marla"));
+    entry["whenCreated"] = json!("2020-01-01T09:00:00Z");
+    entry["diaryDetails"]["rewatch"] = json!(true);
+    entry["links"] = json!([{
+        "type": "letterboxd",
+        "id": "e1",
+        "url": "https://letterboxd.com/m1/film/fight-club/"
+    }]);
+    serde_json::from_value(json!({ "items": [entry] })).unwrap()
+}
+
+#[test]
+fn test_log_entries_atom() {
+    let mut feed = Feed::new("tag:letterboxd.com,2011:member/m1/diary", "Diary <m1>")
+        .with_link("https://letterboxd.com/m1/films/diary/");
+    feed.extend_log_entries(&log_entries());
+    let atom = feed.to_atom();
+
+    assert!(atom.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n"));
+    assert!(atom.contains("  <title>Diary &lt;m1&gt;</title>\n"));
+    assert!(atom.contains("  <updated>2020-01-01T10:00:00Z</updated>\n"));
+    assert!(atom.contains(
+        "  <entry>\n    <id>tag:letterboxd.com,2011:log-entry/e1</id>\n    \
+         <title>Fight Club (1999) ★★★★½</title>\n    \
+         <link rel=\"alternate\" href=\"https://letterboxd.com/m1/film/fight-club/\"/>\n    \
+         <link rel=\"enclosure\" href=\"https://a.ltrbxd.com/2a9q-230.png?v=1\" type=\"image/png\"/>\n    \
+         <published>2020-01-01T09:00:00Z</published>\n    \
+         <updated>2020-01-01T10:00:00Z</updated>\n    \
+         <author>\n      <name>m1</name>\n    </author>\n"
+    ));
+    assert!(atom.contains(
+        "<content type=\"html\">&lt;p&gt;Rewatched on 2019-12-31&lt;/p&gt;\
+         &lt;div&gt;&lt;b&gt;Tyler&lt;/b&gt; &amp;amp; Marla&lt;/div&gt;</content>"
+    ));
+    assert!(atom.ends_with("  </entry>\n</feed>\n"));
+}
+
+#[test]
+fn test_log_entries_rss() {
+    let mut feed = Feed::new("tag:letterboxd.com,2011:member/m1/diary", "Diary");
+    feed.extend_log_entries(&log_entries());
+    let mut rss = Vec::new();
+    feed.write(&mut rss, Format::Rss).unwrap();
+    let rss = String::from_utf8(rss).unwrap();
+
+    assert!(rss.contains("    <lastBuildDate>Wed, 01 Jan 2020 10:00:00 +0000</lastBuildDate>\n"));
+    assert!(rss.contains(
+        "      <guid isPermaLink=\"false\">tag:letterboxd.com,2011:log-entry/e1</guid>\n      \
+         <pubDate>Wed, 01 Jan 2020 09:00:00 +0000</pubDate>\n      \
+         <dc:creator>m1</dc:creator>\n"
+    ));
+    assert!(rss.contains(
+        "      <enclosure url=\"https://a.ltrbxd.com/2a9q-230.png?v=1\" length=\"0\" type=\"image/png\"/>\n"
+    ));
+}
+
+#[test]
+fn test_list_entries() {
+    let mut list = common::list("l1", "Favourites");
+    list["descriptionLbml"] = json!("My <i>favourite</i> films");
+    list["whenPublished"] = json!("2020-02-03T12:00:00Z");
+    list["links"] = json!([{
+        "type": "letterboxd",
+        "id": "l1",
+        "url": "https://letterboxd.com/m1/list/favourites/"
+    }]);
+    let list: List = serde_json::from_value(list).unwrap();
+    let mut entry = common::list_entry(1, common::film("hTha", "Parasite", 2019), Some("Stairs"));
+    entry["containsSpoilers"] = json!(true);
+    let page: ListEntriesResponse = serde_json::from_value(json!({ "items": [entry] })).unwrap();
+
+    let mut feed = Feed::for_list(&list);
+    feed.extend_list_entries(&list, &page);
+
+    assert_eq!(feed.id, "tag:letterboxd.com,2011:list/l1");
+    assert_eq!(feed.description.as_deref(), Some("My favourite films"));
+    assert_eq!(
+        feed.link.as_deref(),
+        Some("https://letterboxd.com/m1/list/favourites/")
+    );
+    let entry = &feed.entries[0];
+    assert_eq!(entry.id, "tag:letterboxd.com,2011:list/l1/film/hTha");
+    assert_eq!(entry.title, "1. Parasite (2019)");
+    assert_eq!(entry.published, "2020-02-03T12:00:00Z");
+    assert_eq!(
+        entry.content,
+        "<p><em>This text contains spoilers.</em></p><div>Stairs</div>"
+    );
+    assert_eq!(entry.enclosure, None);
+}
+
+#[test]
+fn test_activity() {
+    let film = common::film("hTha", "Parasite", 2019);
+    let page: ActivityResponse = serde_json::from_value(json!({ "items": [
+        {
+            "type": "FilmRatingActivity",
+            "member": common::member("m2"),
+            "whenCreated": "2020-03-01T20:00:00Z",
+            "film": film,
+            "rating": 4.0
+        },
+        {
+            "type": "FollowActivity",
+            "member": common::member("m2"),
+            "whenCreated": "2020-03-02T20:00:00Z",
+            "followed": common::member("m1")
+        }
+    ]}))
+    .unwrap();
+
+    let entries: Vec<Entry> = page.items.iter().map(Entry::from_activity).collect();
+    assert_eq!(
+        entries[0].id,
+        "tag:letterboxd.com,2011:member/m2/activity/film-rating/hTha/2020-03-01T20:00:00Z"
+    );
+    assert_eq!(entries[0].title, "m2 rated Parasite (2019) ★★★★");
+    assert_eq!(
+        entries[0].link.as_deref(),
+        Some("https://letterboxd.com/film/hTha/")
+    );
+    assert_eq!(entries[1].title, "m2 followed m1");
+    assert_eq!(entries[1].link, None);
+
+    let mut feed = Feed::new("tag:letterboxd.com,2011:member/m2/activity", "Activity");
+    feed.extend_activity(&page);
+    assert_eq!(feed.updated(), Some("2020-03-02T20:00:00Z"));
+
+    // the follow has neither content nor a link, so its title is its content
+    let atom = feed.to_atom();
+    assert_eq!(atom.matches("<content type=\"text\">").count(), 1);
+    assert!(atom.contains("    <content type=\"text\">m2 followed m1</content>\n  </entry>\n"));
+}
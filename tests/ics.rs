@@ -0,0 +1,83 @@
+mod common;
+
+use letterboxd::ics::IcsExport;
+use letterboxd::{Film, LogEntry};
+
+use serde_json::json;
+
+fn film(id: &str, run_time: u16) -> Film {
+    serde_json::from_value(common::film_details(id, id, 2000, run_time)).unwrap()
+}
+
+fn entry(id: &str, film: &str, name: &str, date: Option<&str>, rewatch: bool) -> LogEntry {
+    let mut entry = common::log_entry(id, common::film(film, name, 1999), date, None);
+    if date.is_some() {
+        entry["diaryDetails"]["rewatch"] = json!(rewatch);
+    }
+    serde_json::from_value(entry).unwrap()
+}
+
+#[test]
+fn test_all_day_events() {
+    let entries = vec![
+        entry("e1", "2a9q", "Fight Club", Some("2019-12-31"), true),
+        entry("e2", "hTha", "Fight Club", None, false),
+    ];
+    let ics = IcsExport::new().with_name("Diary; 2019").to_ics(&entries);
+    let lines: Vec<&str> = ics.split("\r\n").collect();
+    assert_eq!(
+        lines,
+        [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//letterboxd-rs//Diary//EN",
+            "CALSCALE:GREGORIAN",
+            "METHOD:PUBLISH",
+            "X-WR-CALNAME:Diary\\; 2019",
+            "BEGIN:VEVENT",
+            "UID:e1@letterboxd.com",
+            "DTSTAMP:20200101T100000Z",
+            "DTSTART;VALUE=DATE:20191231",
+            "DTEND;VALUE=DATE:20200101",
+            "SUMMARY:Fight Club (1999) ★★★★½",
+            "DESCRIPTION:Rewatch",
+            "URL:https://letterboxd.com/film/2a9q/",
+            "END:VEVENT",
+            "END:VCALENDAR",
+            "",
+        ]
+    );
+}
+
+#[test]
+fn test_timed_events() {
+    let entries = vec![
+        entry("e1", "2a9q", "Fight Club", Some("2020-02-28"), false),
+        entry("e2", "hTha", "Parasite", Some("2020-03-01"), false),
+    ];
+    let ics = IcsExport::new()
+        .with_start_time(22, 30)
+        .with_films(&[film("2a9q", 139)])
+        .to_ics(&entries);
+
+    assert!(ics.contains("DTSTART:20200228T223000\r\nDTEND:20200229T004900\r\n"));
+    assert!(ics.contains("DESCRIPTION:Runtime: 139 min\r\n"));
+    // the runtime of the second film is unknown
+    assert!(ics.contains("DTSTART;VALUE=DATE:20200301\r\nDTEND;VALUE=DATE:20200302\r\n"));
+}
+
+#[test]
+fn test_line_folding() {
+    let name = "Dr. Strangelove or: How I Learned to Stop Worrying and Love the Bomb, Ünterwegs";
+    let entries = vec![entry("e1", "2a9q", name, Some("2020-01-01"), false)];
+    let ics = IcsExport::new().to_ics(&entries);
+
+    for line in ics.split("\r\n") {
+        assert!(line.len() <= 75, "{:?} is too long", line);
+    }
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains(&format!(
+        "SUMMARY:{} (1999) ★★★★½\r\n",
+        name.replace(',', "\\,")
+    )));
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use letterboxd::image::CropRect;
+use letterboxd::{Film, Image, ImageSize};
+
+fn size(width: usize, height: usize) -> ImageSize {
+    ImageSize {
+        width,
+        height,
+        url: format!("https://a.ltrbxd.com/{}x{}.jpg", width, height),
+    }
+}
+
+#[test]
+fn size_selection() {
+    let poster = Image {
+        sizes: vec![
+            size(230, 345),
+            size(1000, 1500),
+            size(70, 105),
+            size(460, 690),
+        ],
+    };
+    assert_eq!(poster.smallest().unwrap().width, 70);
+    assert_eq!(poster.largest().unwrap().width, 1000);
+    assert_eq!(poster.best_for_width(50, 1.0).unwrap().width, 70);
+    assert_eq!(poster.best_for_width(231, 1.0).unwrap().width, 460);
+    assert_eq!(poster.best_for_width(150, 1.5).unwrap().width, 230);
+    assert_eq!(poster.best_for_width(800, 2.0).unwrap().width, 1000);
+
+    let empty = Image { sizes: vec![] };
+    assert!(empty.best_for_width(230, 1.0).is_none());
+    assert!(empty.largest().is_none());
+    assert_eq!(empty.to_srcset(), "");
+}
+
+#[test]
+fn srcset() {
+    let image = Image {
+        sizes: vec![size(460, 690), size(230, 345), size(230, 345)],
+    };
+    assert_eq!(
+        image.to_srcset(),
+        "https://a.ltrbxd.com/230x345.jpg 230w, https://a.ltrbxd.com/460x690.jpg 460w"
+    );
+}
+
+#[test]
+fn crop_rect() {
+    let backdrop = size(1920, 1080);
+
+    // shorter than 16:9, centered on the focal point
+    assert_eq!(
+        backdrop.crop_rect(1920.0 / 540.0, 0.5),
+        CropRect {
+            x: 0,
+            y: 270,
+            width: 1920,
+            height: 540
+        }
+    );
+    // the rectangle stays within the image
+    assert_eq!(backdrop.crop_rect(1920.0 / 540.0, 0.1).y, 0);
+    assert_eq!(backdrop.crop_rect(1920.0 / 540.0, 1.0).y, 540);
+    // narrower than 16:9, centered horizontally
+    assert_eq!(
+        backdrop.crop_rect(1.0, 0.2),
+        CropRect {
+            x: 420,
+            y: 0,
+            width: 1080,
+            height: 1080
+        }
+    );
+}
+
+#[test]
+fn film_backdrop_crop_rect() {
+    let mut film = common::film_details("2a9q", "Fight Club", 1999, 139);
+    film["backdrop"] = serde_json::json!({
+        "sizes": [{ "width": 1200, "height": 675, "url": "backdrop.jpg" }]
+    });
+    film["backdropFocalPoint"] = serde_json::json!(0.25);
+    let film: Film = serde_json::from_value(film).unwrap();
+    let size = film.backdrop.largest().unwrap();
+    assert_eq!(
+        film.backdrop_crop_rect(size, 4.0),
+        CropRect {
+            x: 0,
+            y: 19,
+            width: 1200,
+            height: 300
+        }
+    );
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use letterboxd::export::{self, Export};
+use letterboxd::import::{ImportSet, Plan, Row};
+use letterboxd::{FilmSummary, LogEntry};
+
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+
+const DIARY: &str = "Date,Name,Year,Letterboxd URI,Rating,Rewatch,Tags,Watched Date
+2020-01-01,Fight Club,1999,https://boxd.it/e1,4.5,Yes,\"cult, rewatch\",2020-01-01
+2020-01-02,Melancholia,2011,https://boxd.it/e2,,,,2020-01-02
+";
+const REVIEWS: &str = "Date,Name,Year,Letterboxd URI,Rating,Rewatch,Review,Tags,Watched Date
+2020-01-02,Melancholia,2011,https://boxd.it/e2,,,Bleak.,,2020-01-02
+2020-01-03,Unknown Film,2000,https://boxd.it/e3,,,Who?,,
+";
+const WATCHLIST: &str = "Date,Name,Year,Letterboxd URI
+2020-01-04,Stalker,1979,https://boxd.it/2b5o
+";
+
+fn resolve(row: &Row) -> Option<String> {
+    match row.name.as_str() {
+        "Fight Club" => Some(String::from("2a9q")),
+        "Melancholia" => Some(String::from("bPI")),
+        "Stalker" => Some(String::from("2b5o")),
+        _ => None,
+    }
+}
+
+#[test]
+fn read_zip() -> letterboxd::Result<()> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default();
+    zip.start_file("diary.csv", options)?;
+    zip.write_all(DIARY.as_bytes())?;
+    zip.start_file("watchlist.csv", options)?;
+    zip.write_all(WATCHLIST.as_bytes())?;
+    let archive = zip.finish()?;
+
+    let import = ImportSet::from_zip(archive)?;
+    assert_eq!(import.diary.len(), 2);
+    assert_eq!(import.watchlist.len(), 1);
+    assert!(import.reviews.is_empty());
+
+    let row = &import.diary[0];
+    assert_eq!(row.name, "Fight Club");
+    assert_eq!(row.year, Some(1999));
+    assert_eq!(row.rating, Some(4.5));
+    assert!(row.is_rewatch());
+    assert_eq!(row.tags(), vec!["cult", "rewatch"]);
+    assert_eq!(row.watched_date.as_deref(), Some("2020-01-01"));
+    assert_eq!(import.diary[1].rating, None);
+    Ok(())
+}
+
+#[test]
+fn plan_for_empty_account() -> letterboxd::Result<()> {
+    let import = ImportSet {
+        diary: ImportSet::read_rows(DIARY.as_bytes())?,
+        reviews: ImportSet::read_rows(REVIEWS.as_bytes())?,
+        watchlist: ImportSet::read_rows(WATCHLIST.as_bytes())?,
+        ..Default::default()
+    };
+    let target = Export {
+        member: String::from("m1"),
+        ..Default::default()
+    };
+
+    let plan = Plan::new(&import, resolve, &target);
+    assert_eq!(
+        plan.to_string(),
+        "Relationship updates: 3
+  Fight Club (2a9q): watched
+  Stalker (2b5o): watchlist
+  Melancholia (bPI): watched
+Log entries: 2
+  Fight Club (2a9q) on 2020-01-01 (rewatch), rating 4.5
+  Melancholia (bPI) on 2020-01-02, with review
+Unmatched rows: 1
+  Unknown Film (2000)
+"
+    );
+    Ok(())
+}
+
+#[test]
+fn plan_for_matching_account_is_empty() -> letterboxd::Result<()> {
+    let mut watched_film = common::film("2a9q", "Fight Club", 1999);
+    watched_film["relationships"] = serde_json::json!([common::film_relationship("m1", Some(4.5))]);
+    let log_entries: Vec<LogEntry> =
+        serde_json::from_value(serde_json::json!([common::log_entry(
+            "e1",
+            watched_film.clone(),
+            Some("2020-01-01"),
+            None
+        )]))?;
+    let watched: Vec<FilmSummary> = serde_json::from_value(serde_json::json!([watched_film]))?;
+    let target = Export {
+        member: String::from("m1"),
+        log_entries,
+        watched,
+        ..Default::default()
+    };
+
+    // round trip through the export format
+    let mut diary = Vec::new();
+    export::write_diary(&mut diary, &target.log_entries)?;
+    let mut ratings = Vec::new();
+    export::write_ratings(&mut ratings, &target.member, &target.watched)?;
+    let import = ImportSet {
+        diary: ImportSet::read_rows(&diary[..])?,
+        ratings: ImportSet::read_rows(&ratings[..])?,
+        ..Default::default()
+    };
+
+    let plan = Plan::new(&import, resolve, &target);
+    assert!(plan.is_empty(), "{}", plan);
+    assert!(plan.unmatched.is_empty());
+    Ok(())
+}
+
+#[test]
+fn plan_attaches_each_review_once() -> letterboxd::Result<()> {
+    let diary = "Date,Name,Year,Letterboxd URI,Rating,Rewatch,Tags,Watched Date
+2020-01-01,Fight Club,1999,https://boxd.it/e1,,,,2020-01-01
+2020-01-01,Fight Club,1999,https://boxd.it/e2,,Yes,,2020-01-01
+";
+    let reviews = "Date,Name,Year,Letterboxd URI,Rating,Rewatch,Review,Tags,Watched Date
+2020-01-01,Fight Club,1999,https://boxd.it/e1,,,Twice in a day.,,2020-01-01
+2020-01-05,Stalker,1979,https://boxd.it/e4,,,The Zone.,,2020-01-05
+";
+    let import = ImportSet {
+        diary: ImportSet::read_rows(diary.as_bytes())?,
+        reviews: ImportSet::read_rows(reviews.as_bytes())?,
+        ..Default::default()
+    };
+    let target = Export {
+        member: String::from("m1"),
+        ..Default::default()
+    };
+
+    let plan = Plan::new(&import, resolve, &target);
+    let reviews: Vec<Option<&str>> = plan
+        .log_entries
+        .iter()
+        .map(|creation| creation.request.review.as_ref().map(|r| r.text.as_str()))
+        .collect();
+    // the dated review without diary row is not lost
+    assert_eq!(reviews, [Some("Twice in a day."), None, Some("The Zone.")]);
+    Ok(())
+}
+
+#[test]
+fn log_entry_uris_are_not_matched() -> letterboxd::Result<()> {
+    let import = ImportSet {
+        diary: ImportSet::read_rows(DIARY.as_bytes())?,
+        reviews: ImportSet::read_rows(REVIEWS.as_bytes())?,
+        watchlist: ImportSet::read_rows(WATCHLIST.as_bytes())?,
+        ..Default::default()
+    };
+    let target = Export {
+        member: String::from("m1"),
+        ..Default::default()
+    };
+
+    // the URIs of diary and review rows are the ones of their log entries
+    let uris = std::cell::RefCell::new(HashSet::new());
+    Plan::new(
+        &import,
+        |row| {
+            uris.borrow_mut().insert(row.letterboxd_uri.clone());
+            resolve(row)
+        },
+        &target,
+    );
+    let expected: HashSet<_> = vec![None, Some(String::from("https://boxd.it/2b5o"))]
+        .into_iter()
+        .collect();
+    assert_eq!(uris.into_inner(), expected);
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use letterboxd::lbml::{self, Node, ValidationError};
+use letterboxd::Kind;
+
+#[test]
+fn sanitize() {
+    assert_eq!(
+        lbml::sanitize("<p>Great <span class=\"x\">film</span>!</p><script>alert(1)</script>"),
+        "Great film!"
+    );
+    assert_eq!(
+        lbml::sanitize("<a href=\"javascript:alert(1)\">click</a> <a href=' JaVaScRiPt:x'>me</a>"),
+        "click me"
+    );
+    assert_eq!(
+        lbml::sanitize("<B>bold <i>and italic</B> tail"),
+        "<b>bold <i>and italic</i></b> tail"
+    );
+    assert_eq!(lbml::sanitize("<em>unclosed"), "<em>unclosed</em>");
+    assert_eq!(
+        lbml::sanitize("a < b &amp;&amp; c &gt; d"),
+        "a &lt; b &amp;&amp; c &gt; d"
+    );
+    assert_eq!(
+        lbml::sanitize("<a href=\"/film/fight-club/\" onclick=\"x\">link</a>"),
+        "<a href=\"/film/fight-club/\">link</a>"
+    );
+}
+
+#[test]
+fn parse() {
+    let doc = lbml::parse(
+        "<blockquote>Quote &quot;me&quot;</blockquote><br/><a href='mailto:a@b.c'>mail</a>",
+    );
+    assert_eq!(
+        doc.nodes,
+        vec![
+            Node::Blockquote(vec![Node::Text("Quote \"me\"".to_string())]),
+            Node::LineBreak,
+            Node::Link {
+                href: "mailto:a@b.c".to_string(),
+                children: vec![Node::Text("mail".to_string())],
+            },
+        ]
+    );
+    assert_eq!(doc.plain_text(), "Quote \"me\"\nmail");
+}
+
+#[test]
+fn render() {
+    let doc = lbml::parse(
+        "First line\nsecond <strong>*line*</strong>\n<blockquote>Quoted\nlines</blockquote>\
+         <a href=\"https://letterboxd.com\">https://letterboxd.com</a>",
+    );
+    assert_eq!(
+        doc.to_html(),
+        "First line<br>\nsecond <strong>*line*</strong><br>\n<blockquote>Quoted<br>\nlines</blockquote>\
+         <a href=\"https://letterboxd.com\" rel=\"nofollow\">https://letterboxd.com</a>"
+    );
+    assert_eq!(
+        doc.to_markdown(),
+        "First line\nsecond **\\*line\\***\n> Quoted\n> lines\n[https://letterboxd.com](https://letterboxd.com)"
+    );
+    assert_eq!(
+        doc.to_text(),
+        "First line\nsecond *line*\n> Quoted\n> lines\nhttps://letterboxd.com"
+    );
+}
+
+#[test]
+fn text_with_angle_brackets() {
+    let doc = lbml::parse("I <3 this film. 5 > 4");
+    assert_eq!(
+        doc.nodes,
+        vec![Node::Text("I <3 this film. 5 > 4".to_string())]
+    );
+    assert_eq!(doc.to_text(), "I <3 this film. 5 > 4");
+    assert_eq!(doc.to_html(), "I &lt;3 this film. 5 &gt; 4");
+    assert_eq!(
+        lbml::sanitize("I <3 this film. 5 > 4"),
+        "I &lt;3 this film. 5 &gt; 4"
+    );
+}
+
+fn validation_error(result: letterboxd::Result<impl std::fmt::Debug>) -> ValidationError {
+    match result.unwrap_err().kind() {
+        Kind::InvalidLbml(e) => e.clone(),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn validate() {
+    assert!(lbml::validate(
+        "<b>Bold</b> <i>and</i> <strong>strong</strong> <em>and</em><br/>\
+         <blockquote>quoted <a href=\"https://boxd.it/2a9q\">link</a></blockquote> a < b"
+    )
+    .is_ok());
+
+    let err = validation_error(lbml::validate("First line\nsecond <p>line</p>"));
+    assert_eq!(
+        err,
+        ValidationError {
+            line: 2,
+            column: 8,
+            message: "tag <p> is not allowed".to_string(),
+        }
+    );
+    let err = validation_error(lbml::validate("<b>ö <i>x</b></i>"));
+    assert_eq!((err.line, err.column), (1, 10));
+    assert_eq!(
+        err.message,
+        "closing tag </b> does not match <i> at line 1, column 6"
+    );
+    let err = validation_error(lbml::validate("<em>unclosed"));
+    assert_eq!(err.message, "tag <em> is not closed");
+    let err = validation_error(lbml::validate("x</em>"));
+    assert_eq!(err.message, "closing tag </em> has no opening tag");
+    let err = validation_error(lbml::validate("<b class=\"x\">bold</b>"));
+    assert_eq!(err.message, "attribute class of <b> is not allowed");
+    let err = validation_error(lbml::validate("<a href=\"javascript:x\">x</a>"));
+    assert_eq!(err.message, "link target \"javascript:x\" is not allowed");
+
+    let long = "x".repeat(lbml::MAX_LENGTH);
+    assert!(lbml::validate(&long).is_ok());
+    let err = validation_error(lbml::validate(&format!("{}y", long)));
+    assert_eq!((err.line, err.column), (1, lbml::MAX_LENGTH + 1));
+    assert_eq!(
+        err.message,
+        "text has 100001 characters, the maximum is 100000"
+    );
+}
+
+#[test]
+fn from_markdown() -> letterboxd::Result<()> {
+    assert_eq!(
+        lbml::from_markdown(
+            "# Fight Club #\n\nA **must** see, *really*.\nSecond _line_ with snake_case \
+             and `<code>`.\n\n> Quoted [link](https://boxd.it/2a9q \"title\")\n> \
+             > nested <https://letterboxd.com>\n\n```\nlet x = **1**;\n```\n"
+        )?,
+        "<strong>Fight Club</strong>\n\nA <strong>must</strong> see, <em>really</em>.\n\
+         Second <em>line</em> with snake_case and &lt;code&gt;.\n\n<blockquote>Quoted \
+         <a href=\"https://boxd.it/2a9q\">link</a>\n\n<blockquote>nested \
+         <a href=\"https://letterboxd.com\">https://letterboxd.com</a></blockquote>\
+         </blockquote>\n\nlet x = **1**;"
+    );
+    assert_eq!(
+        lbml::from_markdown("Don't say \"never\" & <3")?,
+        "Don't say \"never\" &amp; &lt;3"
+    );
+    assert_eq!(
+        lbml::from_markdown("2 * 3 \\* 4 and [no link] and <b>html</b>")?,
+        "2 * 3 * 4 and [no link] and &lt;b&gt;html&lt;/b&gt;"
+    );
+
+    let err = validation_error(lbml::from_markdown(
+        "Fine.\n\n> Click [here](javascript:alert(1)) now",
+    ));
+    assert_eq!(
+        err,
+        ValidationError {
+            line: 3,
+            column: 16,
+            message: "link target \"javascript:alert(1)\" is not allowed".to_string(),
+        }
+    );
+    let err = validation_error(lbml::from_markdown("# t [l](javascript:x) ##"));
+    assert_eq!((err.line, err.column), (1, 9));
+    assert_eq!(lbml::from_markdown("# ##\n\n> \n>\n\nText")?, "Text");
+    let err = validation_error(lbml::from_markdown(&"&".repeat(lbml::MAX_LENGTH)));
+    assert_eq!(
+        err.message,
+        "text has 500000 characters, the maximum is 100000"
+    );
+    Ok(())
+}
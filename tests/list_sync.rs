@@ -0,0 +1,161 @@
+mod common;
+
+use letterboxd::list_sync::{DesiredEntry, ListSync};
+use letterboxd::{ListEntry, ListUpdateRequest};
+
+use std::collections::HashSet;
+
+fn entries(films: &[(&str, Option<&str>)]) -> Vec<ListEntry> {
+    let entries = films
+        .iter()
+        .enumerate()
+        .map(|(index, &(id, notes))| {
+            common::list_entry(index + 1, common::film(id, id, 2000), notes)
+        })
+        .collect();
+    serde_json::from_value(serde_json::Value::Array(entries)).expect("valid list entries")
+}
+
+fn desired(films: &[&str]) -> Vec<DesiredEntry> {
+    films
+        .iter()
+        .map(|id| DesiredEntry::new(id.to_string()))
+        .collect()
+}
+
+/// Applies an update request to the films of a list in the same way as Letterboxd: the ranked
+/// entries take their ranks and the other entries keep their relative order.
+fn apply(current: &[ListEntry], request: &ListUpdateRequest) -> Vec<String> {
+    let mut ranked: Vec<_> = request
+        .entries
+        .iter()
+        .filter(|e| e.rank.is_some())
+        .collect();
+    ranked.sort_by_key(|entry| entry.rank);
+    let mut films: Vec<String> = current
+        .iter()
+        .map(|entry| entry.film.id.clone())
+        .filter(|film| !request.films_to_remove.contains(film))
+        .filter(|film| !ranked.iter().any(|entry| entry.film == *film))
+        .collect();
+    for entry in &request.entries {
+        if entry.rank.is_none() && !films.contains(&entry.film) {
+            films.push(entry.film.clone());
+        }
+    }
+    for entry in ranked {
+        let rank = entry.rank.expect("ranked entry");
+        films.insert((rank - 1).min(films.len()), entry.film.clone());
+    }
+    films
+}
+
+/// Asserts that no two entries of a request have the same rank.
+fn assert_unique_ranks(request: &ListUpdateRequest) {
+    let ranks: Vec<usize> = request.entries.iter().filter_map(|e| e.rank).collect();
+    let unique: HashSet<usize> = ranks.iter().copied().collect();
+    assert_eq!(unique.len(), ranks.len(), "duplicate ranks {:?}", ranks);
+}
+
+#[test]
+fn in_sync() {
+    let current = entries(&[("a", None), ("b", Some("note"))]);
+    let mut entries = desired(&["a", "b"]);
+    entries[1].notes = Some(String::from("note"));
+
+    let sync = ListSync::new(String::from("list"), entries);
+    assert!(sync.diff("name", &current).is_none());
+}
+
+#[test]
+fn minimal_moves() {
+    let current = entries(&[
+        ("d", None),
+        ("c", None),
+        ("a", None),
+        ("b", None),
+        ("x", None),
+    ]);
+    let sync = ListSync::new(String::from("list"), desired(&["a", "b", "n", "c", "d"]));
+
+    let request = sync.diff("name", &current).expect("request");
+    assert_eq!(request.name, "name");
+    assert_eq!(request.films_to_remove, vec!["x"]);
+    let films: Vec<&str> = request.entries.iter().map(|e| e.film.as_str()).collect();
+    assert_eq!(films, vec!["n", "c", "d"]);
+    assert_unique_ranks(&request);
+    assert_eq!(apply(&current, &request), vec!["a", "b", "n", "c", "d"]);
+}
+
+#[test]
+fn final_ranks() {
+    let current = entries(&[("c", None), ("b", None), ("a", None), ("d", None)]);
+    let sync = ListSync::new(String::from("list"), desired(&["a", "b", "c", "d"]));
+
+    let request = sync.diff("name", &current).expect("request");
+    assert_eq!(request.entries.len(), 2);
+    assert_unique_ranks(&request);
+    for entry in &request.entries {
+        let rank = entry.rank.expect("moved entry");
+        assert_eq!(["a", "b", "c", "d"][rank - 1], entry.film);
+    }
+    assert_eq!(apply(&current, &request), vec!["a", "b", "c", "d"]);
+}
+
+#[test]
+fn all_permutations() {
+    let films = ["a", "b", "c", "d", "e"];
+    let current = entries(&films.iter().map(|&f| (f, None)).collect::<Vec<_>>());
+    let mut order = films.to_vec();
+    // Heap's algorithm over all permutations of the desired order
+    let mut counters = vec![0; order.len()];
+    let check = |order: &[&str]| {
+        let sync = ListSync::new(String::from("list"), desired(order));
+        match sync.diff("name", &current) {
+            Some(request) => {
+                assert_unique_ranks(&request);
+                assert_eq!(apply(&current, &request), order);
+            }
+            None => assert_eq!(order, films),
+        }
+    };
+    check(&order);
+    let mut i = 0;
+    while i < order.len() {
+        if counters[i] < i {
+            order.swap(if i % 2 == 0 { 0 } else { counters[i] }, i);
+            check(&order);
+            counters[i] += 1;
+            i = 0;
+        } else {
+            counters[i] = 0;
+            i += 1;
+        }
+    }
+}
+
+#[test]
+fn notes_and_spoilers() {
+    let current = entries(&[("a", Some("old")), ("b", Some("keep"))]);
+    let mut entries = desired(&["a", "b", "c"]);
+    entries[0].notes = Some(String::from("new"));
+    entries[0].contains_spoilers = true;
+    entries[1].notes = Some(String::from("keep"));
+    entries[2].notes = Some(String::from("added"));
+
+    let sync = ListSync::new(String::from("list"), entries);
+    let request = sync.diff("name", &current).expect("request");
+    assert!(request.films_to_remove.is_empty());
+    assert_eq!(request.entries.len(), 2);
+
+    let added = &request.entries[0];
+    assert_eq!(added.film, "c");
+    assert_eq!(added.rank, Some(3));
+    assert_eq!(added.notes.as_deref(), Some("added"));
+
+    let updated = &request.entries[1];
+    assert_eq!(updated.film, "a");
+    assert_eq!(updated.rank, None);
+    assert_eq!(updated.notes.as_deref(), Some("new"));
+    assert_eq!(updated.contains_spoilers, Some(true));
+}
@@ -0,0 +1,246 @@
+use letterboxd::cache::{CacheBackend, CacheEntry, MemoryCache, ResponseCache};
+use letterboxd::middleware::{header, BoxFuture, Middleware, Next, Request, Response, StatusCode};
+use letterboxd::{ApiKeyPair, Client};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const GENRES: &str = r#"{"items":[{"id":"8G","name":"Horror"}]}"#;
+
+/// Answers all requests after signing and records the requests.
+#[derive(Debug, Default)]
+struct Stub {
+    requests: Mutex<Vec<Request>>,
+}
+
+impl Middleware for Stub {
+    fn after_sign(&self, request: &mut Request) -> Option<Response> {
+        self.requests.lock().unwrap().push(request.clone());
+        Some(Response::ok(GENRES.as_bytes().to_vec()))
+    }
+}
+
+/// Counts the calls of its hooks.
+#[derive(Debug, Default)]
+struct Counter {
+    before_sign: AtomicUsize,
+    after_sign: AtomicUsize,
+    on_response: AtomicUsize,
+}
+
+impl Middleware for Counter {
+    fn before_sign(&self, request: &mut Request) -> Option<Response> {
+        self.before_sign.fetch_add(1, Ordering::SeqCst);
+        request
+            .headers
+            .insert("x-test", header::HeaderValue::from_static("1"));
+        None
+    }
+
+    fn after_sign(&self, _request: &mut Request) -> Option<Response> {
+        self.after_sign.fetch_add(1, Ordering::SeqCst);
+        None
+    }
+
+    fn on_response(&self, _request: &Request, _response: &mut Response) {
+        self.on_response.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn client() -> Client {
+    Client::new(ApiKeyPair::new("key".to_string(), "secret".to_string()))
+}
+
+#[test]
+fn chain_order_and_short_circuit() -> letterboxd::Result<()> {
+    let counter = Arc::new(Counter::default());
+    let stub = Arc::new(Stub::default());
+    let mut client = client();
+    client.add_middleware(counter.clone());
+    client.add_middleware(stub.clone());
+    client.add_middleware(Arc::new(Counter::default())); // never called
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let genres = rt.block_on(client.film_genres())?;
+    assert_eq!(genres.items[0].name, "Horror");
+
+    assert_eq!(counter.before_sign.load(Ordering::SeqCst), 1);
+    assert_eq!(counter.after_sign.load(Ordering::SeqCst), 1);
+    assert_eq!(counter.on_response.load(Ordering::SeqCst), 1);
+
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].endpoint_path, "films/genres");
+    assert_eq!(requests[0].headers["x-test"], "1");
+    let params: Vec<String> = requests[0]
+        .url
+        .query_pairs()
+        .map(|(name, _)| name.into_owned())
+        .collect();
+    assert_eq!(params, vec!["apikey", "nonce", "timestamp", "signature"]);
+    Ok(())
+}
+
+#[test]
+fn error_response() {
+    struct NotFound;
+    impl Middleware for NotFound {
+        fn before_sign(&self, _request: &mut Request) -> Option<Response> {
+            Some(Response {
+                status: StatusCode::NOT_FOUND,
+                ..Response::ok(b"missing".to_vec())
+            })
+        }
+    }
+
+    let mut client = client();
+    client.add_middleware(NotFound);
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let err = rt.block_on(client.film("2a9q")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "https://api.letterboxd.com/api/v0/film/2a9q: Server Error: 404 Not Found, Response: missing"
+    );
+}
+
+#[test]
+fn retry_around_sign_and_send() -> letterboxd::Result<()> {
+    /// Fails the first request with `503 Service Unavailable`.
+    #[derive(Debug, Default)]
+    struct Unavailable {
+        failed: AtomicUsize,
+    }
+    impl Middleware for Unavailable {
+        fn after_sign(&self, _request: &mut Request) -> Option<Response> {
+            if self.failed.fetch_add(1, Ordering::SeqCst) > 0 {
+                return None;
+            }
+            Some(Response {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                ..Response::ok(Vec::new())
+            })
+        }
+    }
+
+    /// Retries requests which failed with a server error.
+    struct Retry;
+    impl Middleware for Retry {
+        fn handle<'a>(
+            &'a self,
+            request: Request,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, letterboxd::Result<Response>> {
+            Box::pin(async move {
+                let response = next.run(request.clone()).await?;
+                if response.status.is_server_error() {
+                    return next.run(request).await;
+                }
+                Ok(response)
+            })
+        }
+    }
+
+    let counter = Arc::new(Counter::default());
+    let stub = Arc::new(Stub::default());
+    let mut client = client();
+    client.add_middleware(counter.clone());
+    client.add_middleware(Retry);
+    client.add_middleware(Unavailable::default());
+    client.add_middleware(stub.clone());
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let genres = rt.block_on(client.film_genres())?;
+    assert_eq!(genres.items[0].name, "Horror");
+
+    // the request is signed and passes the hooks after signing for each attempt
+    assert_eq!(counter.before_sign.load(Ordering::SeqCst), 1);
+    assert_eq!(counter.after_sign.load(Ordering::SeqCst), 2);
+    assert_eq!(counter.on_response.load(Ordering::SeqCst), 2);
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].headers["x-test"], "1");
+    Ok(())
+}
+
+#[test]
+fn cache_comes_first() -> letterboxd::Result<()> {
+    let stub = Arc::new(Stub::default());
+    let mut client = client();
+    client.set_cache(Some(
+        ResponseCache::new(MemoryCache::new(10)).with_ttl("films/genres", Duration::from_secs(60)),
+    ));
+    client.add_middleware(stub.clone());
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(client.film_genres())?;
+    let genres = rt.block_on(client.film_genres())?;
+    assert_eq!(genres.items[0].name, "Horror");
+    assert_eq!(stub.requests.lock().unwrap().len(), 1);
+
+    // not cached
+    rt.block_on(client.film_services())?;
+    assert_eq!(stub.requests.lock().unwrap().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn cache_entry_evicted_before_revalidation() -> letterboxd::Result<()> {
+    /// Holds a single stale entry, which is evicted once it was read.
+    #[derive(Debug)]
+    struct Evicting {
+        entry: Mutex<Option<CacheEntry>>,
+    }
+    impl CacheBackend for Evicting {
+        fn get(&self, _key: &str) -> Option<CacheEntry> {
+            self.entry.lock().unwrap().take()
+        }
+        fn put(&self, _key: &str, _entry: CacheEntry) {}
+        fn remove(&self, _key: &str) {}
+    }
+
+    /// Answers conditional requests with `304 Not Modified`.
+    #[derive(Debug, Default)]
+    struct NotModified {
+        conditional: AtomicUsize,
+    }
+    impl Middleware for NotModified {
+        fn after_sign(&self, request: &mut Request) -> Option<Response> {
+            if !request.headers.contains_key(header::IF_NONE_MATCH) {
+                return None;
+            }
+            self.conditional.fetch_add(1, Ordering::SeqCst);
+            Some(Response {
+                status: StatusCode::NOT_MODIFIED,
+                ..Response::ok(Vec::new())
+            })
+        }
+    }
+
+    let stale = CacheEntry::new(
+        b"{}".to_vec(),
+        Some("\"v1\"".to_string()),
+        Duration::from_secs(0),
+    );
+    let backend = Evicting {
+        entry: Mutex::new(Some(stale)),
+    };
+    let not_modified = Arc::new(NotModified::default());
+    let stub = Arc::new(Stub::default());
+    let mut client = client();
+    client.set_cache(Some(
+        ResponseCache::new(backend).with_ttl("films/genres", Duration::from_secs(60)),
+    ));
+    client.add_middleware(not_modified.clone());
+    client.add_middleware(stub.clone());
+
+    // the request is sent again without `If-None-Match`
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let genres = rt.block_on(client.film_genres())?;
+    assert_eq!(genres.items[0].name, "Horror");
+    assert_eq!(not_modified.conditional.load(Ordering::SeqCst), 1);
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].headers.contains_key(header::IF_NONE_MATCH));
+    Ok(())
+}
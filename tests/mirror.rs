@@ -0,0 +1,186 @@
+#![cfg(feature = "mirror")]
+
+mod common;
+
+use letterboxd::mirror::{rusqlite, Mirror, MirrorUpdate, SyncReport, LOG_ENTRIES};
+use letterboxd::{FilmSummary, ListEntry, ListSummary, LogEntry};
+
+use std::collections::HashMap;
+
+fn log_entry(id: &str, film: &str, when_updated: &str) -> LogEntry {
+    let mut entry = common::log_entry(id, common::film(film, film, 2000), Some("2020-01-01"), None);
+    entry["whenUpdated"] = serde_json::json!(when_updated);
+    serde_json::from_value(entry).expect("valid log entry")
+}
+
+fn films(ids: &[&str]) -> Vec<FilmSummary> {
+    ids.iter()
+        .map(|id| serde_json::from_value(common::film(id, id, 2000)).expect("valid film"))
+        .collect()
+}
+
+fn update() -> MirrorUpdate {
+    let list: ListSummary =
+        serde_json::from_value(common::list("l1", "Favorites")).expect("valid list");
+    let entries: Vec<ListEntry> = serde_json::from_value(serde_json::json!([
+        common::list_entry(1, common::film("f2", "f2", 2000), None),
+        common::list_entry(2, common::film("f1", "f1", 2000), Some("Best.")),
+    ]))
+    .expect("valid list entries");
+    let mut list_entries = HashMap::new();
+    list_entries.insert("l1".to_string(), entries);
+
+    MirrorUpdate {
+        member: "m1".to_string(),
+        log_entries: vec![
+            log_entry("e2", "f2", "2020-03-01T10:00:00Z"),
+            log_entry("e1", "f1", "2020-02-01T10:00:00Z"),
+        ],
+        log_entry_ids: Some(vec!["e2".to_string(), "e1".to_string()]),
+        lists: vec![list],
+        list_entries,
+        watched: films(&["f1", "f2"]),
+        watchlist: films(&["f3"]),
+    }
+}
+
+#[test]
+fn apply_update() -> letterboxd::Result<()> {
+    let mut mirror = Mirror::open_in_memory()?;
+    let report = mirror.apply(&update())?;
+    assert_eq!(
+        report,
+        SyncReport {
+            log_entries: 2,
+            lists: 1,
+            watched: 2,
+            watchlist: 1,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        mirror.cursor("m1", LOG_ENTRIES)?.as_deref(),
+        Some("2020-03-01T10:00:00Z")
+    );
+
+    let conn = mirror.connection();
+    let mut stmt = conn.prepare(
+        "SELECT films.name, list_entries.notes_lbml FROM list_entries
+         JOIN films ON films.id = list_entries.film_id
+         JOIN log_entries ON log_entries.film_id = films.id
+         WHERE list_entries.list_id = 'l1' ORDER BY list_entries.position",
+    )?;
+    let rows: Vec<(String, Option<String>)> = stmt
+        .query_map(rusqlite::NO_PARAMS, |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    assert_eq!(
+        rows,
+        vec![
+            ("f2".to_string(), None),
+            ("f1".to_string(), Some("Best.".to_string()))
+        ]
+    );
+
+    let watchlist: Vec<String> = conn
+        .prepare(
+            "SELECT film_id FROM film_relationships
+             WHERE member_id = 'm1' AND in_watchlist ORDER BY film_id",
+        )?
+        .query_map(rusqlite::NO_PARAMS, |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    assert_eq!(watchlist, vec!["f3"]);
+
+    // the stored list is unchanged
+    assert!(mirror.changed_lists(&update().lists)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn incremental_and_full_update() -> letterboxd::Result<()> {
+    let mut mirror = Mirror::open_in_memory()?;
+    mirror.apply(&update())?;
+
+    // an update without the LIDs of all log entries does not delete any and keeps the list
+    // entries
+    let incremental = MirrorUpdate {
+        log_entries: vec![log_entry("e3", "f3", "2020-04-01T10:00:00Z")],
+        log_entry_ids: None,
+        list_entries: HashMap::new(),
+        watchlist: Vec::new(),
+        ..update()
+    };
+    let report = mirror.apply(&incremental)?;
+    assert_eq!(report.log_entries, 1);
+    assert_eq!(report.removed_log_entries, 0);
+    assert_eq!(report.lists, 0);
+    assert_eq!(
+        mirror.cursor("m1", LOG_ENTRIES)?.as_deref(),
+        Some("2020-04-01T10:00:00Z")
+    );
+    let count = |mirror: &Mirror, sql: &str| -> rusqlite::Result<i64> {
+        mirror
+            .connection()
+            .query_row(sql, rusqlite::NO_PARAMS, |row| row.get(0))
+    };
+    assert_eq!(count(&mirror, "SELECT count(*) FROM log_entries")?, 3);
+    assert_eq!(count(&mirror, "SELECT count(*) FROM list_entries")?, 2);
+    assert_eq!(
+        count(
+            &mirror,
+            "SELECT count(*) FROM film_relationships WHERE in_watchlist"
+        )?,
+        0
+    );
+
+    // a full update deletes log entries and lists which no longer exist
+    mirror.reset("m1")?;
+    assert_eq!(mirror.cursor("m1", LOG_ENTRIES)?, None);
+    let full = MirrorUpdate {
+        log_entries: vec![log_entry("e1", "f1", "2020-02-01T10:00:00Z")],
+        log_entry_ids: Some(vec!["e1".to_string()]),
+        lists: Vec::new(),
+        list_entries: HashMap::new(),
+        ..update()
+    };
+    let report = mirror.apply(&full)?;
+    assert_eq!(report.removed_log_entries, 2);
+    assert_eq!(report.removed_lists, 1);
+    assert_eq!(count(&mirror, "SELECT count(*) FROM log_entries")?, 1);
+    assert_eq!(count(&mirror, "SELECT count(*) FROM list_entries")?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn fetch_edited_log_entry() -> letterboxd::Result<()> {
+    use letterboxd::mock::{Dataset, MockServer};
+    use letterboxd::ApiKeyPair;
+
+    let api_key_pair = ApiKeyPair::new("key".to_string(), "secret".to_string());
+    let mut mirror = Mirror::open_in_memory()?;
+    let mut rt = tokio::runtime::Runtime::new()?;
+
+    let server = MockServer::start(api_key_pair.clone(), Dataset::seeded())?;
+    let report = rt.block_on(mirror.sync(&server.client(), "m1"))?;
+    assert_eq!(report.log_entries, 2);
+
+    // the oldest log entry of the member is edited and the other one is deleted
+    let mut dataset = server.dataset();
+    dataset
+        .rate_log_entry("e1", Some(2.0))
+        .remove_log_entry("e2");
+    let server = MockServer::start(api_key_pair, dataset)?;
+    let update = rt.block_on(mirror.fetch(&server.client(), "m1"))?;
+    let ids: Vec<&str> = update.log_entries.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, vec!["e1"]);
+
+    let report = mirror.apply(&update)?;
+    assert_eq!(report.removed_log_entries, 1);
+    let rating: Option<f64> = mirror.connection().query_row(
+        "SELECT rating FROM log_entries WHERE id = 'e1'",
+        rusqlite::NO_PARAMS,
+        |row| row.get(0),
+    )?;
+    assert_eq!(rating, Some(2.0));
+    Ok(())
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "mock")]
+
+use letterboxd::mock::{Dataset, MockServer};
+use letterboxd::{
+    ApiKeyPair, FilmRelationshipType, FilmsRequest, ListCreateEntry, ListCreationRequest,
+    ListEntriesRequest, ListUpdateEntry, ListUpdateRequest, LogEntriesRequest,
+    LogEntryCreationRequest,
+};
+
+fn server() -> MockServer {
+    let api_key_pair = ApiKeyPair::new("key".to_string(), "secret".to_string());
+    MockServer::start(api_key_pair, Dataset::seeded()).unwrap()
+}
+
+#[test]
+fn signature_is_verified() -> letterboxd::Result<()> {
+    let server = server();
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+
+    let genres = rt.block_on(server.client().film_genres())?;
+    assert_eq!(genres.items.len(), 2);
+
+    let mut client = letterboxd::Client::new(ApiKeyPair::new(
+        "key".to_string(),
+        "wrong secret".to_string(),
+    ));
+    client.set_api_base_url(server.url());
+    let err = rt.block_on(client.film_genres()).unwrap_err();
+    assert!(err.to_string().contains("401 Unauthorized"), "{}", err);
+    Ok(())
+}
+
+#[test]
+fn authentication_and_pagination() -> letterboxd::Result<()> {
+    let server = server();
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let mut client = server.client();
+
+    assert!(rt.block_on(client.login("alice", "wrong")).is_err());
+    rt.block_on(client.login("alice", "password"))?;
+    assert!(client.is_authenticated());
+
+    let mut request = FilmsRequest {
+        per_page: Some(2),
+        ..Default::default()
+    };
+    let mut names = Vec::new();
+    loop {
+        let resp = rt.block_on(client.films(&request))?;
+        assert!(resp.items.len() <= 2);
+        names.extend(resp.items.into_iter().map(|film| film.name));
+        match resp.next {
+            Some(next) => request.cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(names.len(), 5);
+    assert_eq!(names[0], "Fight Club");
+
+    // a cursor past the end is an empty page
+    request.cursor = Some(format!("start={}", u64::MAX));
+    let resp = rt.block_on(client.films(&request))?;
+    assert!(resp.items.is_empty());
+    assert!(resp.next.is_none());
+
+    let request = FilmsRequest {
+        member: Some("m1".to_string()),
+        member_relationship: Some(FilmRelationshipType::InWatchlist),
+        ..Default::default()
+    };
+    let watchlist = rt.block_on(client.films(&request))?;
+    let names: Vec<_> = watchlist
+        .items
+        .iter()
+        .map(|film| film.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Stalker", "Portrait of a Lady on Fire"]);
+
+    assert!(rt.block_on(client.film("unknown")).is_err());
+    Ok(())
+}
+
+#[test]
+fn list_mutations() -> letterboxd::Result<()> {
+    let server = server();
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let mut client = server.client();
+
+    // authentication is required
+    let mut request = ListCreationRequest::new("Seen in 2020".to_string());
+    request.ranked = true;
+    request.entries = vec![
+        ListCreateEntry::new("2a9q".to_string()),
+        ListCreateEntry::new("bPI".to_string()),
+    ];
+    assert!(rt.block_on(client.create_list(&request)).is_err());
+
+    rt.block_on(client.login("alice", "password"))?;
+    let created = rt.block_on(client.create_list(&request))?.data;
+    assert_eq!(created.film_count, 2);
+
+    let mut update = ListUpdateRequest::new("Seen in 2020".to_string());
+    update.films_to_remove = vec!["2a9q".to_string()];
+    let mut entry = ListUpdateEntry::new("hTha".to_string());
+    entry.rank = Some(1);
+    update.entries = vec![entry];
+    rt.block_on(client.update_list(&created.id, &update))?;
+
+    let entries = rt.block_on(client.list_entries(&created.id, &ListEntriesRequest::default()))?;
+    let films: Vec<_> = entries.items.iter().map(|e| e.film.id.as_str()).collect();
+    assert_eq!(films, vec!["hTha", "bPI"]);
+
+    // entries are placed at their ranks, which must be unique
+    let mut update = ListUpdateRequest::new("Seen in 2020".to_string());
+    update.entries = ["1Kxk", "bPI", "hTha"]
+        .iter()
+        .enumerate()
+        .map(|(index, film)| {
+            let mut entry = ListUpdateEntry::new(film.to_string());
+            entry.rank = Some(index + 1);
+            entry
+        })
+        .collect();
+    update.entries.remove(1);
+    rt.block_on(client.update_list(&created.id, &update))?;
+    let entries = rt.block_on(client.list_entries(&created.id, &ListEntriesRequest::default()))?;
+    let films: Vec<_> = entries.items.iter().map(|e| e.film.id.as_str()).collect();
+    assert_eq!(films, vec!["1Kxk", "bPI", "hTha"]);
+
+    update.entries[1].rank = Some(1);
+    let err = rt
+        .block_on(client.update_list(&created.id, &update))
+        .unwrap_err();
+    assert!(err.to_string().contains("DuplicateRank"), "{}", err);
+
+    // lists of other members can't be changed
+    let mut bob = server.client();
+    rt.block_on(bob.login("bob", "password"))?;
+    assert!(rt.block_on(bob.delete_list(&created.id)).is_err());
+
+    rt.block_on(client.delete_list(&created.id))?;
+    assert!(rt.block_on(client.list(&created.id)).is_err());
+    Ok(())
+}
+
+#[test]
+fn log_entry_creation() -> letterboxd::Result<()> {
+    let server = server();
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let mut client = server.client();
+    rt.block_on(client.login("bob", "password"))?;
+
+    let mut request = LogEntryCreationRequest::new("1Kxk".to_string());
+    request.rating = Some(3.5);
+    let entry = rt.block_on(client.create_log_entry(&request))?;
+    assert_eq!(entry.rating, Some(3.5));
+
+    let request = LogEntriesRequest {
+        member: Some("m2".to_string()),
+        ..Default::default()
+    };
+    let entries = rt.block_on(client.log_entries(&request))?;
+    let ids: Vec<_> = entries.items.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, vec![entry.id.as_str(), "e3"]);
+    Ok(())
+}
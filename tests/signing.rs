@@ -0,0 +1,107 @@
+use letterboxd::signing::{self, Signer};
+use letterboxd::ApiKeyPair;
+use url::Url;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+const TIMESTAMP: u64 = 1_577_836_800;
+
+fn key_pair() -> ApiKeyPair {
+    ApiKeyPair::new("key".to_string(), "secret".to_string())
+}
+
+fn signer(nonce: &'static str, now: u64) -> Signer {
+    Signer::new()
+        .with_nonce(move || nonce.to_string())
+        .with_clock(move || UNIX_EPOCH + Duration::from_secs(now))
+}
+
+#[test]
+fn test_vectors() {
+    // HMAC-SHA256 of "METHOD\0URL\0BODY" keyed with the secret
+    let vectors = [
+        (
+            "GET",
+            "https://api.letterboxd.com/api/v0/film/2a9q?apikey=key&nonce=0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0&timestamp=1577836800",
+            "",
+            "4bb1e3e0dae47853ebedc4c980c9d567ac026795e6aa7836f1bcfee324eab33b",
+        ),
+        (
+            "POST",
+            "https://api.letterboxd.com/api/v0/auth/token?apikey=key&nonce=n1&timestamp=1577836800",
+            "grant_type=password&username=alice&password=p%40ss",
+            "1b8e1d442506575ec357e6df53e0195635a9785025aa807a722db082dec3aacd",
+        ),
+    ];
+    for &(method, url, body, expected) in &vectors {
+        let url = Url::parse(url).unwrap();
+        assert_eq!(
+            signing::signature(method, &url, body.as_bytes(), "secret"),
+            expected
+        );
+    }
+}
+
+#[test]
+fn sign_appends_params() {
+    let url = Url::parse("https://api.letterboxd.com/api/v0/film/2a9q").unwrap();
+    let signed = signer("0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0", TIMESTAMP).sign(
+        "GET",
+        url,
+        b"",
+        &key_pair(),
+    );
+    assert_eq!(
+        signed.as_str(),
+        "https://api.letterboxd.com/api/v0/film/2a9q?apikey=key&nonce=0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0&timestamp=1577836800\
+         &signature=4bb1e3e0dae47853ebedc4c980c9d567ac026795e6aa7836f1bcfee324eab33b"
+    );
+}
+
+#[test]
+fn verify() {
+    let body = b"{\"name\":\"list\"}";
+    let url = Url::parse("https://api.letterboxd.com/api/v0/lists?perPage=2").unwrap();
+    let signed = signer("n", TIMESTAMP).sign("POST", url, body, &key_pair());
+
+    let verifier = signer("unused", TIMESTAMP + 60);
+    assert!(verifier.verify("POST", &signed, body, &key_pair()).is_ok());
+
+    let reason = |res: letterboxd::Result<()>| res.unwrap_err().to_string();
+    assert_eq!(
+        reason(verifier.verify("PATCH", &signed, body, &key_pair())),
+        "invalid request signature: signature mismatch"
+    );
+    assert_eq!(
+        reason(verifier.verify("POST", &signed, b"{}", &key_pair())),
+        "invalid request signature: signature mismatch"
+    );
+    let other = ApiKeyPair::new("other".to_string(), "secret".to_string());
+    assert_eq!(
+        reason(verifier.verify("POST", &signed, body, &other)),
+        "invalid request signature: invalid api key"
+    );
+
+    let mut unsigned = signed.clone();
+    unsigned.set_query(Some("perPage=2&apikey=key"));
+    assert_eq!(
+        reason(verifier.verify("POST", &unsigned, body, &key_pair())),
+        "invalid request signature: missing signature"
+    );
+
+    let late = signer("unused", TIMESTAMP + 301);
+    assert_eq!(
+        reason(late.verify("POST", &signed, body, &key_pair())),
+        "invalid request signature: expired timestamp"
+    );
+    let lenient = late.with_max_age(Duration::from_secs(600));
+    assert!(lenient.verify("POST", &signed, body, &key_pair()).is_ok());
+
+    let early = signer("unused", TIMESTAMP - 301);
+    assert_eq!(
+        reason(early.verify("POST", &signed, body, &key_pair())),
+        "invalid request signature: future timestamp"
+    );
+    let skewed = signer("unused", TIMESTAMP - 300);
+    assert!(skewed.verify("POST", &signed, body, &key_pair()).is_ok());
+}
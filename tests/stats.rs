@@ -0,0 +1,121 @@
+mod common;
+
+use letterboxd::stats::{Streak, Tally, YearInReview};
+use letterboxd::{Film, LogEntry};
+
+use serde_json::json;
+
+fn film(id: &str, run_time: u16, genres: &[&str], director: &str, actors: &[&str]) -> Film {
+    let contributor = |name: &str| json!({ "id": name.to_lowercase(), "name": name });
+    let mut film = common::film_details(id, id, 2000, run_time);
+    film["genres"] = genres
+        .iter()
+        .map(|name| json!({ "id": name.to_lowercase(), "name": name }))
+        .collect();
+    film["contributions"] = json!([
+        { "type": "Director", "contributors": [contributor(director)] },
+        { "type": "Actor", "contributors": actors.iter().map(|name| contributor(name)).collect::<Vec<_>>() }
+    ]);
+    serde_json::from_value(film).unwrap()
+}
+
+fn entry(film: &str, date: Option<&str>, rating: Option<f32>, rewatch: bool) -> LogEntry {
+    let mut entry = common::log_entry("e", common::film(film, film, 2000), date, None);
+    entry["rating"] = json!(rating);
+    if date.is_some() {
+        entry["diaryDetails"]["rewatch"] = json!(rewatch);
+    }
+    serde_json::from_value(entry).unwrap()
+}
+
+#[test]
+fn year_in_review() {
+    let films = vec![
+        film(
+            "f1",
+            139,
+            &["Drama", "Thriller"],
+            "Fincher",
+            &["Pitt", "Norton"],
+        ),
+        film("f2", 132, &["Comedy", "Drama"], "Bong", &["Song"]),
+        film("f3", 161, &["Drama"], "Tarkovsky", &[]),
+    ];
+    let entries = vec![
+        entry("f1", Some("2019-01-30"), Some(4.5), false),
+        entry("f1", Some("2019-01-31"), Some(5.0), true),
+        entry("f2", Some("2019-02-01"), Some(4.5), false),
+        entry("f3", Some("2019-03-01"), None, false),
+        entry("f4", Some("2019-12-31"), Some(2.0), false),
+        // ignored: another year and no diary date
+        entry("f3", Some("2018-12-31"), Some(1.0), false),
+        entry("f2", None, Some(1.0), false),
+    ];
+    let review = YearInReview::new(2019, &entries, &films);
+
+    assert_eq!(review.entries, 5);
+    assert_eq!(review.films, 4);
+    assert_eq!(
+        review.entries_per_month,
+        [2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1]
+    );
+    assert_eq!(review.rewatches, 1);
+    assert_eq!(review.rewatch_ratio(), Some(0.2));
+    assert_eq!(review.run_time, 139 * 2 + 132 + 161);
+
+    assert_eq!(review.ratings.len(), 10);
+    assert_eq!(review.ratings[8].rating, 4.5);
+    assert_eq!(review.ratings[8].count, 2);
+    assert_eq!(review.ratings[8].normalized_weight, 1.0);
+    assert_eq!(review.ratings[3].normalized_weight, 0.5);
+    assert_eq!(review.average_rating(), Some(4.0));
+
+    let names = |tallies: &[Tally]| -> Vec<(String, usize)> {
+        tallies.iter().map(|t| (t.name.clone(), t.count)).collect()
+    };
+    assert_eq!(
+        names(&review.top_genres),
+        [
+            ("Drama".to_string(), 3),
+            ("Comedy".to_string(), 1),
+            ("Thriller".to_string(), 1)
+        ]
+    );
+    assert_eq!(review.top_directors.len(), 3);
+    assert_eq!(review.top_directors[0].id, "bong");
+    assert_eq!(names(&review.top_actors).len(), 3);
+
+    assert_eq!(
+        review.longest_streaks,
+        [
+            Streak {
+                start: "2019-01-30".to_string(),
+                end: "2019-02-01".to_string(),
+                days: 3
+            },
+            Streak {
+                start: "2019-03-01".to_string(),
+                end: "2019-03-01".to_string(),
+                days: 1
+            },
+            Streak {
+                start: "2019-12-31".to_string(),
+                end: "2019-12-31".to_string(),
+                days: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn empty_year() {
+    let review = YearInReview::new(2019, &[], &[]);
+    assert_eq!(review.entries, 0);
+    assert_eq!(review.rewatch_ratio(), None);
+    assert_eq!(review.average_rating(), None);
+    assert!(review
+        .ratings
+        .iter()
+        .all(|bar| bar.normalized_weight == 0.0));
+    assert!(review.longest_streaks.is_empty());
+}
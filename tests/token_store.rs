@@ -0,0 +1,73 @@
+use letterboxd::token_store::{EnvTokenStore, FileTokenStore, MemoryTokenStore, TokenStore};
+use letterboxd::{AccessToken, ApiKeyPair, Client};
+
+fn token(access_token: &str) -> AccessToken {
+    AccessToken {
+        access_token: access_token.to_string(),
+        token_type: "bearer".to_string(),
+        refresh_token: "refresh".to_string(),
+        expires_in: 3600,
+    }
+}
+
+fn api_key_pair() -> ApiKeyPair {
+    ApiKeyPair::new("key".to_string(), "secret".to_string())
+}
+
+#[test]
+fn file_store() -> letterboxd::Result<()> {
+    let dir = std::env::temp_dir().join(format!("letterboxd-token-{}", std::process::id()));
+    let store = FileTokenStore::new(dir.join("token.json"));
+    assert!(store.load()?.is_none());
+
+    store.save(&token("a"))?;
+    store.save(&token("b"))?;
+    assert_eq!(store.load()?.map(|t| t.access_token).as_deref(), Some("b"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(store.path())?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    store.clear()?;
+    store.clear()?;
+    assert!(store.load()?.is_none());
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn env_store() -> letterboxd::Result<()> {
+    let store = EnvTokenStore::new("LETTERBOXD_TEST_TOKEN");
+    assert!(store.load()?.is_none());
+
+    std::env::set_var("LETTERBOXD_TEST_TOKEN", "plain");
+    assert_eq!(
+        store.load()?.map(|t| t.access_token).as_deref(),
+        Some("plain")
+    );
+
+    std::env::set_var(
+        "LETTERBOXD_TEST_TOKEN",
+        serde_json::to_string(&token("json"))?,
+    );
+    let loaded = store.load()?.expect("token");
+    assert_eq!(loaded.access_token, "json");
+    assert_eq!(loaded.refresh_token, "refresh");
+    std::env::remove_var("LETTERBOXD_TEST_TOKEN");
+    Ok(())
+}
+
+#[test]
+fn client_from_store() -> letterboxd::Result<()> {
+    let client = Client::from_store(api_key_pair(), MemoryTokenStore::default())?;
+    assert!(!client.is_authenticated());
+
+    let mut client = Client::from_store(api_key_pair(), MemoryTokenStore::new(Some(token("a"))))?;
+    assert_eq!(client.token().map(|t| t.access_token.as_str()), Some("a"));
+
+    client.logout()?;
+    assert!(!client.is_authenticated());
+    Ok(())
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "tracing")]
+
+use letterboxd::middleware::{BoxFuture, Middleware, Next, Request, Response, StatusCode};
+use letterboxd::{ApiKeyPair, Client};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tracing_core::span::Current;
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Answers all requests after signing.
+#[derive(Debug)]
+struct Stub;
+
+impl Middleware for Stub {
+    fn after_sign(&self, _request: &mut Request) -> Option<Response> {
+        Some(Response::ok(br#"{"items":[]}"#.to_vec()))
+    }
+}
+
+/// Fails the first request with a server error.
+#[derive(Debug, Default)]
+struct Unavailable {
+    failed: AtomicUsize,
+}
+
+impl Middleware for Unavailable {
+    fn after_sign(&self, _request: &mut Request) -> Option<Response> {
+        if self.failed.fetch_add(1, Ordering::SeqCst) > 0 {
+            return None;
+        }
+        Some(Response {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            ..Response::ok(Vec::new())
+        })
+    }
+}
+
+/// Retries requests which failed with a server error.
+#[derive(Debug)]
+struct Retry;
+
+impl Middleware for Retry {
+    fn handle<'a>(
+        &'a self,
+        request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, letterboxd::Result<Response>> {
+        Box::pin(async move {
+            let response = next.run(request.clone()).await?;
+            if response.status.is_server_error() {
+                return next.run(request).await;
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Records the fields of the request spans and the urls recorded in them.
+#[derive(Debug, Default, Clone)]
+struct Recorder {
+    /// Metadata of the created spans, whose IDs are their indices plus one.
+    spans: Arc<Mutex<Vec<&'static Metadata<'static>>>>,
+    /// IDs of the entered spans.
+    entered: Arc<Mutex<Vec<u64>>>,
+    fields: Arc<Mutex<Vec<String>>>,
+    urls: Arc<Mutex<Vec<String>>>,
+    retries: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Visit for Recorder {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "url" {
+            self.urls.lock().unwrap().push(value.to_string());
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "retries" {
+            self.retries.lock().unwrap().push(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+        if span.metadata().name() == "letterboxd_request" {
+            let mut fields = self.fields.lock().unwrap();
+            fields.extend(
+                span.metadata()
+                    .fields()
+                    .iter()
+                    .map(|f| f.name().to_string()),
+            );
+        }
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(span.metadata());
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, values: &Record) {
+        values.record(&mut self.clone());
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event) {}
+
+    fn enter(&self, span: &Id) {
+        self.entered.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+
+    fn current_span(&self) -> Current {
+        match self.entered.lock().unwrap().last() {
+            Some(&id) => {
+                let metadata = self.spans.lock().unwrap()[id as usize - 1];
+                Current::new(Id::from_u64(id), metadata)
+            }
+            None => Current::none(),
+        }
+    }
+}
+
+#[test]
+fn request_span_redacts_credentials() -> letterboxd::Result<()> {
+    let mut client = Client::new(ApiKeyPair::new("key".to_string(), "secret".to_string()));
+    client.add_middleware(Stub);
+    let recorder = Recorder::default();
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    tracing::subscriber::with_default(recorder.clone(), || rt.block_on(client.film_genres()))?;
+
+    let fields = recorder.fields.lock().unwrap();
+    assert_eq!(
+        *fields,
+        vec![
+            "method",
+            "endpoint_path",
+            "url",
+            "status",
+            "latency_ms",
+            "response_size",
+            "retries"
+        ]
+    );
+    let urls = recorder.urls.lock().unwrap();
+    assert_eq!(urls.len(), 1);
+    let url = url::Url::parse(&urls[0]).unwrap();
+    let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    assert_eq!(params[0], ("apikey".to_string(), "[redacted]".to_string()));
+    assert_eq!(params[1].0, "nonce");
+    assert_ne!(params[1].1, "[redacted]");
+    assert_eq!(params[2].0, "timestamp");
+    assert_eq!(
+        params[3],
+        ("signature".to_string(), "[redacted]".to_string())
+    );
+    assert_eq!(*recorder.retries.lock().unwrap(), vec![0]);
+    Ok(())
+}
+
+#[test]
+fn request_span_counts_retries() -> letterboxd::Result<()> {
+    let mut client = Client::new(ApiKeyPair::new("key".to_string(), "secret".to_string()));
+    client.add_middleware(Retry);
+    client.add_middleware(Unavailable::default());
+    client.add_middleware(Stub);
+    let recorder = Recorder::default();
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    tracing::subscriber::with_default(recorder.clone(), || rt.block_on(client.film_genres()))?;
+
+    assert_eq!(*recorder.retries.lock().unwrap(), vec![1]);
+    assert_eq!(recorder.urls.lock().unwrap().len(), 2);
+    Ok(())
+}